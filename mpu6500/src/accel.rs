@@ -71,6 +71,18 @@ pub enum AccelRange {
     G16 = 0b11,
 }
 
+impl AccelRange {
+    /// Sensitivity scale factor, LSB/g.
+    pub fn sensitivity(&self) -> f32 {
+        match self {
+            AccelRange::G2 => 16384.0,
+            AccelRange::G4 => 8192.0,
+            AccelRange::G8 => 4096.0,
+            AccelRange::G16 => 2048.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum AccelDLPFOptions {
@@ -127,6 +127,21 @@ impl FIFOLayout {
 
         Self { fields, sample_size: offset }
     }
+
+    /// FIFO_EN register value that produces this layout.
+    pub fn fifo_register(&self) -> u8 {
+        self.fields.iter().fold(0, |bits, field| {
+            let sensor = match field.entry_type {
+                FIFOEntryType::AccelX | FIFOEntryType::AccelY | FIFOEntryType::AccelZ => FIFOSensors::ACCEL,
+                FIFOEntryType::Temp => FIFOSensors::TEMP,
+                FIFOEntryType::GyroX => FIFOSensors::GYRO_X,
+                FIFOEntryType::GyroY => FIFOSensors::GYRO_Y,
+                FIFOEntryType::GyroZ => FIFOSensors::GYRO_Z,
+            };
+
+            bits | sensor.bits()
+        })
+    }
 }
 
 pub struct FIFOSample<'a> {
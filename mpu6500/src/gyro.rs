@@ -54,3 +54,15 @@ pub enum GyroRange {
     R1000dps = 0b10,
     R2000dps = 0b11,
 }
+
+impl GyroRange {
+    /// Sensitivity scale factor, LSB/(°/s).
+    pub fn sensitivity(&self) -> f32 {
+        match self {
+            GyroRange::R250dps => 131.0,
+            GyroRange::R500dps => 65.5,
+            GyroRange::R1000dps => 32.8,
+            GyroRange::R2000dps => 16.4,
+        }
+    }
+}
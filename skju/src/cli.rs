@@ -0,0 +1,174 @@
+use crate::sink::parse_server_id;
+use crate::source::{parse_sensor_source, parse_source};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use skju_core::SourceConfig;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "skju", version, about = "Processes seismic sensor data")]
+pub struct Cli {
+    /// Sensors file, the first of `data/sensors.{toml,json,yaml,yml,txt}` found is used when unset.
+    #[arg(short, long, global = true)]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Processes live samples from the source of every sensor.
+    Run(RunArgs),
+    /// Processes recorded sensor files from the start, paced by their timestamps.
+    Replay(ReplayArgs),
+    /// Runs the detectors over recorded sensor files and prints the events only.
+    Detect(DetectArgs),
+    /// Exports the recording of a sensor as miniSEED or CSV.
+    Export(ExportArgs),
+    /// Lists or adds the sensors of the sensors file.
+    #[command(subcommand)]
+    Sensors(SensorsCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Overrides the source of a sensor, e.g. `1=udp:0.0.0.0:9000`.
+    #[arg(long = "source", value_name = "ID=SOURCE", value_parser = parse_sensor_source)]
+    pub sources: Vec<(u64, SourceConfig)>,
+    /// Where reading the followed files starts.
+    #[arg(long, value_enum, default_value_t = StartFrom::End)]
+    pub start: StartFrom,
+    /// Read offsets of the followed files, kept up to date while running.
+    #[arg(long, default_value = "data/offsets.json")]
+    pub offsets: PathBuf,
+    #[command(flatten)]
+    pub detection: DetectionArgs,
+    #[command(flatten)]
+    pub forward: ForwardArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum StartFrom {
+    /// Only samples written after the start are processed.
+    End,
+    Beginning,
+    /// Resumes from the saved offsets, files without one are read from the beginning.
+    Saved,
+}
+
+#[derive(Debug, Args)]
+pub struct RecordingArgs {
+    /// Directory holding the `sensor_{id}.txt` recordings.
+    #[arg(long, default_value = "data")]
+    pub dir: PathBuf,
+}
+
+impl RecordingArgs {
+    pub fn path(&self, sensor_id: u64) -> PathBuf {
+        self.dir.join(format!("sensor_{sensor_id}.txt"))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    #[command(flatten)]
+    pub recording: RecordingArgs,
+    /// Playback speed relative to the recording.
+    #[arg(long, default_value_t = 1.0)]
+    pub speed: f64,
+    #[command(flatten)]
+    pub detection: DetectionArgs,
+    #[command(flatten)]
+    pub forward: ForwardArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct DetectArgs {
+    #[command(flatten)]
+    pub recording: RecordingArgs,
+    #[command(flatten)]
+    pub detection: DetectionArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct DetectionArgs {
+    /// Sensors that have to trigger together for a network event, at least 2.
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(2..))]
+    pub min_sensors: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct ForwardArgs {
+    /// Forwards the filtered samples to skju_server at this base URL, e.g. `http://localhost:3000`.
+    #[arg(long, value_name = "URL")]
+    pub server: Option<String>,
+    /// Server id of a sensor, e.g. `1=12`, overrides `server_id` of the sensors file.
+    #[arg(long = "server-id", value_name = "ID=SERVER_ID", value_parser = parse_server_id)]
+    pub server_ids: Vec<(u64, i32)>,
+    /// Readings per request.
+    #[arg(long, default_value_t = 500)]
+    pub batch_size: usize,
+    /// Keeps the readings while the server is unreachable.
+    #[arg(long, default_value = "data/spool.jsonl")]
+    pub spool: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(long)]
+    pub sensor: u64,
+    #[command(flatten)]
+    pub recording: RecordingArgs,
+    #[arg(long, value_enum, default_value_t = ExportFormat::Mseed)]
+    pub format: ExportFormat,
+    /// miniSEED data encoding: int32, float32, float64, steim1 or steim2.
+    #[arg(long, default_value = "float64")]
+    pub encoding: String,
+    /// Multiplier applied before rounding the values for the integer encodings.
+    #[arg(long, default_value_t = 1.0)]
+    pub scale: f64,
+    /// Passes the samples through the filter chain of the sensor first.
+    #[arg(long)]
+    pub filtered: bool,
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Mseed,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SensorsCommand {
+    List,
+    Add(SensorAddArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SensorAddArgs {
+    #[arg(long)]
+    pub id: u64,
+    #[arg(long)]
+    pub name: String,
+    /// East coordinate in km.
+    #[arg(long, allow_negative_numbers = true, required_unless_present = "latitude")]
+    pub x: Option<f32>,
+    /// North coordinate in km.
+    #[arg(long, allow_negative_numbers = true, required_unless_present = "latitude")]
+    pub y: Option<f32>,
+    /// Latitude in degrees, replaces the coordinates by the position in the plane of the sensors file.
+    #[arg(long, allow_negative_numbers = true, requires = "longitude", conflicts_with_all = ["x", "y"])]
+    pub latitude: Option<f64>,
+    /// Longitude in degrees.
+    #[arg(long, allow_negative_numbers = true, requires = "latitude")]
+    pub longitude: Option<f64>,
+    /// Nominal sample rate in Hz.
+    #[arg(long)]
+    pub sample_rate: Option<f64>,
+    #[arg(long)]
+    pub network: Option<String>,
+    /// `file:<path>`, `stdin`, `udp:<bind address>`, `tcp:<address>` or a `ws://` URL.
+    #[arg(long, value_parser = parse_source)]
+    pub source: Option<SourceConfig>,
+}
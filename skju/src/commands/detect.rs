@@ -0,0 +1,58 @@
+use crate::cli::DetectArgs;
+use crate::config::{FilteredSensor, create_sensor_from_config, load_sensors_file};
+use crate::detector::Detector;
+use crate::source::{FileSource, Next, SampleSource};
+use skju_core::SensorData;
+use std::path::Path;
+
+pub fn detect(config: Option<&Path>, args: DetectArgs) -> anyhow::Result<()> {
+    let sensors_file = load_sensors_file(config)?;
+    let mut sensors: Vec<FilteredSensor> = Vec::with_capacity(sensors_file.sensors.len());
+    let mut samples: Vec<(usize, SensorData)> = Vec::new();
+
+    if sensors_file.sensors.is_empty() {
+        println!("There are no sensors to process");
+        return Ok(());
+    }
+
+    for (index, sensor_config) in sensors_file.sensors.iter().enumerate() {
+        sensors.push(create_sensor_from_config(sensor_config)?);
+        samples.extend(
+            read_recording(&args.recording.path(sensor_config.id))?
+                .into_iter()
+                .map(|data| (index, data)),
+        );
+    }
+
+    // sensors are fed in time order so that the coincidence trigger sees the network as it happened
+    samples.sort_by_key(|(_, data)| data.timestamp);
+
+    let mut detector = Detector::new(&sensors.iter().collect::<Vec<_>>(), args.detection.min_sensors as usize)?;
+
+    for (index, data) in samples {
+        let sensor = &mut sensors[index];
+
+        if let Some(event) = sensor.write(data.value, data.timestamp) {
+            println!("[{}]: {}", sensor.name, event);
+        }
+
+        detector.process(index, sensor)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn read_recording(path: &Path) -> anyhow::Result<Vec<SensorData>> {
+    let mut source = FileSource::recording(path)?;
+    let mut samples = Vec::new();
+
+    loop {
+        match source.next()? {
+            Next::Sample(data) => samples.push(data),
+            Next::Idle => continue,
+            Next::End => break,
+        }
+    }
+
+    Ok(samples)
+}
@@ -0,0 +1,100 @@
+use super::read_recording;
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::config::{create_sensor_from_config, load_sensors_file};
+use anyhow::{anyhow, bail};
+use skju_core::mseed::{self, Encoding, MseedEncoder, StreamId};
+use std::fmt::Write as _;
+use std::fs::write;
+use std::path::Path;
+
+/// Network code of streams whose sensor does not belong to a network.
+const DEFAULT_NETWORK: &str = "SK";
+
+pub fn export(config: Option<&Path>, args: ExportArgs) -> anyhow::Result<()> {
+    let sensors_file = load_sensors_file(config)?;
+    let sensor_config = sensors_file
+        .sensors
+        .iter()
+        .find(|sensor| sensor.id == args.sensor)
+        .ok_or_else(|| anyhow!("Unknown sensor {}", args.sensor))?;
+    let mut samples = read_recording(&args.recording.path(args.sensor))?;
+
+    if samples.is_empty() {
+        bail!("Sensor {} has no recorded samples", args.sensor);
+    }
+
+    if args.filtered {
+        let mut sensor = create_sensor_from_config(sensor_config)?;
+        let mut filtered = Vec::with_capacity(samples.len());
+
+        for data in samples {
+            sensor.write(data.value, data.timestamp);
+            filtered.extend(std::iter::from_fn(|| sensor.read()));
+        }
+
+        samples = filtered;
+    }
+
+    let output = match args.format {
+        ExportFormat::Csv => {
+            let mut csv = String::from("timestamp,e,n,z\n");
+
+            for data in &samples {
+                writeln!(
+                    csv,
+                    "{},{},{},{}",
+                    data.timestamp, data.value.e, data.value.n, data.value.z
+                )?;
+            }
+
+            csv.into_bytes()
+        }
+        ExportFormat::Mseed => {
+            let encoding = parse_encoding(&args.encoding)?;
+            let network = sensor_config.network.as_deref().unwrap_or(DEFAULT_NETWORK);
+            let mut records = Vec::new();
+
+            for channel in &sensor_config.channels {
+                let series: Vec<(u128, f64)> = samples
+                    .iter()
+                    .map(|data| (data.timestamp, data.value.get(*channel) * args.scale))
+                    .collect();
+                let to_export_error = |e: mseed::MseedError| anyhow!("{}: {}", channel, e);
+                let sample_rate = sensor_config
+                    .sample_rate
+                    .or_else(|| mseed::sample_rate(&series))
+                    .ok_or(to_export_error(mseed::MseedError::UnknownSampleRate))?;
+                let stream =
+                    StreamId::for_sensor(network, args.sensor, *channel, sample_rate).map_err(to_export_error)?;
+                let mut encoder = MseedEncoder::new(stream, encoding);
+
+                records.extend(encoder.encode_series(&series).map_err(to_export_error)?);
+            }
+
+            records
+        }
+    };
+
+    write(&args.output, output)?;
+    println!(
+        "Exported {} samples of sensor {} to {}",
+        samples.len(),
+        args.sensor,
+        args.output.display()
+    );
+
+    Ok(())
+}
+
+fn parse_encoding(encoding: &str) -> anyhow::Result<Encoding> {
+    let encoding = match encoding {
+        "int32" => Encoding::Int32,
+        "float32" => Encoding::Float32,
+        "float64" => Encoding::Float64,
+        "steim1" => Encoding::Steim1,
+        "steim2" => Encoding::Steim2,
+        _ => bail!("Unknown encoding {encoding}"),
+    };
+
+    Ok(encoding)
+}
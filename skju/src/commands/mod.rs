@@ -0,0 +1,8 @@
+mod detect;
+mod export;
+mod run;
+pub mod sensors;
+
+pub use detect::*;
+pub use export::*;
+pub use run::*;
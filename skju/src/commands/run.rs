@@ -0,0 +1,328 @@
+use crate::cli::{ForwardArgs, ReplayArgs, RunArgs, StartFrom};
+use crate::config::{FilteredSensor, create_sensor_from_config, load_sensors_file};
+use crate::detector::Detector;
+use crate::offsets::Offsets;
+use crate::sink::{ForwardedSensor, HttpSink, HttpSinkConfig};
+use crate::source::{FileSource, Next, SampleSource, StartPosition, default_source, open_source};
+use anyhow::{anyhow, bail};
+use skju_core::{SensorConfig, SensorData, SensorOutput, SourceConfig};
+use std::collections::HashMap;
+use std::iter::once;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+struct Pacing {
+    speed: f64,
+    /// Start of the replay, the first replayed timestamp is played at the given instant.
+    clock: OnceLock<(u128, Instant)>,
+}
+
+pub fn run(config: Option<&Path>, args: RunArgs) -> anyhow::Result<()> {
+    let sensors_file = load_sensors_file(config)?;
+    let mut inputs = Vec::with_capacity(sensors_file.sensors.len());
+
+    if let Some((id, _)) = args
+        .sources
+        .iter()
+        .find(|(id, _)| !sensors_file.sensors.iter().any(|sensor| sensor.id == *id))
+    {
+        bail!("Unknown sensor {id}");
+    }
+
+    for sensor_config in &sensors_file.sensors {
+        let source = args
+            .sources
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == sensor_config.id)
+            .map(|(_, source)| source)
+            .or(sensor_config.source.as_ref())
+            .cloned()
+            .unwrap_or_else(default_source);
+
+        inputs.push((sensor_config, source));
+    }
+
+    if inputs
+        .iter()
+        .filter(|(_, source)| *source == SourceConfig::Stdin)
+        .count()
+        > 1
+    {
+        bail!("Only a single sensor can read from stdin");
+    }
+
+    let offsets = Offsets::load(&args.offsets)?;
+    let inputs = inputs
+        .into_iter()
+        .map(|(sensor_config, source)| {
+            let start = match args.start {
+                StartFrom::End => StartPosition::End,
+                StartFrom::Beginning => StartPosition::Beginning,
+                StartFrom::Saved => offsets
+                    .get(sensor_config.id)
+                    .map_or(StartPosition::Beginning, StartPosition::Offset),
+            };
+            let sensor = create_sensor_from_config(sensor_config)?;
+            let source = open_source(sensor_config, &source, start)
+                .map_err(|e| anyhow!("sensor {}: {}", sensor_config.id, e))?;
+
+            Ok((sensor, source))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let sink = open_sink(&args.forward, &sensors_file.sensors, &inputs)?;
+
+    process(inputs, args.detection.min_sensors as usize, None, Some(offsets), sink)
+}
+
+pub fn replay(config: Option<&Path>, args: ReplayArgs) -> anyhow::Result<()> {
+    if !args.speed.is_finite() || args.speed <= 0.0 {
+        bail!("Replay speed must be positive, use detect to process recordings as fast as possible");
+    }
+
+    let sensors_file = load_sensors_file(config)?;
+    let inputs = sensors_file
+        .sensors
+        .iter()
+        .map(|sensor_config| {
+            let sensor = create_sensor_from_config(sensor_config)?;
+            let source: Box<dyn SampleSource> = Box::new(FileSource::recording(args.recording.path(sensor_config.id))?);
+
+            Ok((sensor, source))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let sink = open_sink(&args.forward, &sensors_file.sensors, &inputs)?;
+    let pacing = Pacing {
+        speed: args.speed,
+        clock: OnceLock::new(),
+    };
+
+    process(inputs, args.detection.min_sensors as usize, Some(pacing), None, sink)
+}
+
+/// Sink forwarding to the server when one is given, sensors are forwarded under their server ids.
+fn open_sink(
+    args: &ForwardArgs,
+    sensor_configs: &[SensorConfig],
+    inputs: &[(FilteredSensor, Box<dyn SampleSource>)],
+) -> anyhow::Result<Option<HttpSink>> {
+    let Some(url) = &args.server else {
+        return Ok(None);
+    };
+
+    if let Some((id, _)) = args
+        .server_ids
+        .iter()
+        .find(|(id, _)| !sensor_configs.iter().any(|sensor| sensor.id == *id))
+    {
+        bail!("Unknown sensor {id}");
+    }
+
+    let mut sensors = HashMap::with_capacity(sensor_configs.len());
+
+    for (sensor_config, (sensor, _)) in sensor_configs.iter().zip(inputs) {
+        let server_id = args
+            .server_ids
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == sensor_config.id)
+            .map(|(_, server_id)| *server_id)
+            .or(sensor_config.server_id);
+        let server_id = match server_id {
+            Some(server_id) => server_id,
+            None => {
+                i32::try_from(sensor_config.id).map_err(|_| anyhow!("Sensor {} needs a server id", sensor_config.id))?
+            }
+        };
+
+        sensors.insert(
+            sensor_config.id,
+            ForwardedSensor {
+                server_id,
+                channels: sensor_config.channels.clone(),
+                unit: sensor.unit(),
+            },
+        );
+    }
+
+    let config = HttpSinkConfig {
+        url: url.clone(),
+        batch_size: args.batch_size,
+        spool: args.spool.clone(),
+    };
+
+    Ok(Some(HttpSink::start(config, sensors)?))
+}
+
+/// A sample read by the reader thread of the sensor at `index`.
+struct Reading {
+    index: usize,
+    data: SensorData,
+    position: Option<u64>,
+}
+
+/// Reads every source on its own thread and prints the latest samples and the detections as readings
+/// arrive, until stopped or until every source is exhausted.
+fn process(
+    inputs: Vec<(FilteredSensor, Box<dyn SampleSource>)>,
+    min_sensors: usize,
+    pacing: Option<Pacing>,
+    offsets: Option<Offsets>,
+    sink: Option<HttpSink>,
+) -> anyhow::Result<()> {
+    if inputs.is_empty() {
+        println!("There are no sensors to process");
+        return Ok(());
+    }
+
+    let (sensors, sources): (Vec<_>, Vec<_>) = inputs.into_iter().unzip();
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = channel();
+
+    std::thread::scope(|scope| {
+        for (index, source) in sources.into_iter().enumerate() {
+            let sender = sender.clone();
+            let stop = &stop;
+            let pacing = pacing.as_ref();
+
+            scope.spawn(move || {
+                if let Err(e) = read_sensor_data(index, source, pacing, stop, sender) {
+                    eprintln!("{:?}", e);
+                    stop.store(true, Relaxed);
+                }
+            });
+        }
+
+        // the channel disconnects once every reader has finished
+        drop(sender);
+
+        let result = process_sensor_data(sensors, min_sensors, receiver, offsets, sink);
+
+        stop.store(true, Relaxed);
+        result
+    })
+}
+
+fn read_sensor_data(
+    index: usize,
+    mut source: Box<dyn SampleSource>,
+    pacing: Option<&Pacing>,
+    stop: &AtomicBool,
+    sender: Sender<Reading>,
+) -> anyhow::Result<()> {
+    while !stop.load(Relaxed) {
+        let data = match source.next()? {
+            Next::Sample(data) => data,
+            Next::Idle => continue,
+            Next::End => break,
+        };
+
+        if let Some(pacing) = pacing {
+            let (start, started_at) = *pacing
+                .clock
+                .get_or_init(|| (data.timestamp, Instant::now()));
+            let offset = data.timestamp.saturating_sub(start) as f64 / pacing.speed;
+            let due = started_at + Duration::from_secs_f64(offset / 1000.0);
+
+            sleep(due.saturating_duration_since(Instant::now()));
+        }
+
+        let reading = Reading { index, data, position: source.position() };
+
+        if sender.send(reading).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn process_sensor_data(
+    mut sensors: Vec<FilteredSensor>,
+    min_sensors: usize,
+    receiver: Receiver<Reading>,
+    mut offsets: Option<Offsets>,
+    sink: Option<HttpSink>,
+) -> anyhow::Result<()> {
+    let mut detector = Detector::new(&sensors.iter().collect::<Vec<_>>(), min_sensors)?;
+    let mut forwarded = vec![0; sensors.len()];
+
+    while let Ok(reading) = receiver.recv() {
+        // everything that arrived meanwhile is printed once, each reading is detected and forwarded before
+        // the following ones can evict it from the sensor
+        for Reading { index, data, position } in once(reading).chain(receiver.try_iter()) {
+            let sensor = &mut sensors[index];
+
+            if let Some(event) = sensor.write(data.value, data.timestamp) {
+                println!("[{}]: {}", sensor.name, event);
+            }
+
+            detector.process(index, sensor)?;
+
+            if let Some(sink) = &sink {
+                forward(sink, sensor, &mut forwarded[index]);
+            }
+
+            // a forwarded reading only counts as read once the sink has delivered or spooled it
+            if let (Some(offsets), Some(position)) = (offsets.as_mut(), position) {
+                match &sink {
+                    Some(sink) => sink.mark(sensor.id, position),
+                    None => offsets.set(sensor.id, position),
+                }
+            }
+        }
+
+        if let (Some(offsets), Some(sink)) = (offsets.as_mut(), &sink) {
+            sink.acknowledged()
+                .for_each(|(sensor_id, position)| offsets.set(sensor_id, position));
+        }
+
+        let result: Option<Vec<SensorOutput>> = sensors.iter().map(|sensor| sensor.get_latest()).collect();
+
+        if let Some(data) = result {
+            data.iter()
+                .for_each(|s| println!("[{}]: {} at {}", s.sensor_name, s.value, s.timestamp));
+        }
+
+        if let Some(offsets) = offsets.as_mut() {
+            offsets.save_if_due()?;
+        }
+    }
+
+    let acknowledged = sink.map(HttpSink::close).unwrap_or_default();
+
+    if let Some(offsets) = offsets.as_mut() {
+        acknowledged
+            .into_iter()
+            .for_each(|(sensor_id, position)| offsets.set(sensor_id, position));
+        offsets.save()?;
+    }
+
+    Ok(())
+}
+
+/// Sends the readings stored since the previous call, `forwarded` counts the readings stored until then.
+fn forward(sink: &HttpSink, sensor: &FilteredSensor, forwarded: &mut u64) {
+    let unsent = sensor.stored() - *forwarded;
+    let snapshot = sensor.snapshot();
+    let available = unsent.min(snapshot.len() as u64) as usize;
+
+    if unsent > available as u64 {
+        eprintln!(
+            "[{}]: {} readings evicted before they were forwarded",
+            sensor.name,
+            unsent - available as u64
+        );
+    }
+
+    for data in snapshot.iter().skip(snapshot.len() - available) {
+        sink.send(&sensor.output(data));
+    }
+
+    *forwarded = sensor.stored();
+}
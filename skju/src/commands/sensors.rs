@@ -0,0 +1,78 @@
+use crate::cli::SensorAddArgs;
+use crate::config::{load_sensors_file, sensors_path};
+use crate::source::default_source;
+use skju_core::geo::GeoCoord;
+use skju_core::utils::{ConfigFormat, NetworkConfig, write_sensors_file};
+use skju_core::{Coord, SensorConfig};
+use std::path::{Path, PathBuf};
+
+/// Sensors file created by `sensors add` when none exists yet.
+const NEW_SENSORS_FILE: &str = "data/sensors.toml";
+
+pub fn list(config: Option<&Path>) -> anyhow::Result<()> {
+    let sensors_file = load_sensors_file(config)?;
+
+    if sensors_file.sensors.is_empty() {
+        println!("There are no sensors configured");
+        return Ok(());
+    }
+
+    for sensor in &sensors_file.sensors {
+        let source = sensor.source.clone().unwrap_or_else(default_source);
+        let network = sensor.network.as_deref().unwrap_or("-");
+        let sample_rate = sensor
+            .sample_rate
+            .map_or("-".to_string(), |sample_rate| format!("{sample_rate} Hz"));
+
+        println!(
+            "{}\t{}\t({}, {})\t{}\t{}\t{:?}",
+            sensor.id, sensor.name, sensor.coord.x, sensor.coord.y, network, sample_rate, source
+        );
+    }
+
+    Ok(())
+}
+
+pub fn add(config: Option<&Path>, args: SensorAddArgs) -> anyhow::Result<()> {
+    let mut sensors_file = load_sensors_file(config)?;
+    let mut path = sensors_path(config);
+
+    // the legacy text format cannot hold the new fields, its sensors are migrated to a versioned file
+    if config.is_none() && (!path.exists() || ConfigFormat::from_path(&path) == ConfigFormat::Legacy) {
+        path = PathBuf::from(NEW_SENSORS_FILE);
+    }
+
+    let coord = Coord {
+        x: args.x.unwrap_or_default(),
+        y: args.y.unwrap_or_default(),
+    };
+    let mut sensor = SensorConfig::new(args.id, &args.name, coord);
+
+    if let (Some(latitude), Some(longitude)) = (args.latitude, args.longitude) {
+        sensor.location = Some(GeoCoord::new(latitude, longitude, 0.0));
+    }
+
+    sensor.sample_rate = args.sample_rate;
+    sensor.source = args.source;
+
+    if let Some(network) = &args.network
+        && !sensors_file
+            .networks
+            .iter()
+            .any(|known| &known.code == network)
+    {
+        sensors_file
+            .networks
+            .push(NetworkConfig { code: network.clone(), description: None });
+    }
+
+    sensor.network = args.network;
+    sensors_file.sensors.push(sensor);
+    sensors_file.project_locations();
+    sensors_file.validate()?;
+
+    write_sensors_file(&path, &sensors_file)?;
+    println!("Added sensor {} to {}", args.id, path.display());
+
+    Ok(())
+}
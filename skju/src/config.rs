@@ -0,0 +1,88 @@
+use anyhow::anyhow;
+use skju_core::SensorConfig;
+use skju_core::filter::{FilterChain, HampelFilter, MultiPoleExponentialLowPass};
+use skju_core::sensor::{SampleTiming, Sensor, SensorBuilder};
+use skju_core::utils::{SensorsFile, read_sensors_file};
+use std::path::{Path, PathBuf};
+
+pub type FilteredSensor = Sensor<FilterChain>;
+
+const SENSORS_FILES: [&str; 4] = [
+    "data/sensors.toml",
+    "data/sensors.json",
+    "data/sensors.yaml",
+    "data/sensors.yml",
+];
+const LEGACY_SENSORS_FILE: &str = "data/sensors.txt";
+
+/// The given sensors file, otherwise the first existing default one. Versioned files describe a processing
+/// pipeline per sensor, the text file is the legacy format.
+pub fn sensors_path(config: Option<&Path>) -> PathBuf {
+    if let Some(config) = config {
+        return config.to_path_buf();
+    }
+
+    let path = SENSORS_FILES
+        .into_iter()
+        .find(|path| Path::new(path).exists())
+        .unwrap_or(LEGACY_SENSORS_FILE);
+
+    PathBuf::from(path)
+}
+
+/// Reads the sensors file, a missing default file means that no sensors are configured yet.
+pub fn load_sensors_file(config: Option<&Path>) -> anyhow::Result<SensorsFile> {
+    let path = sensors_path(config);
+
+    if config.is_none() && !path.exists() {
+        return Ok(SensorsFile::new(Vec::new()));
+    }
+
+    read_sensors_file(&path).map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+pub fn create_sensor_from_config(sensor_config: &SensorConfig) -> anyhow::Result<FilteredSensor> {
+    let capacity = sensor_config.capacity.unwrap_or(100);
+    let filter = if sensor_config.filters.is_empty() {
+        default_filter_chain()
+    } else {
+        FilterChain::from_specs(&sensor_config.filters, sensor_config.sample_rate)
+            .map_err(|e| anyhow!("sensor {}: {}", sensor_config.id, e))?
+    };
+
+    let mut builder = SensorBuilder::new(sensor_config.id, &sensor_config.name)
+        .coord(sensor_config.coord)
+        .filter(filter)
+        .with_capacity(capacity);
+
+    if let Some(response) = sensor_config.response.clone() {
+        builder = builder.response(response);
+    }
+
+    if let Some(orientation) = &sensor_config.orientation {
+        let rotation = orientation
+            .rotation()
+            .map_err(|e| anyhow!("sensor {}: {}", sensor_config.id, e))?;
+
+        builder = builder.rotation(rotation);
+    }
+
+    let sensor = match sensor_config.sample_rate {
+        Some(sample_rate) => builder.timing(SampleTiming::new(sample_rate)).build(),
+        None => builder.build(),
+    };
+
+    Ok(sensor)
+}
+
+fn default_filter_chain() -> FilterChain {
+    let smoothing = 0.1;
+    let number_of_stages = 3;
+    // emulated glitches last up to 10 samples, the window has to be more than twice as long
+    let glitch_window = 21;
+    let glitch_threshold = 3.0;
+
+    FilterChain::new()
+        .then(HampelFilter::new(glitch_window, glitch_threshold))
+        .then(MultiPoleExponentialLowPass::new(number_of_stages, smoothing))
+}
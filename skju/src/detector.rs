@@ -0,0 +1,157 @@
+use crate::config::FilteredSensor;
+use anyhow::{anyhow, bail};
+use skju_core::trigger::{CoincidenceConfig, CoincidenceTrigger, NetworkEvent, RecursiveStaLta, StaLtaConfig, Trigger};
+
+/// STA/LTA trigger per sensor feeding a network coincidence trigger, fed with the readings stored since
+/// the previous call. The network trigger is disabled when fewer sensors are configured than it requires.
+pub struct Detector {
+    coincidence: Option<CoincidenceTrigger>,
+    triggers: Vec<RecursiveStaLta>,
+    last_processed: Vec<Option<u128>>,
+}
+
+impl Detector {
+    pub fn new(sensors: &[&FilteredSensor], min_sensors: usize) -> anyhow::Result<Detector> {
+        // a door slam next to one node must not be reported as an earthquake
+        if min_sensors < 2 {
+            bail!("A network event requires at least 2 sensors");
+        }
+
+        let coincidence = if sensors.len() < min_sensors {
+            println!(
+                "Network trigger disabled, {} of {} required sensors configured",
+                sensors.len(),
+                min_sensors
+            );
+            None
+        } else {
+            let coords = sensors
+                .iter()
+                .map(|sensor| (sensor.id, sensor.coord))
+                .collect();
+            let config = CoincidenceConfig::new(min_sensors);
+
+            Some(CoincidenceTrigger::new(config, coords).map_err(|e| anyhow!("{}", e))?)
+        };
+        let triggers = sensors
+            .iter()
+            .map(|_| RecursiveStaLta::new(StaLtaConfig::default()).map_err(|e| anyhow!("{}", e)))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Detector {
+            coincidence,
+            triggers,
+            last_processed: vec![None; sensors.len()],
+        })
+    }
+
+    /// Prints the trigger, network and glitch events of the sensor at `index`, returns the network events.
+    pub fn process(&mut self, index: usize, sensor: &mut FilteredSensor) -> anyhow::Result<Vec<NetworkEvent>> {
+        let mut network_events = Vec::new();
+        let unprocessed = match self.last_processed[index] {
+            Some(last) => sensor.range(last + 1..),
+            None => sensor.snapshot(),
+        };
+
+        for reading in unprocessed {
+            self.last_processed[index] = Some(reading.timestamp);
+
+            let Some(event) = self.triggers[index].process(reading) else {
+                continue;
+            };
+
+            println!("[{}]: {:?}", sensor.name, event);
+
+            let Some(coincidence) = &mut self.coincidence else {
+                continue;
+            };
+
+            if let Some(network_event) = coincidence
+                .process(sensor.id, &event)
+                .map_err(|e| anyhow!("{}", e))?
+            {
+                println!(
+                    "Network event at {}, declared at {} by sensors {:?}",
+                    network_event.timestamp, network_event.declared_at, network_event.sensor_ids
+                );
+                network_events.push(network_event);
+            }
+        }
+
+        for filter in sensor.filters.iter_mut() {
+            for glitch in filter.drain_replacements() {
+                println!(
+                    "[{}]: glitch on {} at {}, {} replaced by {}",
+                    sensor.name, glitch.channel, glitch.timestamp, glitch.raw_value, glitch.replaced_by
+                );
+            }
+        }
+
+        Ok(network_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{FilteredSensor, create_sensor_from_config};
+    use crate::detector::Detector;
+    use skju_core::{Coord, Sample, SensorConfig};
+
+    fn sensors() -> Vec<FilteredSensor> {
+        (1..=3)
+            .map(|id| {
+                create_sensor_from_config(&SensorConfig::new(id, "Sensor", Coord { x: id as f32, y: 0.0 })).unwrap()
+            })
+            .collect()
+    }
+
+    /// Network events of 12 s of accelerometer noise on top of gravity, with shaking from `event_at` on.
+    fn network_events(event_at: Option<u128>) -> usize {
+        let mut sensors = sensors();
+        let mut detector = Detector::new(&sensors.iter().collect::<Vec<_>>(), 3).unwrap();
+        let mut seed = 42u64;
+        let mut count = 0;
+
+        for i in 0..1200u128 {
+            let timestamp = i * 10;
+
+            for (index, sensor) in sensors.iter_mut().enumerate() {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+
+                let noise = ((seed >> 33) as f64 / (1u64 << 31) as f64) * 0.02 - 0.01;
+                let shaking = match event_at {
+                    Some(event_at) if timestamp >= event_at => (timestamp as f64 / 40.0).sin() * 0.5,
+                    _ => 0.0,
+                };
+
+                sensor.write(Sample::new(noise, noise, 9.81 + noise + shaking), timestamp);
+                count += detector.process(index, sensor).unwrap().len();
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn network_event_on_gravity_offset() {
+        assert_eq!(network_events(None), 0);
+        assert_eq!(network_events(Some(8000)), 1);
+    }
+
+    #[test]
+    fn network_trigger_requires_enough_sensors() {
+        let sensors = sensors();
+        let sensors: Vec<_> = sensors.iter().collect();
+
+        assert!(Detector::new(&sensors, 1).is_err());
+        assert!(
+            Detector::new(&sensors[..1], 3)
+                .unwrap()
+                .coincidence
+                .is_none()
+        );
+        assert!(Detector::new(&sensors, 3).unwrap().coincidence.is_some());
+    }
+}
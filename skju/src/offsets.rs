@@ -0,0 +1,95 @@
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, rename, write};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often changed offsets are written, a restarted run processes at most this much data again.
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Read offsets of the followed files by sensor id, so that a later run can resume where this one stopped.
+pub struct Offsets {
+    path: PathBuf,
+    positions: BTreeMap<u64, u64>,
+    saved_at: Instant,
+    changed: bool,
+}
+
+impl Offsets {
+    /// Reads the offsets file, there are no offsets when it does not exist yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Offsets> {
+        let path = path.as_ref().to_path_buf();
+        let positions = match read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|e| anyhow!("{}: {}", path.display(), e))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(anyhow!("{}: {}", path.display(), e)),
+        };
+
+        Ok(Offsets {
+            path,
+            positions,
+            saved_at: Instant::now(),
+            changed: false,
+        })
+    }
+
+    pub fn get(&self, sensor_id: u64) -> Option<u64> {
+        self.positions.get(&sensor_id).copied()
+    }
+
+    pub fn set(&mut self, sensor_id: u64, position: u64) {
+        if self.positions.insert(sensor_id, position) != Some(position) {
+            self.changed = true;
+        }
+    }
+
+    pub fn save_if_due(&mut self) -> anyhow::Result<()> {
+        if self.saved_at.elapsed() < SAVE_INTERVAL {
+            return Ok(());
+        }
+
+        self.save()
+    }
+
+    /// Writes the changed offsets, through a temporary file so that a crash cannot leave a partial one.
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        let temporary = self.path.with_extension("tmp");
+
+        write(&temporary, serde_json::to_string_pretty(&self.positions)?)?;
+        rename(&temporary, &self.path)?;
+        self.saved_at = Instant::now();
+        self.changed = false;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::offsets::Offsets;
+
+    #[test]
+    fn saves_and_loads_offsets() {
+        let path = std::env::temp_dir().join(format!("skju_offsets_{}.json", std::process::id()));
+        let mut offsets = Offsets::load(&path).unwrap();
+
+        assert_eq!(offsets.get(1), None);
+
+        offsets.set(1, 120);
+        offsets.set(12, 40);
+        offsets.save().unwrap();
+
+        let loaded = Offsets::load(&path).unwrap();
+
+        assert_eq!(
+            (loaded.get(1), loaded.get(12), loaded.get(2)),
+            (Some(120), Some(40), None)
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
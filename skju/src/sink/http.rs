@@ -0,0 +1,341 @@
+use super::ReadingRequest;
+use super::spool::Spool;
+use anyhow::{anyhow, bail};
+use chrono::DateTime;
+use skju_core::response::Unit;
+use skju_core::{Channel, SensorOutput};
+use std::collections::HashMap;
+use std::mem::take;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use ureq::Agent;
+
+const BATCH_PATH: &str = "/api/readings/batch";
+/// Largest batch the server accepts.
+pub const MAX_BATCH_SIZE: usize = 10_000;
+/// A batch is sent at the latest after this long, even when it is not full.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+pub struct HttpSinkConfig {
+    /// Base URL of skju_server, e.g. `http://localhost:3000`.
+    pub url: String,
+    pub batch_size: usize,
+    /// Readings that could not be delivered are kept here until the server is reachable again.
+    pub spool: PathBuf,
+}
+
+/// How the readings of a sensor are forwarded.
+pub struct ForwardedSensor {
+    pub server_id: i32,
+    pub channels: Vec<Channel>,
+    pub unit: Unit,
+}
+
+/// Forwards filtered outputs to skju_server in batches from a background thread. Batches the server cannot
+/// take are spooled to disk and delivered in order once it is reachable again, retried with exponential
+/// backoff. Dropping the sink delivers or spools the pending batch.
+pub struct HttpSink {
+    sensors: HashMap<u64, ForwardedSensor>,
+    sender: Option<Sender<Queued>>,
+    acknowledged: Receiver<(u64, u64)>,
+    worker: Option<JoinHandle<()>>,
+}
+
+enum Queued {
+    Reading(ReadingRequest),
+    /// Read position of a sensor, acknowledged once everything queued before it is delivered or spooled.
+    Position {
+        sensor_id: u64,
+        position: u64,
+    },
+}
+
+struct Delivery {
+    agent: Agent,
+    url: String,
+    batch_size: usize,
+    spool: Spool,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+    positions: Vec<(u64, u64)>,
+    acknowledged: Sender<(u64, u64)>,
+    /// Cleared once readings are lost, later positions are not acknowledged so that a restart reads them again.
+    durable: bool,
+}
+
+enum PostError {
+    /// The server or the network failed, the readings are delivered later.
+    Unavailable(anyhow::Error),
+    /// The server refused the readings, sending them again would not help.
+    Rejected(anyhow::Error),
+}
+
+impl HttpSink {
+    pub fn start(config: HttpSinkConfig, sensors: HashMap<u64, ForwardedSensor>) -> anyhow::Result<HttpSink> {
+        if !(1..=MAX_BATCH_SIZE).contains(&config.batch_size) {
+            bail!("Batch size must be between 1 and {MAX_BATCH_SIZE}");
+        }
+
+        let agent: Agent = Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+        let (acknowledged_sender, acknowledged) = channel();
+        let delivery = Delivery {
+            agent,
+            url: format!("{}{}", config.url.trim_end_matches('/'), BATCH_PATH),
+            batch_size: config.batch_size,
+            spool: Spool::new(config.spool),
+            backoff: INITIAL_BACKOFF,
+            retry_at: None,
+            positions: Vec::new(),
+            acknowledged: acknowledged_sender,
+            durable: true,
+        };
+        let (sender, receiver) = channel();
+        let worker = std::thread::Builder::new()
+            .name("http-sink".into())
+            .spawn(move || delivery.run(receiver))?;
+
+        Ok(HttpSink {
+            sensors,
+            sender: Some(sender),
+            acknowledged,
+            worker: Some(worker),
+        })
+    }
+
+    /// Queues a reading per channel of the sensor, outputs of sensors that are not forwarded are ignored.
+    pub fn send(&self, output: &SensorOutput) {
+        let (Some(sensor), Some(sender)) = (self.sensors.get(&output.sensor_id), &self.sender) else {
+            return;
+        };
+        let Some(timestamp) = i64::try_from(output.timestamp)
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+        else {
+            return;
+        };
+
+        for channel in &sensor.channels {
+            let reading = ReadingRequest {
+                sensor_id: sensor.server_id,
+                channel: *channel,
+                unit: sensor.unit,
+                value: output.value.get(*channel),
+                timestamp,
+            };
+
+            // the worker only stops once the sink is dropped
+            let _ = sender.send(Queued::Reading(reading));
+        }
+    }
+
+    /// Queues the read position of a sensor after the readings sent so far.
+    pub fn mark(&self, sensor_id: u64, position: u64) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(Queued::Position { sensor_id, position });
+        }
+    }
+
+    /// Positions whose preceding readings have been delivered or spooled since the previous call.
+    pub fn acknowledged(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.acknowledged.try_iter()
+    }
+
+    /// Delivers or spools the pending readings and returns the positions acknowledged meanwhile.
+    pub fn close(mut self) -> Vec<(u64, u64)> {
+        self.stop();
+        self.acknowledged.try_iter().collect()
+    }
+
+    fn stop(&mut self) {
+        self.sender.take();
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for HttpSink {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Delivery {
+    fn run(mut self, receiver: Receiver<Queued>) {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut flush_at = Instant::now() + FLUSH_INTERVAL;
+
+        loop {
+            match receiver.recv_timeout(flush_at.saturating_duration_since(Instant::now())) {
+                Ok(queued) => {
+                    match queued {
+                        Queued::Reading(reading) => batch.push(reading),
+                        Queued::Position { sensor_id, position } => self.positions.push((sensor_id, position)),
+                    }
+
+                    if batch.len() < self.batch_size && Instant::now() < flush_at {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.deliver(take(&mut batch));
+                    break;
+                }
+            }
+
+            self.deliver(take(&mut batch));
+            flush_at = Instant::now() + FLUSH_INTERVAL;
+        }
+    }
+
+    fn deliver(&mut self, batch: Vec<ReadingRequest>) {
+        if let Err(e) = self.try_deliver(&batch) {
+            eprintln!("{}: {} readings lost, {:?}", self.url, batch.len(), e);
+            self.durable = false;
+        }
+
+        for position in self.positions.drain(..) {
+            if self.durable {
+                let _ = self.acknowledged.send(position);
+            }
+        }
+    }
+
+    fn try_deliver(&mut self, batch: &[ReadingRequest]) -> anyhow::Result<()> {
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return Ok(self.spool.append(batch)?);
+        }
+
+        // spooled readings are older, they go first so that the server receives every sensor in order
+        if !self.spool.is_empty() {
+            self.spool.append(batch)?;
+            self.drain_spool()?;
+
+            return Ok(());
+        }
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        match self.post(batch) {
+            Ok(()) => Ok(()),
+            Err(PostError::Unavailable(e)) => {
+                self.failed(e);
+                Ok(self.spool.append(batch)?)
+            }
+            // resending would not help, the positions of refused readings count as handled
+            Err(PostError::Rejected(e)) => {
+                eprintln!("{}: {} readings rejected, {:?}", self.url, batch.len(), e);
+                Ok(())
+            }
+        }
+    }
+
+    fn drain_spool(&mut self) -> anyhow::Result<()> {
+        let mut failure = None;
+        let drained = self
+            .spool
+            .drain(self.batch_size, |chunk| match self.post(chunk) {
+                Ok(()) => true,
+                Err(PostError::Unavailable(e)) => {
+                    failure = Some(e);
+                    false
+                }
+                Err(PostError::Rejected(e)) => {
+                    eprintln!("{}: {} spooled readings rejected, {:?}", self.url, chunk.len(), e);
+                    true
+                }
+            })?;
+
+        match failure {
+            Some(e) => self.failed(e),
+            None if drained => {
+                self.backoff = INITIAL_BACKOFF;
+                self.retry_at = None;
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn post(&self, readings: &[ReadingRequest]) -> Result<(), PostError> {
+        match self.agent.post(&self.url).send_json(readings) {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::StatusCode(status)) if (400..500).contains(&status) && status != 408 && status != 429 => {
+                Err(PostError::Rejected(anyhow!("server responded with {status}")))
+            }
+            Err(e) => Err(PostError::Unavailable(e.into())),
+        }
+    }
+
+    fn failed(&mut self, error: anyhow::Error) {
+        eprintln!(
+            "{}: {}, spooling readings and retrying in {:?}",
+            self.url, error, self.backoff
+        );
+
+        self.retry_at = Some(Instant::now() + self.backoff);
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sink::spool::Spool;
+    use crate::sink::{ForwardedSensor, HttpSink, HttpSinkConfig};
+    use skju_core::response::Unit;
+    use skju_core::{Channel, Coord, Sample, SensorOutput};
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+
+    #[test]
+    fn acknowledges_positions_once_spooled() {
+        // nothing listens on the port once the listener is dropped
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let spool = std::env::temp_dir().join(format!("skju_sink_spool_{}.jsonl", std::process::id()));
+        let config = HttpSinkConfig {
+            url: format!("http://127.0.0.1:{port}"),
+            batch_size: 100,
+            spool: spool.clone(),
+        };
+        let sensor = ForwardedSensor {
+            server_id: 1,
+            channels: vec![Channel::Z],
+            unit: Unit::Counts,
+        };
+        let sink = HttpSink::start(config, HashMap::from([(1, sensor)])).unwrap();
+
+        sink.send(&SensorOutput {
+            sensor_id: 1,
+            sensor_name: String::from("sensor 1"),
+            sensor_coord: Coord { x: 0.0, y: 0.0 },
+            value: Sample::vertical(1.0),
+            timestamp: 1_700_000_000_000,
+        });
+        sink.mark(1, 42);
+        sink.mark(2, 7);
+
+        assert_eq!(sink.close(), [(1, 42), (2, 7)]);
+        assert!(!Spool::new(spool.clone()).is_empty());
+        std::fs::remove_file(&spool).unwrap();
+    }
+}
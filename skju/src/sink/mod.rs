@@ -0,0 +1,32 @@
+mod http;
+mod spool;
+
+pub use http::*;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use skju_core::Channel;
+use skju_core::response::Unit;
+
+/// Reading as taken by `POST /api/readings/batch` of skju_server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingRequest {
+    pub sensor_id: i32,
+    pub channel: Channel,
+    pub unit: Unit,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// `<sensor id>=<server sensor id>`
+pub fn parse_server_id(spec: &str) -> Result<(u64, i32), String> {
+    let (id, server_id) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("Expected <sensor id>=<server sensor id>, got {spec}"))?;
+    let id = id.parse().map_err(|_| format!("Invalid sensor id {id}"))?;
+    let server_id = server_id
+        .parse()
+        .map_err(|_| format!("Invalid server sensor id {server_id}"))?;
+
+    Ok((id, server_id))
+}
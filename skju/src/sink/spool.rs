@@ -0,0 +1,160 @@
+use super::ReadingRequest;
+use std::fs::{File, OpenOptions, metadata, remove_file, rename};
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::PathBuf;
+
+/// Readings the server could not take yet, one JSON object per line in the order they were produced.
+pub struct Spool {
+    path: PathBuf,
+}
+
+impl Spool {
+    pub fn new(path: PathBuf) -> Spool {
+        Spool { path }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        metadata(&self.path).map_or(true, |metadata| metadata.len() == 0)
+    }
+
+    pub fn append(&self, readings: &[ReadingRequest]) -> io::Result<()> {
+        if readings.is_empty() {
+            return Ok(());
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+
+        write_lines(&mut writer, readings)?;
+        writer.flush()
+    }
+
+    /// Hands the spooled readings to `deliver` in chunks of `chunk_size` until it refuses one. The refused
+    /// chunk and everything after it stay spooled, returns whether the spool was emptied.
+    pub fn drain<F>(&self, chunk_size: usize, mut deliver: F) -> io::Result<bool>
+    where
+        F: FnMut(&[ReadingRequest]) -> bool,
+    {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(true),
+            Err(e) => return Err(e),
+        };
+        let mut lines = BufReader::new(file).lines();
+        let mut chunk = Vec::with_capacity(chunk_size);
+
+        loop {
+            chunk.clear();
+
+            for line in lines.by_ref() {
+                let line = line?;
+
+                match serde_json::from_str(&line) {
+                    Ok(reading) => chunk.push(reading),
+                    // a line cut off by a crash cannot be delivered
+                    Err(e) => eprintln!("{}: skipped spooled reading, {}", self.path.display(), e),
+                }
+
+                if chunk.len() == chunk_size {
+                    break;
+                }
+            }
+
+            if chunk.is_empty() {
+                remove_file(&self.path)?;
+                return Ok(true);
+            }
+
+            if !deliver(&chunk) {
+                break;
+            }
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+
+        write_lines(&mut writer, &chunk)?;
+
+        for line in lines {
+            writeln!(writer, "{}", line?)?;
+        }
+
+        writer.flush()?;
+        drop(writer);
+        rename(&temporary, &self.path)?;
+
+        Ok(false)
+    }
+}
+
+fn write_lines<W: Write>(writer: &mut W, readings: &[ReadingRequest]) -> io::Result<()> {
+    for reading in readings {
+        serde_json::to_writer(&mut *writer, reading)?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sink::ReadingRequest;
+    use crate::sink::spool::Spool;
+    use chrono::DateTime;
+    use skju_core::Channel;
+    use skju_core::response::Unit;
+
+    fn reading(value: f64) -> ReadingRequest {
+        ReadingRequest {
+            sensor_id: 1,
+            channel: Channel::Z,
+            unit: Unit::Counts,
+            value,
+            timestamp: DateTime::from_timestamp_millis(1_700_000_000_000).unwrap(),
+        }
+    }
+
+    #[test]
+    fn keeps_undelivered_readings_in_order() {
+        let path = std::env::temp_dir().join(format!("skju_spool_{}.jsonl", std::process::id()));
+        let spool = Spool::new(path.clone());
+        let mut delivered = Vec::new();
+
+        assert!(spool.is_empty());
+        spool
+            .append(&[reading(1.0), reading(2.0), reading(3.0)])
+            .unwrap();
+        spool.append(&[reading(4.0), reading(5.0)]).unwrap();
+
+        // the server goes away after the first chunk
+        let drained = spool
+            .drain(2, |chunk| {
+                if delivered.is_empty() {
+                    delivered.extend_from_slice(chunk);
+                    return true;
+                }
+
+                false
+            })
+            .unwrap();
+
+        assert!(!drained);
+        assert_eq!(delivered, [reading(1.0), reading(2.0)]);
+
+        let drained = spool
+            .drain(2, |chunk| {
+                delivered.extend_from_slice(chunk);
+                true
+            })
+            .unwrap();
+
+        assert!(drained && spool.is_empty() && !path.exists());
+        assert_eq!(
+            delivered.iter().map(|r| r.value).collect::<Vec<_>>(),
+            [1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+    }
+}
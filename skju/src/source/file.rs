@@ -0,0 +1,235 @@
+use super::{IDLE_TIMEOUT, LineRead, LineReader, Next, SampleSource, parse_line};
+use anyhow::anyhow;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs::{File, Metadata, metadata};
+use std::io::{self, BufReader, ErrorKind, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+
+/// Where reading a followed file starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartPosition {
+    Beginning,
+    End,
+    /// A previously saved offset, the file is read from its beginning when it has become shorter since.
+    Offset(u64),
+}
+
+/// Text file of samples, either followed as it grows or read once from the start.
+///
+/// A followed file is woken up by file system notifications. Once it is truncated it is read again from
+/// its beginning, once it is replaced (rotated or recreated, detected on unix) the new file is read from its
+/// beginning after the remaining lines of the old one.
+pub struct FileSource {
+    path: PathBuf,
+    reader: Option<LineReader<BufReader<File>>>,
+    follow: Option<Follow>,
+}
+
+struct Follow {
+    _watcher: RecommendedWatcher,
+    changes: Receiver<()>,
+}
+
+impl FileSource {
+    /// Follows the file, which does not need to exist yet.
+    pub fn follow<P: AsRef<Path>>(path: P, start: StartPosition) -> anyhow::Result<FileSource> {
+        let path = path.as_ref().to_path_buf();
+        let name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("{} is not a file", path.display()))?
+            .to_owned();
+        let (sender, changes) = channel();
+
+        // the directory is watched so that the file is noticed when it is created again
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let relevant = match &event {
+                Ok(event) => event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == Some(&name)),
+                Err(_) => true,
+            };
+
+            if relevant {
+                let _ = sender.send(());
+            }
+        })?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| anyhow!("{}: {}", dir.display(), e))?;
+
+        let mut source = FileSource {
+            path,
+            reader: None,
+            follow: Some(Follow { _watcher: watcher, changes }),
+        };
+
+        source.open(start)?;
+        Ok(source)
+    }
+
+    pub fn recording<P: AsRef<Path>>(path: P) -> anyhow::Result<FileSource> {
+        let reader = BufReader::new(File::open(&path)?);
+
+        Ok(FileSource {
+            path: path.as_ref().to_path_buf(),
+            reader: Some(LineReader::new(reader)),
+            follow: None,
+        })
+    }
+
+    /// Opens the file unless it does not exist.
+    fn open(&mut self, start: StartPosition) -> io::Result<()> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        let position = match start {
+            StartPosition::Beginning => 0,
+            StartPosition::End => len,
+            StartPosition::Offset(offset) if offset <= len => offset,
+            StartPosition::Offset(_) => 0,
+        };
+
+        file.seek(SeekFrom::Start(position))?;
+        self.reader = Some(LineReader::at(BufReader::new(file), position));
+
+        Ok(())
+    }
+
+    /// Starts over when the end of the file was reached because it was truncated or replaced.
+    fn reopen_if_changed(&mut self) -> io::Result<()> {
+        let Some(reader) = &mut self.reader else {
+            return self.open(StartPosition::Beginning);
+        };
+        let opened = reader.get_mut().get_ref().metadata()?;
+
+        if opened.len() < reader.get_mut().stream_position()? {
+            reader.get_mut().seek(SeekFrom::Start(0))?;
+            reader.restart(0);
+        }
+
+        match metadata(&self.path) {
+            Ok(current) if !same_file(&opened, &current) => self.open(StartPosition::Beginning),
+            Ok(_) => Ok(()),
+            // a removed file is kept until another one is created at its path
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Blocks until the file changes, or for at most [`IDLE_TIMEOUT`] so that a stop is noticed.
+    fn wait(&self) {
+        if let Some(follow) = &self.follow
+            && follow.changes.recv_timeout(IDLE_TIMEOUT).is_ok()
+        {
+            // the next read covers every change that happened so far
+            follow.changes.try_iter().for_each(drop);
+        }
+    }
+}
+
+impl SampleSource for FileSource {
+    fn next(&mut self) -> anyhow::Result<Next> {
+        if self.follow.is_none() {
+            return match &mut self.reader {
+                Some(reader) => reader.next_sample(),
+                None => Ok(Next::End),
+            };
+        }
+
+        let line = match &mut self.reader {
+            Some(reader) => reader.read_line()?,
+            None => LineRead::Eof,
+        };
+
+        match line {
+            LineRead::Line(line) => parse_line(&line),
+            LineRead::Pending => Ok(Next::Idle),
+            LineRead::Eof => {
+                let position = self.position();
+
+                self.reopen_if_changed()?;
+
+                if self.position() == position {
+                    self.wait();
+                }
+
+                Ok(Next::Idle)
+            }
+        }
+    }
+
+    fn position(&self) -> Option<u64> {
+        self.reader.as_ref().map(LineReader::position)
+    }
+}
+
+#[cfg(unix)]
+fn same_file(opened: &Metadata, current: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    (opened.dev(), opened.ino()) == (current.dev(), current.ino())
+}
+
+#[cfg(not(unix))]
+fn same_file(_: &Metadata, _: &Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{FileSource, Next, SampleSource, StartPosition};
+    use std::fs::{OpenOptions, create_dir_all, remove_dir_all, rename, write};
+    use std::io::Write;
+
+    fn next_timestamp(source: &mut FileSource) -> Option<u128> {
+        for _ in 0..10 {
+            match source.next().unwrap() {
+                Next::Sample(data) => return Some(data.timestamp),
+                Next::Idle => continue,
+                Next::End => return None,
+            }
+        }
+
+        None
+    }
+
+    #[test]
+    fn follows_truncated_and_rotated_files() {
+        let dir = std::env::temp_dir().join(format!("skju_file_source_{}", std::process::id()));
+        let path = dir.join("sensor_1.txt");
+
+        create_dir_all(&dir).unwrap();
+        write(&path, "0;0;1;10\n0;0;1;20\n").unwrap();
+
+        let mut source = FileSource::follow(&path, StartPosition::Offset(9)).unwrap();
+
+        assert_eq!(next_timestamp(&mut source), Some(20));
+        assert_eq!(source.position(), Some(18));
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+
+        file.write_all(b"0;0;1;30\n").unwrap();
+        assert_eq!(next_timestamp(&mut source), Some(30));
+
+        // the emulator truncates the file when it starts again
+        write(&path, "0;0;1;5\n").unwrap();
+        assert_eq!(next_timestamp(&mut source), Some(5));
+
+        rename(&path, dir.join("sensor_1.txt.1")).unwrap();
+        write(&path, "0;0;1;40\n").unwrap();
+        assert_eq!(next_timestamp(&mut source), Some(40));
+        assert_eq!(next_timestamp(&mut source), None);
+
+        remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+mod file;
+mod socket;
+mod stdin;
+mod websocket;
+
+pub use file::*;
+pub use socket::*;
+pub use stdin::*;
+pub use websocket::*;
+
+use anyhow::anyhow;
+use skju_core::{SensorConfig, SensorData, SourceConfig};
+use std::io::{self, BufRead, ErrorKind};
+use std::time::Duration;
+
+/// Path of the file followed by sensors without a configured source.
+pub const DEFAULT_SOURCE_PATH: &str = "data/sensor_{id}.txt";
+/// How long a source blocks before reporting that it is idle, so that readers can notice a stop.
+const IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+pub enum Next {
+    Sample(SensorData),
+    /// No sample is available yet.
+    Idle,
+    /// The source is exhausted.
+    End,
+}
+
+/// Delivers the samples of a single sensor.
+pub trait SampleSource: Send {
+    fn next(&mut self) -> anyhow::Result<Next>;
+
+    /// Byte offset just after the last delivered sample, for sources that can be resumed from there.
+    fn position(&self) -> Option<u64> {
+        None
+    }
+}
+
+pub fn default_source() -> SourceConfig {
+    SourceConfig::File { path: DEFAULT_SOURCE_PATH.into() }
+}
+
+/// Opens the configured source of a sensor, the `{id}` placeholder of paths and URLs is replaced by its id.
+pub fn open_source(
+    sensor: &SensorConfig,
+    config: &SourceConfig,
+    start: StartPosition,
+) -> anyhow::Result<Box<dyn SampleSource>> {
+    let expand = |template: &str| template.replace("{id}", &sensor.id.to_string());
+
+    let source: Box<dyn SampleSource> = match config {
+        SourceConfig::File { path } => Box::new(FileSource::follow(expand(path), start)?),
+        SourceConfig::Stdin => Box::new(StdinSource::new()),
+        SourceConfig::Udp { bind } => Box::new(UdpSource::bind(&expand(bind))?),
+        SourceConfig::Tcp { address } => Box::new(TcpSource::connect(&expand(address))?),
+        SourceConfig::WebSocket { url } => Box::new(WebSocketSource::connect(&expand(url), sensor)?),
+    };
+
+    Ok(source)
+}
+
+/// `file:<path>`, `stdin`, `udp:<bind address>`, `tcp:<address>` or a `ws://` / `wss://` URL.
+pub fn parse_source(spec: &str) -> Result<SourceConfig, String> {
+    if spec == "stdin" {
+        return Ok(SourceConfig::Stdin);
+    }
+
+    if spec.starts_with("ws://") || spec.starts_with("wss://") {
+        return Ok(SourceConfig::WebSocket { url: spec.into() });
+    }
+
+    match spec.split_once(':') {
+        Some(("file", path)) => Ok(SourceConfig::File { path: path.into() }),
+        Some(("udp", bind)) => Ok(SourceConfig::Udp { bind: bind.into() }),
+        Some(("tcp", address)) => Ok(SourceConfig::Tcp { address: address.into() }),
+        _ => Err(format!("Unknown source {spec}")),
+    }
+}
+
+/// `<sensor id>=<source>`
+pub fn parse_sensor_source(spec: &str) -> Result<(u64, SourceConfig), String> {
+    let (id, source) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("Expected <sensor id>=<source>, got {spec}"))?;
+    let id = id.parse().map_err(|_| format!("Invalid sensor id {id}"))?;
+
+    Ok((id, parse_source(source)?))
+}
+
+/// Parses a trimmed sample line, blank lines are skipped.
+pub(crate) fn parse_line(line: &str) -> anyhow::Result<Next> {
+    if line.is_empty() {
+        return Ok(Next::Idle);
+    }
+
+    let data: SensorData = line
+        .parse()
+        .map_err(|e: String| anyhow!("{}: {}", e, line))?;
+
+    Ok(Next::Sample(data))
+}
+
+pub(crate) enum LineRead {
+    Line(String),
+    /// No complete line is available yet, a partial one is kept for the next read.
+    Pending,
+    Eof,
+}
+
+/// Reads lines from a reader that may time out or reach its end in the middle of a line.
+pub(crate) struct LineReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    /// Byte offset just after the last complete line.
+    position: u64,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        LineReader::at(reader, 0)
+    }
+
+    /// Reader whose underlying reader already starts at `position`.
+    pub fn at(reader: R, position: u64) -> Self {
+        LineReader { reader, line: Vec::new(), position }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Drops the partial line after the underlying reader was moved to `position`.
+    pub fn restart(&mut self, position: u64) {
+        self.line.clear();
+        self.position = position;
+    }
+
+    pub fn read_line(&mut self) -> io::Result<LineRead> {
+        match self.reader.read_until(b'\n', &mut self.line) {
+            Ok(0) => Ok(LineRead::Eof),
+            Ok(_) if self.line.ends_with(b"\n") => {
+                self.position += self.line.len() as u64;
+                Ok(LineRead::Line(self.take()))
+            }
+            Ok(_) => Ok(LineRead::Pending),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(LineRead::Pending),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Next sample of a stream which is exhausted at its end, a final line without line break included.
+    pub fn next_sample(&mut self) -> anyhow::Result<Next> {
+        match self.read_line()? {
+            LineRead::Line(line) => parse_line(&line),
+            LineRead::Pending => Ok(Next::Idle),
+            LineRead::Eof if self.line.is_empty() => Ok(Next::End),
+            LineRead::Eof => parse_line(&self.take()),
+        }
+    }
+
+    fn take(&mut self) -> String {
+        let line = String::from_utf8_lossy(&self.line).trim().to_string();
+
+        self.line.clear();
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{LineRead, LineReader, Next, parse_sensor_source, parse_source};
+    use skju_core::SourceConfig;
+    use std::io::Cursor;
+
+    #[test]
+    fn parses_source_specs() {
+        assert_eq!(parse_source("stdin"), Ok(SourceConfig::Stdin));
+        assert_eq!(
+            parse_source("file:data/sensor_{id}.txt"),
+            Ok(SourceConfig::File { path: "data/sensor_{id}.txt".into() })
+        );
+        assert_eq!(
+            parse_sensor_source("2=udp:0.0.0.0:9000"),
+            Ok((2, SourceConfig::Udp { bind: "0.0.0.0:9000".into() }))
+        );
+        assert_eq!(
+            parse_source("ws://localhost:3000/api/readings/stream?sensor_id={id}"),
+            Ok(SourceConfig::WebSocket {
+                url: "ws://localhost:3000/api/readings/stream?sensor_id={id}".into()
+            })
+        );
+        assert!(parse_source("serial:/dev/ttyUSB0").is_err());
+        assert!(parse_sensor_source("x=stdin").is_err());
+    }
+
+    #[test]
+    fn keeps_partial_lines() {
+        let mut reader = LineReader::new(Cursor::new("0.1;0.2;0.3;10\n0.4;5"));
+
+        assert!(matches!(reader.read_line().unwrap(), LineRead::Line(line) if line == "0.1;0.2;0.3;10"));
+        assert!(matches!(reader.read_line().unwrap(), LineRead::Pending));
+        assert_eq!(reader.position(), 15);
+        assert!(matches!(reader.next_sample().unwrap(), Next::Sample(data) if data.timestamp == 5));
+        assert!(matches!(reader.next_sample().unwrap(), Next::End));
+    }
+}
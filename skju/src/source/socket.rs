@@ -0,0 +1,75 @@
+use super::{IDLE_TIMEOUT, LineReader, Next, SampleSource, parse_line};
+use std::collections::VecDeque;
+use std::io::{BufReader, ErrorKind};
+use std::net::{TcpStream, UdpSocket};
+
+/// Datagrams holding one or more sample lines.
+pub struct UdpSource {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+    pending: VecDeque<String>,
+}
+
+/// Sample lines read from a connection to a streaming node or gateway.
+pub struct TcpSource {
+    reader: LineReader<BufReader<TcpStream>>,
+}
+
+impl UdpSource {
+    pub fn bind(address: &str) -> anyhow::Result<UdpSource> {
+        let socket = UdpSocket::bind(address)?;
+
+        socket.set_read_timeout(Some(IDLE_TIMEOUT))?;
+
+        Ok(UdpSource {
+            socket,
+            buffer: vec![0; 65_535],
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+impl SampleSource for UdpSource {
+    fn next(&mut self) -> anyhow::Result<Next> {
+        if self.pending.is_empty() {
+            let length = match self.socket.recv(&mut self.buffer) {
+                Ok(length) => length,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Ok(Next::Idle),
+                Err(e) => return Err(e.into()),
+            };
+
+            let datagram = String::from_utf8_lossy(&self.buffer[..length]);
+
+            self.pending.extend(
+                datagram
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            );
+        }
+
+        match self.pending.pop_front() {
+            Some(line) => parse_line(&line),
+            None => Ok(Next::Idle),
+        }
+    }
+}
+
+impl TcpSource {
+    pub fn connect(address: &str) -> anyhow::Result<TcpSource> {
+        let stream = TcpStream::connect(address)?;
+
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+
+        Ok(TcpSource {
+            reader: LineReader::new(BufReader::new(stream)),
+        })
+    }
+}
+
+impl SampleSource for TcpSource {
+    fn next(&mut self) -> anyhow::Result<Next> {
+        self.reader.next_sample()
+    }
+}
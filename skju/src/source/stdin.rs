@@ -0,0 +1,21 @@
+use super::{LineReader, Next, SampleSource};
+use std::io::{BufReader, Stdin, stdin};
+
+/// Samples piped into the process, a single sensor can read them.
+pub struct StdinSource {
+    reader: LineReader<BufReader<Stdin>>,
+}
+
+impl StdinSource {
+    pub fn new() -> StdinSource {
+        StdinSource {
+            reader: LineReader::new(BufReader::new(stdin())),
+        }
+    }
+}
+
+impl SampleSource for StdinSource {
+    fn next(&mut self) -> anyhow::Result<Next> {
+        self.reader.next_sample()
+    }
+}
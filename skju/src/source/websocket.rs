@@ -0,0 +1,184 @@
+use super::{IDLE_TIMEOUT, Next, SampleSource};
+use anyhow::bail;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use skju_core::{Channel, Sample, SensorConfig, SensorData};
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket, connect};
+
+/// Live readings of `skju_server` (`/api/readings/stream`), which publishes one message per channel.
+pub struct WebSocketSource {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    sensor_id: u64,
+    assembler: SampleAssembler,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveReading {
+    sensor_id: i32,
+    channel: Channel,
+    value: f64,
+    timestamp: DateTime<Utc>,
+}
+
+/// Combines single channel readings into samples, a sample is complete once every channel of the sensor
+/// arrived. When a newer timestamp arrives first, the missing channels keep their previous value, an
+/// incomplete first sample is dropped.
+pub(crate) struct SampleAssembler {
+    channels: Vec<Channel>,
+    pending: Option<(u128, Sample, [bool; 3])>,
+    previous: Option<Sample>,
+    ready: VecDeque<SensorData>,
+}
+
+impl WebSocketSource {
+    pub fn connect(url: &str, sensor: &SensorConfig) -> anyhow::Result<WebSocketSource> {
+        let (socket, _) = connect(url)?;
+
+        let stream = match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream,
+            MaybeTlsStream::Rustls(stream) => stream.get_ref(),
+            _ => bail!("Unsupported WebSocket stream"),
+        };
+
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+
+        Ok(WebSocketSource {
+            socket,
+            sensor_id: sensor.id,
+            assembler: SampleAssembler::new(sensor.channels.clone()),
+        })
+    }
+}
+
+impl SampleSource for WebSocketSource {
+    fn next(&mut self) -> anyhow::Result<Next> {
+        if let Some(data) = self.assembler.pop() {
+            return Ok(Next::Sample(data));
+        }
+
+        let text = match self.socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => return Ok(Next::End),
+            Ok(_) => return Ok(Next::Idle),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Ok(Next::Idle);
+            }
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(Next::End),
+            Err(e) => return Err(e.into()),
+        };
+
+        let reading: LiveReading = serde_json::from_str(&text)?;
+
+        // the stream carries every sensor unless the URL filters by sensor id
+        if reading.sensor_id as u64 == self.sensor_id {
+            let timestamp = reading.timestamp.timestamp_millis().max(0) as u128;
+
+            self.assembler
+                .push(reading.channel, reading.value, timestamp);
+        }
+
+        Ok(self.assembler.pop().map_or(Next::Idle, Next::Sample))
+    }
+}
+
+impl SampleAssembler {
+    pub fn new(channels: Vec<Channel>) -> SampleAssembler {
+        SampleAssembler {
+            channels,
+            pending: None,
+            previous: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, channel: Channel, value: f64, timestamp: u128) {
+        if self
+            .pending
+            .is_some_and(|(pending_timestamp, ..)| pending_timestamp != timestamp)
+        {
+            self.flush();
+        }
+
+        let (_, sample, received) = self
+            .pending
+            .get_or_insert((timestamp, Sample::default(), [false; 3]));
+
+        sample.set(channel, value);
+        received[channel.index()] = true;
+
+        if self
+            .channels
+            .iter()
+            .all(|channel| received[channel.index()])
+        {
+            self.flush();
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<SensorData> {
+        self.ready.pop_front()
+    }
+
+    fn flush(&mut self) {
+        let Some((timestamp, mut value, received)) = self.pending.take() else {
+            return;
+        };
+
+        for channel in &self.channels {
+            if received[channel.index()] {
+                continue;
+            }
+
+            // a zero would be a step for the filters and the triggers
+            match self.previous {
+                Some(previous) => value.set(*channel, previous.get(*channel)),
+                None => return,
+            }
+        }
+
+        self.previous = Some(value);
+        self.ready.push_back(SensorData { value, timestamp });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::websocket::SampleAssembler;
+    use skju_core::{Channel, Sample};
+
+    #[test]
+    fn assembles_channels_into_samples() {
+        let mut assembler = SampleAssembler::new(Channel::ALL.to_vec());
+
+        assembler.push(Channel::E, 1.0, 10);
+        assembler.push(Channel::N, 2.0, 10);
+        assert!(assembler.pop().is_none());
+
+        assembler.push(Channel::Z, 3.0, 10);
+        assert_eq!(assembler.pop().unwrap().value, Sample::new(1.0, 2.0, 3.0));
+
+        // the north and vertical channels of the second sample never arrive
+        assembler.push(Channel::E, 4.0, 20);
+        assembler.push(Channel::Z, 5.0, 30);
+
+        let incomplete = assembler.pop().unwrap();
+        assert_eq!(
+            (incomplete.value, incomplete.timestamp),
+            (Sample::new(4.0, 2.0, 3.0), 20)
+        );
+        assert!(assembler.pop().is_none());
+    }
+
+    #[test]
+    fn drops_incomplete_first_sample() {
+        let mut assembler = SampleAssembler::new(Channel::ALL.to_vec());
+
+        assembler.push(Channel::E, 1.0, 10);
+        assembler.push(Channel::E, 2.0, 20);
+        assert!(assembler.pop().is_none());
+    }
+}
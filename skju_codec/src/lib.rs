@@ -0,0 +1,314 @@
+//! Wire format of the MPU6500 FIFO batches sent by the sensor nodes.
+//!
+//! A batch is a fixed size header followed by the raw FIFO samples, exactly as they were drained
+//! from the sensor. All header fields are big-endian, the same as the FIFO data.
+//!
+//! | offset | size | field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 1    | format version                          |
+//! | 1      | 1    | FIFO_EN register value (sample layout)  |
+//! | 2      | 2    | node id                                 |
+//! | 4      | 4    | sequence number                         |
+//! | 8      | 2    | sample rate, Hz                         |
+//! | 10     | 8    | first sample timestamp, µs              |
+//! | 18     | 2    | sample count                            |
+//! | 20     | ..   | samples                                 |
+#![no_std]
+
+use core::fmt;
+use mpu6500::fifo::{FIFOLayout, FIFOSample};
+
+pub const VERSION: u8 = 1;
+pub const HEADER_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchHeader {
+    pub fifo_en: u8,
+    pub node_id: u16,
+    pub sequence: u32,
+    pub sample_rate_hz: u16,
+    pub timestamp_us: u64,
+    pub sample_count: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecError {
+    BufferTooSmall { needed: usize, available: usize },
+    UnsupportedVersion(u8),
+    LengthMismatch { expected: usize, actual: usize },
+    EmptyLayout,
+}
+
+pub struct Batch<'a> {
+    pub header: BatchHeader,
+    layout: FIFOLayout,
+    samples: &'a [u8],
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::BufferTooSmall { needed, available } => {
+                write!(
+                    formatter,
+                    "Buffer too small: {needed} bytes needed, {available} available"
+                )
+            }
+            CodecError::UnsupportedVersion(version) => write!(formatter, "Unsupported format version: {version}"),
+            CodecError::LengthMismatch { expected, actual } => {
+                write!(formatter, "Expected {expected} bytes of samples, got {actual}")
+            }
+            CodecError::EmptyLayout => write!(formatter, "FIFO layout has no fields"),
+        }
+    }
+}
+
+impl BatchHeader {
+    pub fn new(layout: &FIFOLayout, node_id: u16, sequence: u32, sample_rate_hz: u16, timestamp_us: u64) -> Self {
+        Self {
+            fifo_en: layout.fifo_register(),
+            node_id,
+            sequence,
+            sample_rate_hz,
+            timestamp_us,
+            sample_count: 0,
+        }
+    }
+
+    pub fn sample_count(mut self, sample_count: u16) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+}
+
+impl<'a> Batch<'a> {
+    pub fn layout(&self) -> &FIFOLayout {
+        &self.layout
+    }
+
+    pub fn raw_samples(&self) -> &'a [u8] {
+        self.samples
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = FIFOSample<'_>> {
+        self.samples
+            .chunks_exact(self.layout.sample_size)
+            .map(|data| FIFOSample::new(data, &self.layout))
+    }
+
+    /// Timestamp of the sample at `index` derived from the first sample timestamp and the sample rate, `None`
+    /// when it does not fit into `u64`.
+    pub fn sample_timestamp_us(&self, index: usize) -> Option<u64> {
+        if self.header.sample_rate_hz == 0 {
+            return Some(self.header.timestamp_us);
+        }
+
+        let offset = (index as u64).checked_mul(1_000_000)? / self.header.sample_rate_hz as u64;
+
+        self.header.timestamp_us.checked_add(offset)
+    }
+}
+
+pub fn encoded_len(layout: &FIFOLayout, sample_count: usize) -> usize {
+    HEADER_SIZE + layout.sample_size * sample_count
+}
+
+/// Writes the header and `samples` into `output`, the sample count is taken from the samples length.
+/// Returns the number of bytes written.
+pub fn encode_batch(header: &BatchHeader, samples: &[u8], output: &mut [u8]) -> Result<usize, CodecError> {
+    let layout = layout_from_register(header.fifo_en)?;
+
+    if !samples.len().is_multiple_of(layout.sample_size) {
+        let expected = samples.len() - samples.len() % layout.sample_size;
+        return Err(CodecError::LengthMismatch { expected, actual: samples.len() });
+    }
+
+    let sample_count = samples.len() / layout.sample_size;
+
+    // the header counts the samples in 16 bits
+    if sample_count > u16::MAX as usize {
+        let expected = u16::MAX as usize * layout.sample_size;
+        return Err(CodecError::LengthMismatch { expected, actual: samples.len() });
+    }
+
+    let needed = encoded_len(&layout, sample_count);
+
+    if output.len() < needed {
+        return Err(CodecError::BufferTooSmall { needed, available: output.len() });
+    }
+
+    output[0] = VERSION;
+    output[1] = header.fifo_en;
+    output[2..4].copy_from_slice(&header.node_id.to_be_bytes());
+    output[4..8].copy_from_slice(&header.sequence.to_be_bytes());
+    output[8..10].copy_from_slice(&header.sample_rate_hz.to_be_bytes());
+    output[10..18].copy_from_slice(&header.timestamp_us.to_be_bytes());
+    output[18..20].copy_from_slice(&(sample_count as u16).to_be_bytes());
+    output[HEADER_SIZE..needed].copy_from_slice(samples);
+
+    Ok(needed)
+}
+
+pub fn decode_batch(data: &[u8]) -> Result<Batch<'_>, CodecError> {
+    if data.len() < HEADER_SIZE {
+        return Err(CodecError::BufferTooSmall {
+            needed: HEADER_SIZE,
+            available: data.len(),
+        });
+    }
+
+    if data[0] != VERSION {
+        return Err(CodecError::UnsupportedVersion(data[0]));
+    }
+
+    let header = BatchHeader {
+        fifo_en: data[1],
+        node_id: u16::from_be_bytes([data[2], data[3]]),
+        sequence: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+        sample_rate_hz: u16::from_be_bytes([data[8], data[9]]),
+        timestamp_us: u64::from_be_bytes([
+            data[10], data[11], data[12], data[13], data[14], data[15], data[16], data[17],
+        ]),
+        sample_count: u16::from_be_bytes([data[18], data[19]]),
+    };
+
+    let layout = layout_from_register(header.fifo_en)?;
+    let expected = layout.sample_size * header.sample_count as usize;
+    let samples = &data[HEADER_SIZE..];
+
+    if samples.len() < expected {
+        return Err(CodecError::LengthMismatch { expected, actual: samples.len() });
+    }
+
+    Ok(Batch {
+        header,
+        layout,
+        samples: &samples[..expected],
+    })
+}
+
+fn layout_from_register(fifo_en: u8) -> Result<FIFOLayout, CodecError> {
+    let layout = FIFOLayout::from_fifo_register(fifo_en);
+
+    if layout.sample_size == 0 {
+        return Err(CodecError::EmptyLayout);
+    }
+
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BatchHeader, CodecError, HEADER_SIZE, decode_batch, encode_batch, encoded_len};
+    use mpu6500::fifo::{FIFOEntryType, FIFOLayout, FIFOSensors};
+
+    fn layout() -> FIFOLayout {
+        let sensors = FIFOSensors::ACCEL | FIFOSensors::GYRO_X | FIFOSensors::GYRO_Y | FIFOSensors::GYRO_Z;
+        FIFOLayout::from_fifo_register(sensors.bits())
+    }
+
+    fn samples() -> [u8; 24] {
+        let values: [i16; 12] = [1, -2, 16384, 100, -100, 0, -32768, 32767, 7, 8, 9, -10];
+        let mut bytes = [0u8; 24];
+
+        for (i, value) in values.iter().enumerate() {
+            bytes[i * 2..i * 2 + 2].copy_from_slice(&value.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let layout = layout();
+        let header = BatchHeader::new(&layout, 7, 42, 100, 1_000_000);
+        let mut output = [0u8; 64];
+        let written = encode_batch(&header, &samples(), &mut output).unwrap();
+        let batch = decode_batch(&output[..written]).unwrap();
+
+        assert_eq!(written, encoded_len(&layout, 2));
+        assert_eq!(batch.header, header.sample_count(2));
+        assert_eq!(batch.layout().sample_size, 12);
+        assert_eq!(batch.raw_samples(), &samples());
+        assert_eq!(batch.sample_timestamp_us(1), Some(1_010_000));
+
+        let decoded: [[Option<i16>; 3]; 2] = {
+            let mut decoded = [[None; 3]; 2];
+
+            for (i, sample) in batch.samples().enumerate() {
+                decoded[i] = [
+                    sample.get_value(FIFOEntryType::AccelZ),
+                    sample.get_value(FIFOEntryType::GyroX),
+                    sample.get_value(FIFOEntryType::Temp),
+                ];
+            }
+
+            decoded
+        };
+
+        assert_eq!(decoded[0], [Some(16384), Some(100), None]);
+        assert_eq!(decoded[1], [Some(7), Some(8), None]);
+    }
+
+    #[test]
+    fn encode_errors() {
+        let header = BatchHeader::new(&layout(), 7, 42, 100, 0);
+        let mut output = [0u8; 32];
+
+        assert_eq!(
+            encode_batch(&header, &samples()[..13], &mut output),
+            Err(CodecError::LengthMismatch { expected: 12, actual: 13 })
+        );
+        assert_eq!(
+            encode_batch(&header, &samples(), &mut output),
+            Err(CodecError::BufferTooSmall { needed: 44, available: 32 })
+        );
+        assert_eq!(
+            encode_batch(&BatchHeader { fifo_en: 0, ..header }, &samples(), &mut output),
+            Err(CodecError::EmptyLayout)
+        );
+
+        let accel = FIFOLayout::from_fifo_register(FIFOSensors::ACCEL.bits());
+        let too_many = [0u8; 6 * (u16::MAX as usize + 1)];
+
+        assert_eq!(
+            encode_batch(&BatchHeader::new(&accel, 7, 42, 100, 0), &too_many, &mut output),
+            Err(CodecError::LengthMismatch {
+                expected: 6 * u16::MAX as usize,
+                actual: too_many.len()
+            })
+        );
+    }
+
+    #[test]
+    fn timestamp_overflow() {
+        let header = BatchHeader::new(&layout(), 7, 42, 100, u64::MAX - 5_000);
+        let mut output = [0u8; 64];
+        let written = encode_batch(&header, &samples(), &mut output).unwrap();
+        let batch = decode_batch(&output[..written]).unwrap();
+
+        assert_eq!(batch.sample_timestamp_us(0), Some(u64::MAX - 5_000));
+        assert_eq!(batch.sample_timestamp_us(1), None);
+        assert_eq!(batch.sample_timestamp_us(usize::MAX), None);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let header = BatchHeader::new(&layout(), 7, 42, 100, 0);
+        let mut output = [0u8; 64];
+        let written = encode_batch(&header, &samples(), &mut output).unwrap();
+
+        assert!(matches!(
+            decode_batch(&output[..HEADER_SIZE - 1]).err(),
+            Some(CodecError::BufferTooSmall { .. })
+        ));
+        assert_eq!(
+            decode_batch(&output[..written - 1]).err(),
+            Some(CodecError::LengthMismatch { expected: 24, actual: 23 })
+        );
+
+        output[0] = 0xFF;
+
+        assert_eq!(decode_batch(&output).err(), Some(CodecError::UnsupportedVersion(0xFF)));
+    }
+}
@@ -1,6 +1,16 @@
+use crate::filter::{FilterSpec, Replacement};
+use crate::geo::GeoCoord;
+use crate::math;
+use crate::orientation::Orientation;
+use crate::response::InstrumentResponse;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
 
+/// Position in a local plane, `x` east and `y` north in km, see [`LocalFrame`](crate::geo::LocalFrame).
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Coord {
     pub x: f32,
@@ -11,12 +21,83 @@ pub struct Coord {
 pub struct SensorConfig {
     pub id: u64,
     pub name: String,
+    /// Required unless the sensor has a `location`.
+    #[serde(default = "unplaced")]
     pub coord: Coord,
+    /// Geographic position, replaces `coord` by its projection into the frame of the sensors file, see
+    /// [`SensorsFile::frame`](crate::utils::SensorsFile::frame).
+    #[serde(default)]
+    pub location: Option<GeoCoord>,
+    /// Nominal sample rate in Hz, required by the frequency based filters.
+    #[serde(default)]
+    pub sample_rate: Option<f64>,
+    /// Number of filtered readings kept by the sensor.
+    #[serde(default)]
+    pub capacity: Option<usize>,
+    /// Processing pipeline, the application default is used when empty.
+    #[serde(default)]
+    pub filters: Vec<FilterSpec>,
+    /// Converts raw counts into physical units before filtering, readings stay in counts when unset.
+    #[serde(default)]
+    pub response: Option<InstrumentResponse>,
+    /// Mounting of the sensor, samples are rotated into East, North and vertical when set.
+    #[serde(default)]
+    pub orientation: Option<Orientation>,
+    /// Channels delivered by the sensor.
+    #[serde(default = "all_channels")]
+    pub channels: Vec<Channel>,
+    /// Code of the network the sensor belongs to.
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Where the samples come from, the application default is used when unset.
+    #[serde(default)]
+    pub source: Option<SourceConfig>,
+    /// Id of the sensor on skju_server when forwarded there, the sensor id is used when unset.
+    #[serde(default)]
+    pub server_id: Option<i32>,
 }
 
-#[derive(Clone, Debug)]
+/// Input of a sensor. Text sources deliver `e;n;z;timestamp` or `value;timestamp` lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SourceConfig {
+    /// Follows a file as it grows, `{id}` in the path is replaced by the sensor id.
+    File {
+        path: String,
+    },
+    Stdin,
+    /// Lines received as datagrams on the bound address.
+    Udp {
+        bind: String,
+    },
+    /// Lines read from a connection to the address.
+    Tcp {
+        address: String,
+    },
+    /// Live readings of the sensor published by the server.
+    WebSocket {
+        url: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Channel {
+    E,
+    N,
+    Z,
+}
+
+/// Ground motion sample along the East, North and vertical axes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub e: f64,
+    pub n: f64,
+    pub z: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct SensorData {
-    pub value: f64,
+    pub value: Sample,
     pub timestamp: u128,
 }
 
@@ -25,17 +106,147 @@ pub struct SensorOutput {
     pub sensor_id: u64,
     pub sensor_name: String,
     pub sensor_coord: Coord,
-    pub value: f64,
+    pub value: Sample,
     pub timestamp: u128,
 }
 
 pub struct FilterContext<'a> {
+    pub channel: Channel,
     pub readings: &'a VecDeque<SensorData>,
     pub raw_value: f64,
     pub timestamp: u128,
     pub capacity: usize,
 }
 
-pub trait LowPassFilter {
+pub trait Filter {
     fn apply(&mut self, context: &FilterContext) -> f64;
+
+    /// Moves the readings replaced by spike rejection stages since the last call into `replacements`.
+    fn take_replacements(&mut self, _replacements: &mut Vec<Replacement>) {}
+}
+
+pub trait LowPassFilter: Filter {}
+
+impl SensorConfig {
+    pub fn new(id: u64, name: &str, coord: Coord) -> SensorConfig {
+        SensorConfig {
+            id,
+            name: name.into(),
+            coord,
+            location: None,
+            sample_rate: None,
+            capacity: None,
+            filters: Vec::new(),
+            response: None,
+            orientation: None,
+            channels: all_channels(),
+            network: None,
+            source: None,
+            server_id: None,
+        }
+    }
+}
+
+/// Coord of sensors which are only given by their location until they are projected.
+fn unplaced() -> Coord {
+    Coord { x: f32::NAN, y: f32::NAN }
+}
+
+fn all_channels() -> Vec<Channel> {
+    Channel::ALL.to_vec()
+}
+
+impl Channel {
+    pub const ALL: [Channel; 3] = [Channel::E, Channel::N, Channel::Z];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Channel::E => 0,
+            Channel::N => 1,
+            Channel::Z => 2,
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::E => write!(formatter, "E"),
+            Channel::N => write!(formatter, "N"),
+            Channel::Z => write!(formatter, "Z"),
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = String;
+
+    fn from_str(channel: &str) -> Result<Self, Self::Err> {
+        match channel {
+            "E" | "e" => Ok(Channel::E),
+            "N" | "n" => Ok(Channel::N),
+            "Z" | "z" => Ok(Channel::Z),
+            _ => Err(alloc::format!("Unknown channel: {channel}")),
+        }
+    }
+}
+
+impl Sample {
+    pub fn new(e: f64, n: f64, z: f64) -> Sample {
+        Sample { e, n, z }
+    }
+
+    /// Single component reading, as produced by the legacy scalar sensors.
+    pub fn vertical(z: f64) -> Sample {
+        Sample { e: 0.0, n: 0.0, z }
+    }
+
+    pub fn get(&self, channel: Channel) -> f64 {
+        match channel {
+            Channel::E => self.e,
+            Channel::N => self.n,
+            Channel::Z => self.z,
+        }
+    }
+
+    pub fn set(&mut self, channel: Channel, value: f64) {
+        match channel {
+            Channel::E => self.e = value,
+            Channel::N => self.n = value,
+            Channel::Z => self.z = value,
+        }
+    }
+
+    pub fn magnitude(&self) -> f64 {
+        math::sqrt(self.e * self.e + self.n * self.n + self.z * self.z)
+    }
+
+    pub fn horizontal(&self) -> f64 {
+        math::hypot(self.e, self.n)
+    }
+}
+
+impl fmt::Display for Sample {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "E:{} N:{} Z:{}", self.e, self.n, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Sample};
+
+    #[test]
+    fn sample_components() {
+        let mut sample = Sample::new(3.0, 4.0, 12.0);
+
+        assert_eq!(sample.horizontal(), 5.0);
+        assert_eq!(sample.magnitude(), 13.0);
+
+        sample.set(Channel::N, -4.0);
+
+        assert_eq!(sample.get(Channel::N), -4.0);
+        assert_eq!(sample.horizontal(), 5.0);
+        assert_eq!(Sample::vertical(2.0).magnitude(), 2.0);
+    }
 }
@@ -3,3 +3,6 @@ pub const P_WAVE: f64 = 6.0;
 
 /// S-Wave speed km/S
 pub const S_WAVE: f64 = 3.5;
+
+/// Standard gravity m/S², accelerometers report in multiples of it
+pub const GRAVITY: f64 = 9.80665;
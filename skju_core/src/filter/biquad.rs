@@ -0,0 +1,160 @@
+use crate::common::{Filter, FilterContext};
+use crate::math;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterDesignError {
+    InvalidOrder(u8),
+    InvalidFrequency(f64),
+    InvalidBand { low: f64, high: f64 },
+    InvalidQuality(f64),
+    MissingSampleRate,
+}
+
+impl fmt::Display for FilterDesignError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterDesignError::InvalidOrder(order) => write!(formatter, "Invalid filter order: {order}"),
+            FilterDesignError::InvalidFrequency(frequency) => {
+                write!(formatter, "Frequency {frequency} Hz must be between 0 and Nyquist")
+            }
+            FilterDesignError::InvalidBand { low, high } => write!(formatter, "Invalid band: {low} Hz - {high} Hz"),
+            FilterDesignError::InvalidQuality(q) => write!(formatter, "Invalid quality factor: {q}"),
+            FilterDesignError::MissingSampleRate => write!(formatter, "Filter design requires a sample rate"),
+        }
+    }
+}
+
+/// Second order section in transposed direct form II, coefficients are normalized by `a0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    pub fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Biquad {
+        Biquad { b0, b1, b2, a1, a2, z1: 0.0, z2: 0.0 }
+    }
+
+    pub fn low_pass(cutoff: f64, quality: f64, sample_rate: f64) -> Biquad {
+        let (cos_w0, alpha) = Self::prewarp(cutoff, quality, sample_rate);
+        let b1 = 1.0 - cos_w0;
+
+        Self::normalized(b1 / 2.0, b1, b1 / 2.0, 1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha)
+    }
+
+    pub fn high_pass(cutoff: f64, quality: f64, sample_rate: f64) -> Biquad {
+        let (cos_w0, alpha) = Self::prewarp(cutoff, quality, sample_rate);
+        let b0 = (1.0 + cos_w0) / 2.0;
+
+        Self::normalized(b0, -2.0 * b0, b0, 1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha)
+    }
+
+    pub fn notch(frequency: f64, quality: f64, sample_rate: f64) -> Biquad {
+        let (cos_w0, alpha) = Self::prewarp(frequency, quality, sample_rate);
+
+        Self::normalized(1.0, -2.0 * cos_w0, 1.0, 1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha)
+    }
+
+    /// First order section packed into a biquad (`b2 = a2 = 0`).
+    pub fn first_order_low_pass(cutoff: f64, sample_rate: f64) -> Biquad {
+        let k = math::tan(PI * cutoff / sample_rate);
+
+        Self::normalized(k, k, 0.0, 1.0 + k, k - 1.0, 0.0)
+    }
+
+    pub fn first_order_high_pass(cutoff: f64, sample_rate: f64) -> Biquad {
+        let k = math::tan(PI * cutoff / sample_rate);
+
+        Self::normalized(1.0, -1.0, 0.0, 1.0 + k, k - 1.0, 0.0)
+    }
+
+    pub fn process(&mut self, input: f64) -> f64 {
+        let output = self.b0 * input + self.z1;
+
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+
+        output
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    /// Magnitude of the transfer function at `frequency`.
+    pub fn gain(&self, frequency: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * PI * frequency / sample_rate;
+        let (sin_w, cos_w) = math::sin_cos(w);
+        let (sin_2w, cos_2w) = math::sin_cos(2.0 * w);
+
+        let num_re = self.b0 + self.b1 * cos_w + self.b2 * cos_2w;
+        let num_im = -self.b1 * sin_w - self.b2 * sin_2w;
+        let den_re = 1.0 + self.a1 * cos_w + self.a2 * cos_2w;
+        let den_im = -self.a1 * sin_w - self.a2 * sin_2w;
+
+        math::hypot(num_re, num_im) / math::hypot(den_re, den_im)
+    }
+
+    fn prewarp(frequency: f64, quality: f64, sample_rate: f64) -> (f64, f64) {
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let (sin_w0, cos_w0) = math::sin_cos(w0);
+
+        (cos_w0, sin_w0 / (2.0 * quality))
+    }
+
+    fn normalized(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Biquad {
+        Biquad::new(b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BiquadCascade {
+    pub sections: Vec<Biquad>,
+}
+
+impl BiquadCascade {
+    pub fn new(sections: Vec<Biquad>) -> BiquadCascade {
+        BiquadCascade { sections }
+    }
+
+    pub fn process(&mut self, input: f64) -> f64 {
+        self.sections
+            .iter_mut()
+            .fold(input, |value, section| section.process(value))
+    }
+
+    pub fn reset(&mut self) {
+        self.sections.iter_mut().for_each(Biquad::reset);
+    }
+
+    pub fn gain(&self, frequency: f64, sample_rate: f64) -> f64 {
+        self.sections
+            .iter()
+            .map(|section| section.gain(frequency, sample_rate))
+            .product()
+    }
+}
+
+impl Filter for BiquadCascade {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.process(context.raw_value)
+    }
+}
+
+pub(crate) fn validate_frequency(frequency: f64, sample_rate: f64) -> Result<(), FilterDesignError> {
+    if frequency > 0.0 && frequency < sample_rate / 2.0 {
+        Ok(())
+    } else {
+        Err(FilterDesignError::InvalidFrequency(frequency))
+    }
+}
@@ -0,0 +1,209 @@
+use crate::common::{Filter, FilterContext, LowPassFilter};
+use crate::filter::biquad::{Biquad, BiquadCascade, FilterDesignError, validate_frequency};
+use crate::math;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+pub const MAX_BUTTERWORTH_ORDER: u8 = 16;
+
+/// Butterworth low-pass of the given order, realized as cascaded biquads.
+#[derive(Debug, Clone)]
+pub struct ButterworthLowPass {
+    pub cascade: BiquadCascade,
+}
+
+#[derive(Debug, Clone)]
+pub struct ButterworthHighPass {
+    pub cascade: BiquadCascade,
+}
+
+/// High-pass at `low_cutoff` followed by a low-pass at `high_cutoff`, both of the given order.
+#[derive(Debug, Clone)]
+pub struct ButterworthBandPass {
+    pub cascade: BiquadCascade,
+}
+
+impl ButterworthLowPass {
+    pub fn new(order: u8, cutoff: f64, sample_rate: f64) -> Result<ButterworthLowPass, FilterDesignError> {
+        validate_order(order)?;
+        validate_frequency(cutoff, sample_rate)?;
+
+        let cascade = design(
+            order,
+            cutoff,
+            sample_rate,
+            Biquad::low_pass,
+            Biquad::first_order_low_pass,
+        );
+
+        Ok(ButterworthLowPass { cascade })
+    }
+}
+
+impl ButterworthHighPass {
+    pub fn new(order: u8, cutoff: f64, sample_rate: f64) -> Result<ButterworthHighPass, FilterDesignError> {
+        validate_order(order)?;
+        validate_frequency(cutoff, sample_rate)?;
+
+        let cascade = design(
+            order,
+            cutoff,
+            sample_rate,
+            Biquad::high_pass,
+            Biquad::first_order_high_pass,
+        );
+
+        Ok(ButterworthHighPass { cascade })
+    }
+}
+
+impl ButterworthBandPass {
+    pub fn new(
+        order: u8,
+        low_cutoff: f64,
+        high_cutoff: f64,
+        sample_rate: f64,
+    ) -> Result<ButterworthBandPass, FilterDesignError> {
+        if low_cutoff >= high_cutoff {
+            return Err(FilterDesignError::InvalidBand { low: low_cutoff, high: high_cutoff });
+        }
+
+        let high_pass = ButterworthHighPass::new(order, low_cutoff, sample_rate)?;
+        let low_pass = ButterworthLowPass::new(order, high_cutoff, sample_rate)?;
+        let mut sections = high_pass.cascade.sections;
+
+        sections.extend(low_pass.cascade.sections);
+
+        Ok(ButterworthBandPass { cascade: BiquadCascade::new(sections) })
+    }
+}
+
+impl LowPassFilter for ButterworthLowPass {}
+
+impl Filter for ButterworthLowPass {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.cascade.apply(context)
+    }
+}
+
+impl Filter for ButterworthHighPass {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.cascade.apply(context)
+    }
+}
+
+impl Filter for ButterworthBandPass {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.cascade.apply(context)
+    }
+}
+
+fn validate_order(order: u8) -> Result<(), FilterDesignError> {
+    if (1..=MAX_BUTTERWORTH_ORDER).contains(&order) {
+        Ok(())
+    } else {
+        Err(FilterDesignError::InvalidOrder(order))
+    }
+}
+
+fn design(
+    order: u8,
+    cutoff: f64,
+    sample_rate: f64,
+    second_order: fn(f64, f64, f64) -> Biquad,
+    first_order: fn(f64, f64) -> Biquad,
+) -> BiquadCascade {
+    let order = order as usize;
+    let mut sections: Vec<Biquad> = (0..order / 2)
+        .map(|k| {
+            // angle between the k-th conjugate pole pair and the negative real axis
+            let angle = PI * (order - 1 - 2 * k) as f64 / (2 * order) as f64;
+            let quality = 1.0 / (2.0 * math::cos(angle));
+
+            second_order(cutoff, quality, sample_rate)
+        })
+        .collect();
+
+    if order % 2 == 1 {
+        sections.push(first_order(cutoff, sample_rate));
+    }
+
+    BiquadCascade::new(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::{ButterworthBandPass, ButterworthHighPass, ButterworthLowPass, FilterDesignError};
+    use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    const SAMPLE_RATE: f64 = 100.0;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "expected {expected} ± {tolerance}, got {actual}"
+        );
+    }
+
+    fn sine_amplitude(filter: &mut impl FnMut(f64) -> f64, frequency: f64) -> f64 {
+        let samples = 4000;
+
+        (0..samples)
+            .map(|i| filter((2.0 * PI * frequency * i as f64 / SAMPLE_RATE).sin()))
+            .skip(samples / 2)
+            .fold(0.0, |peak: f64, value| peak.max(value.abs()))
+    }
+
+    #[test]
+    fn rejects_invalid_designs() {
+        assert_eq!(
+            ButterworthLowPass::new(0, 10.0, SAMPLE_RATE).err(),
+            Some(FilterDesignError::InvalidOrder(0))
+        );
+        assert_eq!(
+            ButterworthHighPass::new(2, 50.0, SAMPLE_RATE).err(),
+            Some(FilterDesignError::InvalidFrequency(50.0))
+        );
+        assert_eq!(
+            ButterworthBandPass::new(2, 20.0, 0.1, SAMPLE_RATE).err(),
+            Some(FilterDesignError::InvalidBand { low: 20.0, high: 0.1 })
+        );
+    }
+
+    #[test]
+    fn cutoff_is_half_power_for_any_order() {
+        for order in 1..=8 {
+            let low_pass = ButterworthLowPass::new(order, 10.0, SAMPLE_RATE).unwrap();
+            let high_pass = ButterworthHighPass::new(order, 10.0, SAMPLE_RATE).unwrap();
+
+            assert_eq!(low_pass.cascade.sections.len(), (order as usize).div_ceil(2));
+            assert_close(low_pass.cascade.gain(10.0, SAMPLE_RATE), FRAC_1_SQRT_2, 1e-9);
+            assert_close(high_pass.cascade.gain(10.0, SAMPLE_RATE), FRAC_1_SQRT_2, 1e-9);
+            assert_close(low_pass.cascade.gain(0.0, SAMPLE_RATE), 1.0, 1e-9);
+            assert_close(high_pass.cascade.gain(SAMPLE_RATE / 2.0, SAMPLE_RATE), 1.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn band_pass_keeps_seismic_band() {
+        let mut filter = ButterworthBandPass::new(4, 0.1, 20.0, SAMPLE_RATE).unwrap();
+
+        assert_close(filter.cascade.gain(2.0, SAMPLE_RATE), 1.0, 1e-3);
+        assert!(filter.cascade.gain(0.01, SAMPLE_RATE) < 1e-3);
+        assert!(filter.cascade.gain(40.0, SAMPLE_RATE) < 0.05);
+
+        let mut process = |value| filter.cascade.process(value);
+        assert_close(sine_amplitude(&mut process, 2.0), 1.0, 0.01);
+    }
+
+    #[test]
+    fn high_pass_removes_offset() {
+        let mut filter = ButterworthHighPass::new(2, 0.5, SAMPLE_RATE).unwrap();
+        let last = (0..2000)
+            .map(|_| filter.cascade.process(3.0))
+            .last()
+            .unwrap();
+
+        assert_close(last, 0.0, 1e-6);
+    }
+}
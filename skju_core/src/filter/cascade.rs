@@ -0,0 +1,40 @@
+use crate::common::{Filter, FilterContext, LowPassFilter};
+use crate::filter::Replacement;
+use alloc::vec::Vec;
+
+/// `first` followed by `second`, the second stage sees the output of the first one as its raw value.
+/// `context.readings` holds the outputs of the whole cascade, stages keep their own state.
+#[derive(Debug, Clone)]
+pub struct Cascade<A: Filter, B: Filter> {
+    pub first: A,
+    pub second: B,
+}
+
+pub trait FilterExt: Filter + Sized {
+    fn then<B: Filter>(self, next: B) -> Cascade<Self, B> {
+        Cascade { first: self, second: next }
+    }
+}
+
+impl<T: Filter> FilterExt for T {}
+
+impl<A: Filter, B: LowPassFilter> LowPassFilter for Cascade<A, B> {}
+
+impl<A: Filter, B: Filter> Filter for Cascade<A, B> {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        let raw_value = self.first.apply(context);
+
+        self.second.apply(&FilterContext {
+            channel: context.channel,
+            readings: context.readings,
+            raw_value,
+            timestamp: context.timestamp,
+            capacity: context.capacity,
+        })
+    }
+
+    fn take_replacements(&mut self, replacements: &mut Vec<Replacement>) {
+        self.first.take_replacements(replacements);
+        self.second.take_replacements(replacements);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::common::{Filter, FilterContext};
+use crate::filter::{
+    ButterworthBandPass, ButterworthHighPass, ButterworthLowPass, Demean, Detrend, FilterDesignError, HampelFilter,
+    MultiPoleExponentialLowPass, NotchFilter, Replacement, SinglePoleExponentialLowPass,
+};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Filter which can be boxed into a [`FilterChain`].
+pub trait ChainStage: Filter + fmt::Debug + Send {
+    fn clone_box(&self) -> Box<dyn ChainStage>;
+}
+
+/// Filters applied one after another, every stage sees the output of the previous one as its raw value.
+/// `context.readings` holds the outputs of the whole chain, stages keep their own state.
+#[derive(Debug, Default)]
+pub struct FilterChain {
+    stages: Vec<Box<dyn ChainStage>>,
+}
+
+/// Serializable description of a chain stage, frequencies are in Hz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterSpec {
+    Demean {
+        window: usize,
+    },
+    Detrend {
+        window: usize,
+    },
+    Hampel {
+        window: usize,
+        threshold: f64,
+        #[serde(default)]
+        min_deviation: f64,
+    },
+    SinglePole {
+        smoothing: f32,
+    },
+    MultiPole {
+        stages: u8,
+        smoothing: f32,
+    },
+    LowPass {
+        order: u8,
+        cutoff: f64,
+    },
+    HighPass {
+        order: u8,
+        cutoff: f64,
+    },
+    BandPass {
+        order: u8,
+        low: f64,
+        high: f64,
+    },
+    Notch {
+        frequency: f64,
+        quality: f64,
+    },
+}
+
+impl<T: Filter + Clone + fmt::Debug + Send + 'static> ChainStage for T {
+    fn clone_box(&self) -> Box<dyn ChainStage> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for FilterChain {
+    fn clone(&self) -> Self {
+        FilterChain {
+            stages: self.stages.iter().map(|stage| stage.clone_box()).collect(),
+        }
+    }
+}
+
+impl FilterChain {
+    pub fn new() -> FilterChain {
+        FilterChain::default()
+    }
+
+    pub fn then<T: ChainStage + 'static>(mut self, stage: T) -> FilterChain {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Builds the stages described by `specs`, the frequency based ones require `sample_rate`.
+    pub fn from_specs(specs: &[FilterSpec], sample_rate: Option<f64>) -> Result<FilterChain, FilterDesignError> {
+        let rate = || sample_rate.ok_or(FilterDesignError::MissingSampleRate);
+
+        specs.iter().try_fold(FilterChain::new(), |chain, spec| {
+            Ok(match *spec {
+                FilterSpec::Demean { window } => chain.then(Demean::new(window)),
+                FilterSpec::Detrend { window } => chain.then(Detrend::new(window)),
+                FilterSpec::Hampel { window, threshold, min_deviation } => {
+                    chain.then(HampelFilter::new(window, threshold).min_deviation(min_deviation))
+                }
+                FilterSpec::SinglePole { smoothing } => chain.then(SinglePoleExponentialLowPass::new(smoothing)),
+                FilterSpec::MultiPole { stages, smoothing } => {
+                    chain.then(MultiPoleExponentialLowPass::try_new(stages, smoothing)?)
+                }
+                FilterSpec::LowPass { order, cutoff } => chain.then(ButterworthLowPass::new(order, cutoff, rate()?)?),
+                FilterSpec::HighPass { order, cutoff } => chain.then(ButterworthHighPass::new(order, cutoff, rate()?)?),
+                FilterSpec::BandPass { order, low, high } => {
+                    chain.then(ButterworthBandPass::new(order, low, high, rate()?)?)
+                }
+                FilterSpec::Notch { frequency, quality } => chain.then(NotchFilter::new(frequency, quality, rate()?)?),
+            })
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Readings replaced by the spike rejection stages since the last call, including nested ones.
+    pub fn drain_replacements(&mut self) -> impl Iterator<Item = Replacement> + '_ {
+        let mut replacements = Vec::new();
+
+        self.take_replacements(&mut replacements);
+        replacements.into_iter()
+    }
+}
+
+impl Filter for FilterChain {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.stages
+            .iter_mut()
+            .fold(context.raw_value, |raw_value, stage| {
+                stage.apply(&FilterContext {
+                    channel: context.channel,
+                    readings: context.readings,
+                    raw_value,
+                    timestamp: context.timestamp,
+                    capacity: context.capacity,
+                })
+            })
+    }
+
+    fn take_replacements(&mut self, replacements: &mut Vec<Replacement>) {
+        self.stages
+            .iter_mut()
+            .for_each(|stage| stage.take_replacements(replacements));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Filter, FilterContext, Sample, SensorData};
+    use crate::filter::{Demean, FilterChain, FilterDesignError, FilterExt, FilterSpec, HampelFilter};
+    use alloc::collections::VecDeque;
+    use alloc::vec;
+
+    #[test]
+    fn builds_from_specs() {
+        let specs: vec::Vec<FilterSpec> = serde_json::from_str(
+            r#"[
+                {"type": "demean", "window": 50},
+                {"type": "hampel", "window": 21, "threshold": 3.0},
+                {"type": "band_pass", "order": 2, "low": 0.5, "high": 20.0}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            specs[1],
+            FilterSpec::Hampel {
+                window: 21,
+                threshold: 3.0,
+                min_deviation: 0.0
+            }
+        );
+        assert_eq!(FilterChain::from_specs(&specs, Some(100.0)).unwrap().len(), 3);
+        assert_eq!(
+            FilterChain::from_specs(&specs, None).err(),
+            Some(FilterDesignError::MissingSampleRate)
+        );
+    }
+
+    #[test]
+    fn runs_stages_in_order_and_reports_glitches() {
+        let specs = [
+            FilterSpec::Hampel {
+                window: 11,
+                threshold: 3.0,
+                min_deviation: 0.1,
+            },
+            FilterSpec::Demean { window: 4 },
+        ];
+        let mut chain = FilterChain::from_specs(&specs, None).unwrap();
+        let readings = VecDeque::new();
+        let mut output = vec![];
+
+        for (i, raw_value) in [1.0, 1.0, 1.0, 1.0, 9.0, 1.0].into_iter().enumerate() {
+            output.push(chain.apply(&FilterContext {
+                channel: Channel::Z,
+                readings: &readings,
+                raw_value,
+                timestamp: i as u128,
+                capacity: 0,
+            }));
+        }
+
+        assert_eq!(output, [0.0; 6]);
+        assert_eq!(
+            chain
+                .drain_replacements()
+                .map(|r| r.timestamp)
+                .collect::<vec::Vec<_>>(),
+            [4]
+        );
+        assert_eq!(chain.drain_replacements().count(), 0);
+    }
+
+    #[test]
+    fn reports_glitches_of_nested_stages() {
+        let nested = HampelFilter::new(11, 3.0)
+            .min_deviation(0.1)
+            .then(Demean::new(4));
+        let mut chain = FilterChain::new().then(nested);
+        let readings = VecDeque::new();
+
+        for (i, raw_value) in [1.0, 1.0, 1.0, 1.0, 9.0, 1.0].into_iter().enumerate() {
+            chain.apply(&FilterContext {
+                channel: Channel::Z,
+                readings: &readings,
+                raw_value,
+                timestamp: i as u128,
+                capacity: 0,
+            });
+        }
+
+        assert_eq!(
+            chain
+                .drain_replacements()
+                .map(|r| r.timestamp)
+                .collect::<vec::Vec<_>>(),
+            [4]
+        );
+    }
+
+    #[test]
+    fn exponential_stages_in_front_of_others() {
+        let specs = [
+            FilterSpec::SinglePole { smoothing: 0.5 },
+            FilterSpec::MultiPole { stages: 2, smoothing: 0.5 },
+            FilterSpec::Demean { window: 4 },
+        ];
+        let mut chain = FilterChain::from_specs(&specs, None).unwrap();
+        let mut stages: vec::Vec<FilterChain> = specs
+            .iter()
+            .map(|spec| FilterChain::from_specs(core::slice::from_ref(spec), None).unwrap())
+            .collect();
+        let mut readings = VecDeque::new();
+        let empty = VecDeque::new();
+
+        for (i, raw_value) in [9.81, 9.9, 9.7, 10.2, 9.81, 9.6].into_iter().enumerate() {
+            let context = |readings, raw_value| FilterContext {
+                channel: Channel::Z,
+                readings,
+                raw_value,
+                timestamp: i as u128,
+                capacity: 10,
+            };
+            let output = chain.apply(&context(&readings, raw_value));
+            // every stage on its own, without any stored readings
+            let expected = stages
+                .iter_mut()
+                .fold(raw_value, |value, stage| stage.apply(&context(&empty, value)));
+
+            assert_eq!(output, expected);
+            readings.push_back(SensorData {
+                value: Sample::vertical(output),
+                timestamp: i as u128,
+            });
+        }
+    }
+}
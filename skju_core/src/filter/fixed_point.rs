@@ -0,0 +1,339 @@
+use super::Biquad;
+use crate::math;
+use core::fmt;
+
+/// Fractional bits of the internal coefficients, Q2.30 covers the `(-2, 2)` feedback range of a biquad.
+const COEFFICIENT_BITS: u32 = 30;
+
+/// Signed fixed point sample in `[-1, 1)` with 15 fractional bits, as delivered by 16 bit ADCs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Q15(pub i16);
+
+/// Signed fixed point sample in `[-1, 1)` with 31 fractional bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Q31(pub i32);
+
+/// Number format of the allocation-free filters meant to run on the sensor node. Fixed point samples are
+/// processed in Q31 with Q2.30 coefficients and a 64 bit accumulator, `f32` samples in single precision.
+pub trait FilterSample: Copy + Default + fmt::Debug {
+    type State: Copy + Default + fmt::Debug;
+    type Coefficient: Copy + Default + fmt::Debug;
+
+    /// Rounds to the nearest representable value, saturating out of range values.
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn coefficient(value: f64) -> Self::Coefficient;
+    fn widen(self) -> Self::State;
+    fn narrow(state: Self::State) -> Self;
+    /// `state + smoothing * (input - state)`
+    fn smooth(state: Self::State, smoothing: Self::Coefficient, input: Self::State) -> Self::State;
+    /// Sum of the products of the coefficients and the states.
+    fn dot<const N: usize>(coefficients: &[Self::Coefficient; N], states: &[Self::State; N]) -> Self::State;
+}
+
+impl Q15 {
+    pub const MIN: Q15 = Q15(i16::MIN);
+    pub const MAX: Q15 = Q15(i16::MAX);
+}
+
+impl Q31 {
+    pub const MIN: Q31 = Q31(i32::MIN);
+    pub const MAX: Q31 = Q31(i32::MAX);
+}
+
+impl FilterSample for f32 {
+    type State = f32;
+    type Coefficient = f32;
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn coefficient(value: f64) -> Self::Coefficient {
+        value as f32
+    }
+
+    fn widen(self) -> Self::State {
+        self
+    }
+
+    fn narrow(state: Self::State) -> Self {
+        state
+    }
+
+    fn smooth(state: f32, smoothing: f32, input: f32) -> f32 {
+        state + smoothing * (input - state)
+    }
+
+    fn dot<const N: usize>(coefficients: &[f32; N], states: &[f32; N]) -> f32 {
+        coefficients
+            .iter()
+            .zip(states)
+            .map(|(coefficient, state)| coefficient * state)
+            .sum()
+    }
+}
+
+impl FilterSample for Q31 {
+    type State = i32;
+    type Coefficient = i32;
+
+    fn from_f64(value: f64) -> Self {
+        Q31(to_fixed(value, 31, i32::MAX as i64) as i32)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u64 << 31) as f64
+    }
+
+    fn coefficient(value: f64) -> Self::Coefficient {
+        to_fixed(value, COEFFICIENT_BITS, i32::MAX as i64) as i32
+    }
+
+    fn widen(self) -> Self::State {
+        self.0
+    }
+
+    fn narrow(state: Self::State) -> Self {
+        Q31(state)
+    }
+
+    fn smooth(state: i32, smoothing: i32, input: i32) -> i32 {
+        let delta = input as i64 - state as i64;
+
+        saturate(state as i64 + round_shift(smoothing as i64 * delta, COEFFICIENT_BITS))
+    }
+
+    fn dot<const N: usize>(coefficients: &[i32; N], states: &[i32; N]) -> i32 {
+        // intermediate sums may wrap as long as the result of a stable filter fits
+        let sum = coefficients
+            .iter()
+            .zip(states)
+            .fold(0i64, |sum, (coefficient, state)| {
+                sum.wrapping_add(*coefficient as i64 * *state as i64)
+            });
+
+        saturate(round_shift(sum, COEFFICIENT_BITS))
+    }
+}
+
+impl FilterSample for Q15 {
+    type State = i32;
+    type Coefficient = i32;
+
+    fn from_f64(value: f64) -> Self {
+        Q15(to_fixed(value, 15, i16::MAX as i64) as i16)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u32 << 15) as f64
+    }
+
+    fn coefficient(value: f64) -> Self::Coefficient {
+        Q31::coefficient(value)
+    }
+
+    fn widen(self) -> Self::State {
+        (self.0 as i32) << 16
+    }
+
+    fn narrow(state: Self::State) -> Self {
+        Q15(round_shift(state as i64, 16).clamp(i16::MIN as i64, i16::MAX as i64) as i16)
+    }
+
+    fn smooth(state: i32, smoothing: i32, input: i32) -> i32 {
+        Q31::smooth(state, smoothing, input)
+    }
+
+    fn dot<const N: usize>(coefficients: &[i32; N], states: &[i32; N]) -> i32 {
+        Q31::dot(coefficients, states)
+    }
+}
+
+/// Cascade of `STAGES` exponential low pass poles, the allocation-free counterpart of
+/// [`super::MultiPoleExponentialLowPass`]. The stages start at the first input.
+#[derive(Debug, Clone)]
+pub struct ExponentialLowPass<T: FilterSample, const STAGES: usize> {
+    smoothing: T::Coefficient,
+    stages: Option<[T::State; STAGES]>,
+}
+
+impl<T: FilterSample, const STAGES: usize> ExponentialLowPass<T, STAGES> {
+    pub fn new(smoothing: f32) -> Self {
+        ExponentialLowPass {
+            smoothing: T::coefficient(smoothing as f64),
+            stages: None,
+        }
+    }
+
+    pub fn process(&mut self, input: T) -> T {
+        let input = input.widen();
+        let stages = self.stages.get_or_insert([input; STAGES]);
+
+        let output = stages.iter_mut().fold(input, |value, stage| {
+            *stage = T::smooth(*stage, self.smoothing, value);
+            *stage
+        });
+
+        T::narrow(output)
+    }
+
+    pub fn reset(&mut self) {
+        self.stages = None;
+    }
+}
+
+/// Biquad in direct form I, which keeps the state in the sample range and suits fixed point arithmetic.
+/// Designed with the `f64` [`Biquad`] constructors and quantized with [`FixedBiquad::from_biquad`].
+#[derive(Debug, Clone, Copy)]
+pub struct FixedBiquad<T: FilterSample> {
+    /// `b0, b1, b2, -a1, -a2`
+    coefficients: [T::Coefficient; 5],
+    /// `x[n-1], x[n-2], y[n-1], y[n-2]`
+    state: [T::State; 4],
+}
+
+impl<T: FilterSample> FixedBiquad<T> {
+    pub fn from_biquad(biquad: &Biquad) -> Self {
+        FixedBiquad {
+            coefficients: [biquad.b0, biquad.b1, biquad.b2, -biquad.a1, -biquad.a2].map(T::coefficient),
+            state: Default::default(),
+        }
+    }
+
+    pub fn process(&mut self, input: T) -> T {
+        let [x1, x2, y1, y2] = self.state;
+        let x0 = input.widen();
+        let output = T::dot(&self.coefficients, &[x0, x1, x2, y1, y2]);
+
+        self.state = [x0, x1, output, y1];
+
+        T::narrow(output)
+    }
+
+    pub fn reset(&mut self) {
+        self.state = Default::default();
+    }
+}
+
+fn to_fixed(value: f64, fractional_bits: u32, max: i64) -> i64 {
+    let scaled = math::round(value * (1u64 << fractional_bits) as f64);
+
+    (scaled as i64).clamp(-max - 1, max)
+}
+
+fn round_shift(value: i64, bits: u32) -> i64 {
+    (value + (1 << (bits - 1))) >> bits
+}
+
+fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Filter, FilterContext};
+    use crate::filter::{Biquad, ExponentialLowPass, FilterSample, FixedBiquad, MultiPoleExponentialLowPass, Q15, Q31};
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const SAMPLE_RATE: f64 = 100.0;
+
+    fn exponential_reference(smoothing: f32, inputs: &[f64]) -> Vec<f64> {
+        let mut filter = MultiPoleExponentialLowPass::new(3, smoothing);
+        let readings = VecDeque::new();
+
+        inputs
+            .iter()
+            .map(|raw_value| {
+                filter.apply(&FilterContext {
+                    channel: Channel::Z,
+                    capacity: 1,
+                    raw_value: *raw_value,
+                    readings: &readings,
+                    timestamp: 0,
+                })
+            })
+            .collect()
+    }
+
+    fn max_exponential_error<T: FilterSample>(smoothing: f32, inputs: &[f64]) -> f64 {
+        let mut filter = ExponentialLowPass::<T, 3>::new(smoothing);
+
+        exponential_reference(smoothing, inputs)
+            .iter()
+            .zip(inputs)
+            .map(|(expected, input)| (filter.process(T::from_f64(*input)).to_f64() - expected).abs())
+            .fold(0.0, f64::max)
+    }
+
+    fn max_biquad_error<T: FilterSample>(biquad: Biquad, inputs: &[f64]) -> f64 {
+        let mut reference = biquad;
+        let mut filter = FixedBiquad::<T>::from_biquad(&biquad);
+
+        inputs
+            .iter()
+            .map(|input| (filter.process(T::from_f64(*input)).to_f64() - reference.process(*input)).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn converts_and_saturates() {
+        assert_eq!(Q15::from_f64(0.5), Q15(1 << 14));
+        assert_eq!(Q15::from_f64(1.0), Q15::MAX);
+        assert_eq!(Q15::from_f64(-3.0), Q15::MIN);
+        assert_eq!(Q31::from_f64(-1.0), Q31::MIN);
+        assert_eq!(Q31::from_f64(-0.25).to_f64(), -0.25);
+    }
+
+    #[test]
+    fn exponential_state_advances() {
+        let mut filter = ExponentialLowPass::<Q15, 1>::new(0.5);
+
+        assert_eq!(filter.process(Q15::from_f64(0.0)), Q15(0));
+        assert_eq!(filter.process(Q15::from_f64(0.5)).to_f64(), 0.25);
+        assert_eq!(filter.process(Q15::from_f64(0.5)).to_f64(), 0.375);
+
+        filter.reset();
+        assert_eq!(filter.process(Q15::from_f64(0.5)).to_f64(), 0.5);
+    }
+
+    proptest! {
+        #[test]
+        fn conversions_round_to_nearest(value in -1.0f64..0.999) {
+            prop_assert!((Q15::from_f64(value).to_f64() - value).abs() <= 0.5 / (1 << 15) as f64);
+            prop_assert!((Q31::from_f64(value).to_f64() - value).abs() <= 0.5 / (1u64 << 31) as f64);
+        }
+
+        #[test]
+        fn exponential_tracks_reference(smoothing in 0.05f32..1.0, inputs in vec(-0.9f64..0.9, 1..300)) {
+            prop_assert!(max_exponential_error::<f32>(smoothing, &inputs) < 1e-5);
+            prop_assert!(max_exponential_error::<Q31>(smoothing, &inputs) < 1e-7);
+            prop_assert!(max_exponential_error::<Q15>(smoothing, &inputs) < 1e-4);
+        }
+
+        #[test]
+        fn biquad_tracks_reference(
+            cutoff in 2.0f64..20.0,
+            quality in 0.5f64..1.0,
+            high_pass: bool,
+            inputs in vec(-0.4f64..0.4, 1..300),
+        ) {
+            let biquad = if high_pass {
+                Biquad::high_pass(cutoff, quality, SAMPLE_RATE)
+            } else {
+                Biquad::low_pass(cutoff, quality, SAMPLE_RATE)
+            };
+
+            prop_assert!(max_biquad_error::<f32>(biquad, &inputs) < 1e-4);
+            prop_assert!(max_biquad_error::<Q31>(biquad, &inputs) < 1e-6);
+            prop_assert!(max_biquad_error::<Q15>(biquad, &inputs) < 2e-4);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::common::{Channel, Filter, FilterContext};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Scales the median absolute deviation to the standard deviation of normally distributed noise.
+const MAD_SCALE: f64 = 1.4826;
+const MIN_WINDOW: usize = 3;
+/// Replacements kept until drained, older ones are dropped first.
+pub const MAX_PENDING_REPLACEMENTS: usize = 256;
+
+/// Reading rejected as an outlier together with the value it was replaced by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Replacement {
+    pub channel: Channel,
+    pub timestamp: u128,
+    pub raw_value: f64,
+    pub replaced_by: f64,
+}
+
+/// Hampel identifier over the trailing `window` raw values. A value further than `threshold` scaled
+/// MADs from the window median is replaced by the median and reported. Spikes shorter than half the
+/// window are rejected, longer level changes are let through once they dominate the window.
+#[derive(Debug, Clone)]
+pub struct HampelFilter {
+    pub window: usize,
+    pub threshold: f64,
+    /// Deviations up to this value are never rejected, guards flat signals with a MAD of zero.
+    pub min_deviation: f64,
+    values: VecDeque<f64>,
+    scratch: Vec<f64>,
+    replacements: VecDeque<Replacement>,
+}
+
+impl HampelFilter {
+    pub fn new(window: usize, threshold: f64) -> HampelFilter {
+        let window = window.max(MIN_WINDOW);
+
+        HampelFilter {
+            window,
+            threshold,
+            min_deviation: 0.0,
+            values: VecDeque::with_capacity(window),
+            scratch: Vec::with_capacity(window),
+            replacements: VecDeque::new(),
+        }
+    }
+
+    pub fn min_deviation(mut self, min_deviation: f64) -> HampelFilter {
+        self.min_deviation = min_deviation;
+        self
+    }
+
+    /// Readings replaced since the last call, oldest first.
+    pub fn drain_replacements(&mut self) -> impl Iterator<Item = Replacement> + '_ {
+        self.replacements.drain(..)
+    }
+
+    fn median_of_scratch(&mut self) -> f64 {
+        let middle = self.scratch.len() / 2;
+        let (_, median, _) = self.scratch.select_nth_unstable_by(middle, f64::total_cmp);
+
+        *median
+    }
+}
+
+impl Filter for HampelFilter {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        let raw_value = context.raw_value;
+        let mut output = raw_value;
+
+        if self.values.len() >= MIN_WINDOW {
+            self.scratch.clear();
+            self.scratch.extend(self.values.iter());
+
+            let median = self.median_of_scratch();
+
+            self.scratch
+                .iter_mut()
+                .for_each(|value| *value = (*value - median).abs());
+
+            let mad = MAD_SCALE * self.median_of_scratch();
+            let deviation = (raw_value - median).abs();
+
+            if deviation > self.min_deviation && deviation > self.threshold * mad {
+                output = median;
+
+                if self.replacements.len() == MAX_PENDING_REPLACEMENTS {
+                    self.replacements.pop_front();
+                }
+
+                self.replacements.push_back(Replacement {
+                    channel: context.channel,
+                    timestamp: context.timestamp,
+                    raw_value,
+                    replaced_by: median,
+                });
+            }
+        }
+
+        if self.values.len() == self.window {
+            self.values.pop_front();
+        }
+
+        self.values.push_back(raw_value);
+
+        output
+    }
+
+    fn take_replacements(&mut self, replacements: &mut Vec<Replacement>) {
+        replacements.extend(self.drain_replacements());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Filter, FilterContext};
+    use crate::filter::{FilterExt, HampelFilter, Replacement, SinglePoleExponentialLowPass};
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+
+    fn run(filter: &mut impl Filter, values: &[f64]) -> Vec<f64> {
+        let readings = VecDeque::new();
+
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &raw_value)| {
+                filter.apply(&FilterContext {
+                    channel: Channel::Z,
+                    readings: &readings,
+                    raw_value,
+                    timestamp: i as u128,
+                    capacity: 0,
+                })
+            })
+            .collect()
+    }
+
+    fn noise(length: usize) -> Vec<f64> {
+        (0..length)
+            .map(|i| ((i * 7919) % 13) as f64 / 1000.0)
+            .collect()
+    }
+
+    #[test]
+    fn rejects_spikes_and_reports_them() {
+        let mut values = noise(60);
+        values[30..36].iter_mut().for_each(|value| *value += 2.5);
+
+        let mut filter = HampelFilter::new(21, 3.0);
+        let output = run(&mut filter, &values);
+        let replacements: Vec<Replacement> = filter.drain_replacements().collect();
+
+        assert!(output.iter().all(|value| value.abs() < 0.02));
+        assert_eq!(
+            replacements.iter().map(|r| r.timestamp).collect::<Vec<_>>(),
+            [30, 31, 32, 33, 34, 35]
+        );
+        assert_eq!(replacements[0].raw_value, values[30]);
+        assert_eq!(filter.drain_replacements().count(), 0);
+    }
+
+    #[test]
+    fn accepts_level_changes() {
+        let mut values = noise(60);
+        values[20..].iter_mut().for_each(|value| *value += 1.0);
+
+        let output = run(&mut HampelFilter::new(9, 3.0), &values);
+
+        assert!(output[20..25].iter().all(|value| value.abs() < 0.02));
+        assert!(output[30..].iter().all(|value| *value > 0.99));
+    }
+
+    #[test]
+    fn composes_in_front_of_low_pass() {
+        let mut values = noise(40);
+        values[25] = -3.0;
+
+        let mut filter = HampelFilter::new(11, 3.0).then(SinglePoleExponentialLowPass::new(1.0));
+        let output = run(&mut filter, &values);
+
+        assert!(output[25].abs() < 0.02);
+        assert_eq!(filter.first.drain_replacements().count(), 1);
+    }
+}
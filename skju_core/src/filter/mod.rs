@@ -1,5 +1,21 @@
+mod biquad;
+mod butterworth;
+mod cascade;
+mod chain;
+mod fixed_point;
+mod hampel;
 mod multi_pole_exp_filter;
+mod notch;
 mod single_pole_exp_filter;
+mod trend;
 
+pub use biquad::*;
+pub use butterworth::*;
+pub use cascade::*;
+pub use chain::*;
+pub use fixed_point::*;
+pub use hampel::*;
 pub use multi_pole_exp_filter::*;
+pub use notch::*;
 pub use single_pole_exp_filter::*;
+pub use trend::*;
@@ -1,42 +1,112 @@
-use crate::common::{FilterContext, LowPassFilter};
+use crate::common::{Filter, FilterContext, LowPassFilter};
+use crate::filter::FilterDesignError;
 
+/// Upper bound of the number of poles, the stages are kept inline so that filtering never allocates.
+pub const MAX_EXPONENTIAL_STAGES: usize = 8;
+
+/// Starts from the latest stored reading when there is one, then keeps the state of every pole.
+#[derive(Debug, Clone)]
 pub struct MultiPoleExponentialLowPass {
+    /// Poles beyond [`MAX_EXPONENTIAL_STAGES`] are ignored.
     pub stages: u8,
     pub smoothing: f32,
-    prev_stages: Vec<f64>,
+    prev_stages: Option<[f64; MAX_EXPONENTIAL_STAGES]>,
 }
 
 impl MultiPoleExponentialLowPass {
+    /// `stages` is clamped to [`MAX_EXPONENTIAL_STAGES`], see [`MultiPoleExponentialLowPass::try_new`].
     pub fn new(stages: u8, smoothing: f32) -> MultiPoleExponentialLowPass {
-        MultiPoleExponentialLowPass { stages, smoothing, prev_stages: vec![] }
+        MultiPoleExponentialLowPass {
+            stages: stages.min(MAX_EXPONENTIAL_STAGES as u8),
+            smoothing,
+            prev_stages: None,
+        }
+    }
+
+    /// `stages` must be between 1 and [`MAX_EXPONENTIAL_STAGES`].
+    pub fn try_new(stages: u8, smoothing: f32) -> Result<MultiPoleExponentialLowPass, FilterDesignError> {
+        if stages == 0 || stages as usize > MAX_EXPONENTIAL_STAGES {
+            return Err(FilterDesignError::InvalidOrder(stages));
+        }
+
+        Ok(MultiPoleExponentialLowPass::new(stages, smoothing))
     }
 }
 
-impl LowPassFilter for MultiPoleExponentialLowPass {
+impl LowPassFilter for MultiPoleExponentialLowPass {}
+
+impl Filter for MultiPoleExponentialLowPass {
     fn apply(&mut self, context: &FilterContext) -> f64 {
-        if self.prev_stages.is_empty() {
+        let stages = self.prev_stages.get_or_insert_with(|| {
             let last_reading = context
                 .readings
                 .back()
-                .map(|r| r.value)
+                .map(|r| r.value.get(context.channel))
                 .unwrap_or(context.raw_value);
 
-            self.prev_stages = vec![last_reading; self.stages as usize];
-        }
+            [last_reading; MAX_EXPONENTIAL_STAGES]
+        });
 
         let smoothing = self.smoothing as f64;
-        let mut new_stages = vec![];
-        let prev_stages = &self.prev_stages;
-        let mut result = context.raw_value;
 
-        for i in 0..prev_stages.len() {
-            let value = if i == 0 { context.raw_value } else { new_stages[i - 1] };
-            let new_stage_value = prev_stages[i] + smoothing * (value - prev_stages[i]);
+        stages[..(self.stages as usize).min(MAX_EXPONENTIAL_STAGES)]
+            .iter_mut()
+            .fold(context.raw_value, |value, stage| {
+                *stage += smoothing * (value - *stage);
+                *stage
+            })
+    }
+}
 
-            new_stages.push(new_stage_value);
-            result = new_stage_value;
-        }
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Filter, FilterContext};
+    use crate::filter::{FilterDesignError, MAX_EXPONENTIAL_STAGES, MultiPoleExponentialLowPass};
+    use alloc::collections::VecDeque;
+
+    fn apply(filter: &mut MultiPoleExponentialLowPass, raw_value: f64) -> f64 {
+        let readings = VecDeque::new();
+
+        filter.apply(&FilterContext {
+            channel: Channel::Z,
+            capacity: 10,
+            raw_value,
+            readings: &readings,
+            timestamp: 0,
+        })
+    }
+
+    #[test]
+    fn state_advances_between_samples() {
+        let mut filter = MultiPoleExponentialLowPass::new(2, 0.5);
+
+        assert_eq!(apply(&mut filter, 0.0), 0.0);
+        // first stage 0.5, second 0.25, then 0.75 and 0.5
+        assert_eq!(apply(&mut filter, 1.0), 0.25);
+        assert_eq!(apply(&mut filter, 1.0), 0.5);
+    }
+
+    #[test]
+    fn converges_to_step() {
+        let mut filter = MultiPoleExponentialLowPass::try_new(MAX_EXPONENTIAL_STAGES as u8, 0.3).unwrap();
+        let output = (0..200).fold(0.0, |_, i| apply(&mut filter, if i == 0 { 0.0 } else { 2.0 }));
+
+        assert_eq!(filter.stages, 8);
+        assert!((output - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_unsupported_stages() {
+        assert_eq!(
+            MultiPoleExponentialLowPass::try_new(20, 0.3).unwrap_err(),
+            FilterDesignError::InvalidOrder(20)
+        );
+        assert!(MultiPoleExponentialLowPass::try_new(0, 0.3).is_err());
+        assert_eq!(MultiPoleExponentialLowPass::new(20, 0.3).stages, 8);
+
+        let mut filter = MultiPoleExponentialLowPass::new(2, 0.3);
 
-        result
+        filter.stages = 20;
+        assert!(apply(&mut filter, 1.0).is_finite());
     }
 }
@@ -0,0 +1,53 @@
+use crate::common::{Filter, FilterContext};
+use crate::filter::biquad::{Biquad, BiquadCascade, FilterDesignError, validate_frequency};
+use alloc::vec;
+
+/// Rejects a narrow band around `frequency`, the bandwidth is `frequency / quality`.
+#[derive(Debug, Clone)]
+pub struct NotchFilter {
+    pub cascade: BiquadCascade,
+}
+
+impl NotchFilter {
+    pub fn new(frequency: f64, quality: f64, sample_rate: f64) -> Result<NotchFilter, FilterDesignError> {
+        validate_frequency(frequency, sample_rate)?;
+
+        if quality <= 0.0 {
+            return Err(FilterDesignError::InvalidQuality(quality));
+        }
+
+        let section = Biquad::notch(frequency, quality, sample_rate);
+
+        Ok(NotchFilter {
+            cascade: BiquadCascade::new(vec![section]),
+        })
+    }
+}
+
+impl Filter for NotchFilter {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        self.cascade.apply(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::{FilterDesignError, NotchFilter};
+
+    #[test]
+    fn rejects_notch_frequency() {
+        let filter = NotchFilter::new(50.0, 30.0, 200.0).unwrap();
+
+        assert!(filter.cascade.gain(50.0, 200.0) < 1e-9);
+        assert!((filter.cascade.gain(10.0, 200.0) - 1.0).abs() < 1e-3);
+        assert!((filter.cascade.gain(90.0, 200.0) - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn rejects_invalid_quality() {
+        assert_eq!(
+            NotchFilter::new(50.0, 0.0, 200.0).err(),
+            Some(FilterDesignError::InvalidQuality(0.0))
+        );
+    }
+}
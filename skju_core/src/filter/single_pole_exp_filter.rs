@@ -1,30 +1,44 @@
-use crate::common::{FilterContext, LowPassFilter};
+use crate::common::{Filter, FilterContext, LowPassFilter};
 
+/// Starts from the latest stored reading when there is one, then keeps its own state so that it can be
+/// followed by other stages.
+#[derive(Debug, Clone)]
 pub struct SinglePoleExponentialLowPass {
     pub smoothing: f32,
+    previous: Option<f64>,
 }
 
 impl SinglePoleExponentialLowPass {
     pub fn new(smoothing: f32) -> SinglePoleExponentialLowPass {
-        SinglePoleExponentialLowPass { smoothing }
+        SinglePoleExponentialLowPass { smoothing, previous: None }
     }
 }
 
-impl LowPassFilter for SinglePoleExponentialLowPass {
+impl LowPassFilter for SinglePoleExponentialLowPass {}
+
+impl Filter for SinglePoleExponentialLowPass {
     fn apply(&mut self, context: &FilterContext) -> f64 {
-        context
-            .readings
-            .back()
-            .map(|r| r.value + self.smoothing as f64 * (context.raw_value - r.value))
-            .unwrap_or_else(|| context.raw_value)
+        let output = self
+            .previous
+            .or_else(|| {
+                context
+                    .readings
+                    .back()
+                    .map(|r| r.value.get(context.channel))
+            })
+            .map(|previous| previous + self.smoothing as f64 * (context.raw_value - previous))
+            .unwrap_or(context.raw_value);
+
+        self.previous = Some(output);
+        output
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::{FilterContext, LowPassFilter, SensorData};
+    use crate::common::{Channel, Filter, FilterContext, Sample, SensorData};
     use crate::filter::single_pole_exp_filter::SinglePoleExponentialLowPass;
-    use std::collections::VecDeque;
+    use alloc::collections::VecDeque;
 
     #[test]
     fn filter_with_no_readings() {
@@ -33,6 +47,7 @@ mod tests {
         let capacity = 100;
         let readings = VecDeque::with_capacity(capacity);
         let context = FilterContext {
+            channel: Channel::Z,
             capacity,
             raw_value,
             readings: &readings,
@@ -58,10 +73,11 @@ mod tests {
 
         readings.push_back(SensorData {
             timestamp: 4242,
-            value: last_filtered_value,
+            value: Sample::vertical(last_filtered_value),
         });
 
         let context = FilterContext {
+            channel: Channel::Z,
             capacity,
             raw_value,
             readings: &readings,
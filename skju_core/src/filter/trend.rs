@@ -0,0 +1,115 @@
+use crate::common::{Filter, FilterContext};
+use alloc::collections::VecDeque;
+
+/// Removes the mean of the trailing `window` raw values.
+#[derive(Debug, Clone)]
+pub struct Demean {
+    pub window: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+}
+
+/// Removes the least-squares line through the trailing `window` raw values, evaluated at the newest one.
+#[derive(Debug, Clone)]
+pub struct Detrend {
+    pub window: usize,
+    values: VecDeque<f64>,
+}
+
+impl Demean {
+    pub fn new(window: usize) -> Demean {
+        let window = window.max(1);
+
+        Demean {
+            window,
+            values: VecDeque::with_capacity(window),
+            sum: 0.0,
+        }
+    }
+}
+
+impl Detrend {
+    pub fn new(window: usize) -> Detrend {
+        let window = window.max(2);
+
+        Detrend {
+            window,
+            values: VecDeque::with_capacity(window),
+        }
+    }
+}
+
+impl Filter for Demean {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        if self.values.len() == self.window {
+            self.sum -= self.values.pop_front().unwrap_or_default();
+        }
+
+        self.values.push_back(context.raw_value);
+        self.sum += context.raw_value;
+
+        context.raw_value - self.sum / self.values.len() as f64
+    }
+}
+
+impl Filter for Detrend {
+    fn apply(&mut self, context: &FilterContext) -> f64 {
+        if self.values.len() == self.window {
+            self.values.pop_front();
+        }
+
+        self.values.push_back(context.raw_value);
+
+        let count = self.values.len() as f64;
+
+        if self.values.len() < 2 {
+            return 0.0;
+        }
+
+        let mean_x = (count - 1.0) / 2.0;
+        let mean_y = self.values.iter().sum::<f64>() / count;
+        let (covariance, variance) =
+            self.values
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                    let dx = x as f64 - mean_x;
+                    (covariance + dx * (y - mean_y), variance + dx * dx)
+                });
+        let slope = covariance / variance;
+
+        context.raw_value - (mean_y + slope * (count - 1.0 - mean_x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Filter, FilterContext};
+    use crate::filter::{Demean, Detrend};
+    use alloc::collections::VecDeque;
+
+    fn apply(filter: &mut impl Filter, raw_value: f64) -> f64 {
+        filter.apply(&FilterContext {
+            channel: Channel::Z,
+            readings: &VecDeque::new(),
+            raw_value,
+            timestamp: 0,
+            capacity: 0,
+        })
+    }
+
+    #[test]
+    fn removes_offset_and_trend() {
+        let mut demean = Demean::new(10);
+        let mut detrend = Detrend::new(10);
+        let (mut demeaned, mut detrended) = (0.0, 0.0);
+
+        for i in 0..30 {
+            demeaned = apply(&mut demean, 5.0 + if i % 2 == 0 { 1.0 } else { -1.0 });
+            detrended = apply(&mut detrend, 3.0 + 0.5 * i as f64);
+        }
+
+        assert!((demeaned + 1.0).abs() < 1e-12);
+        assert!(detrended.abs() < 1e-9);
+    }
+}
@@ -0,0 +1,342 @@
+//! WGS84 geographic coordinates and a local East-North-Up plane tangent to them.
+//!
+//! [`Coord`] is a position in such a plane, `x` east and `y` north in km. Sensors positioned with
+//! latitude and longitude are projected through a [`LocalFrame`] before localization.
+
+use crate::common::Coord;
+use crate::math;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// WGS84 semi-major axis, m.
+pub const WGS84_A: f64 = 6_378_137.0;
+/// WGS84 flattening.
+pub const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// Mean Earth radius used by the spherical formulas, km.
+pub const MEAN_EARTH_RADIUS_KM: f64 = 6371.0088;
+
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+const VINCENTY_ITERATIONS: usize = 200;
+const VINCENTY_TOLERANCE: f64 = 1e-12;
+const ECEF_ITERATIONS: usize = 10;
+
+/// Latitude and longitude in degrees, elevation above the ellipsoid in m.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoCoord {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub elevation: f64,
+}
+
+/// East, North and Up offsets in m from the origin of a [`LocalFrame`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnuCoord {
+    pub east: f64,
+    pub north: f64,
+    pub up: f64,
+}
+
+/// Plane tangent to the ellipsoid at `origin`.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalFrame {
+    pub origin: GeoCoord,
+    origin_ecef: [f64; 3],
+}
+
+/// Ellipsoidal distance in km and azimuths in degrees clockwise from North.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodesic {
+    pub distance_km: f64,
+    pub initial_azimuth: f64,
+    pub final_azimuth: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoError {
+    /// Vincenty's iteration does not converge for nearly antipodal points.
+    NotConverged,
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoError::NotConverged => write!(formatter, "Geodesic did not converge, points are nearly antipodal"),
+        }
+    }
+}
+
+impl GeoCoord {
+    pub fn new(latitude: f64, longitude: f64, elevation: f64) -> GeoCoord {
+        GeoCoord { latitude, longitude, elevation }
+    }
+
+    /// Earth-centered, Earth-fixed position in m.
+    pub fn to_ecef(&self) -> [f64; 3] {
+        let (sin_lat, cos_lat) = math::sin_cos(self.latitude.to_radians());
+        let (sin_lon, cos_lon) = math::sin_cos(self.longitude.to_radians());
+        let normal = WGS84_A / math::sqrt(1.0 - WGS84_E2 * sin_lat * sin_lat);
+
+        [
+            (normal + self.elevation) * cos_lat * cos_lon,
+            (normal + self.elevation) * cos_lat * sin_lon,
+            (normal * (1.0 - WGS84_E2) + self.elevation) * sin_lat,
+        ]
+    }
+
+    pub fn from_ecef([x, y, z]: [f64; 3]) -> GeoCoord {
+        let p = math::hypot(x, y);
+        let mut latitude = math::atan2(z, p * (1.0 - WGS84_E2));
+        let mut elevation = 0.0;
+
+        for _ in 0..ECEF_ITERATIONS {
+            let (sin_lat, cos_lat) = math::sin_cos(latitude);
+            let normal = WGS84_A / math::sqrt(1.0 - WGS84_E2 * sin_lat * sin_lat);
+
+            elevation = p * cos_lat + z * sin_lat - WGS84_A * WGS84_A / normal;
+            latitude = math::atan2(z, p * (1.0 - WGS84_E2 * normal / (normal + elevation)));
+        }
+
+        GeoCoord {
+            latitude: latitude.to_degrees(),
+            longitude: math::atan2(y, x).to_degrees(),
+            elevation,
+        }
+    }
+}
+
+impl EnuCoord {
+    /// Horizontal position in km, dropping the vertical offset.
+    pub fn to_coord(&self) -> Coord {
+        Coord {
+            x: (self.east / 1000.0) as f32,
+            y: (self.north / 1000.0) as f32,
+        }
+    }
+
+    pub fn from_coord(coord: Coord) -> EnuCoord {
+        EnuCoord {
+            east: coord.x as f64 * 1000.0,
+            north: coord.y as f64 * 1000.0,
+            up: 0.0,
+        }
+    }
+}
+
+impl LocalFrame {
+    pub fn new(origin: GeoCoord) -> LocalFrame {
+        LocalFrame { origin, origin_ecef: origin.to_ecef() }
+    }
+
+    pub fn to_enu(&self, point: &GeoCoord) -> EnuCoord {
+        let [x, y, z] = point.to_ecef();
+        let [dx, dy, dz] = [
+            x - self.origin_ecef[0],
+            y - self.origin_ecef[1],
+            z - self.origin_ecef[2],
+        ];
+        let (sin_lat, cos_lat, sin_lon, cos_lon) = self.rotation();
+
+        EnuCoord {
+            east: -sin_lon * dx + cos_lon * dy,
+            north: -sin_lat * cos_lon * dx - sin_lat * sin_lon * dy + cos_lat * dz,
+            up: cos_lat * cos_lon * dx + cos_lat * sin_lon * dy + sin_lat * dz,
+        }
+    }
+
+    pub fn to_geo(&self, point: &EnuCoord) -> GeoCoord {
+        let (sin_lat, cos_lat, sin_lon, cos_lon) = self.rotation();
+        let EnuCoord { east, north, up } = *point;
+
+        GeoCoord::from_ecef([
+            self.origin_ecef[0] - sin_lon * east - sin_lat * cos_lon * north + cos_lat * cos_lon * up,
+            self.origin_ecef[1] + cos_lon * east - sin_lat * sin_lon * north + cos_lat * sin_lon * up,
+            self.origin_ecef[2] + cos_lat * north + sin_lat * up,
+        ])
+    }
+
+    /// Position of `point` in the km plane used by localization.
+    pub fn project(&self, point: &GeoCoord) -> Coord {
+        self.to_enu(point).to_coord()
+    }
+
+    /// Geographic position of a point of the km plane, on the ellipsoid surface.
+    pub fn unproject(&self, coord: Coord) -> GeoCoord {
+        let mut geo = self.to_geo(&EnuCoord::from_coord(coord));
+
+        geo.elevation = 0.0;
+        geo
+    }
+
+    fn rotation(&self) -> (f64, f64, f64, f64) {
+        let (sin_lat, cos_lat) = math::sin_cos(self.origin.latitude.to_radians());
+        let (sin_lon, cos_lon) = math::sin_cos(self.origin.longitude.to_radians());
+
+        (sin_lat, cos_lat, sin_lon, cos_lon)
+    }
+}
+
+/// Great circle distance in km on a sphere of [`MEAN_EARTH_RADIUS_KM`].
+pub fn haversine_km(from: &GeoCoord, to: &GeoCoord) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let half_lat = math::sin((lat2 - lat1) / 2.0);
+    let half_lon = math::sin((to.longitude - from.longitude).to_radians() / 2.0);
+    let h = half_lat * half_lat + math::cos(lat1) * math::cos(lat2) * half_lon * half_lon;
+
+    2.0 * MEAN_EARTH_RADIUS_KM * math::asin(math::sqrt(h.min(1.0)))
+}
+
+/// Initial great circle bearing in degrees clockwise from North.
+pub fn azimuth(from: &GeoCoord, to: &GeoCoord) -> f64 {
+    let (sin_lat1, cos_lat1) = math::sin_cos(from.latitude.to_radians());
+    let (sin_lat2, cos_lat2) = math::sin_cos(to.latitude.to_radians());
+    let (sin_dlon, cos_dlon) = math::sin_cos((to.longitude - from.longitude).to_radians());
+    let bearing = math::atan2(
+        sin_dlon * cos_lat2,
+        cos_lat1 * sin_lat2 - sin_lat1 * cos_lat2 * cos_dlon,
+    );
+
+    normalize_degrees(bearing.to_degrees())
+}
+
+/// Distance and azimuths on the WGS84 ellipsoid by Vincenty's inverse formula, accurate to 0.5 mm.
+pub fn vincenty(from: &GeoCoord, to: &GeoCoord) -> Result<Geodesic, GeoError> {
+    let f = WGS84_F;
+    let l = (to.longitude - from.longitude).to_radians();
+    let u1 = math::atan((1.0 - f) * math::tan(from.latitude.to_radians()));
+    let u2 = math::atan((1.0 - f) * math::tan(to.latitude.to_radians()));
+    let (sin_u1, cos_u1) = math::sin_cos(u1);
+    let (sin_u2, cos_u2) = math::sin_cos(u2);
+
+    let mut lambda = l;
+
+    for _ in 0..VINCENTY_ITERATIONS {
+        let (sin_lambda, cos_lambda) = math::sin_cos(lambda);
+        let sin_sigma = math::hypot(cos_u2 * sin_lambda, cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+
+        if sin_sigma == 0.0 {
+            return Ok(Geodesic {
+                distance_km: 0.0,
+                initial_azimuth: 0.0,
+                final_azimuth: 0.0,
+            });
+        }
+
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = math::atan2(sin_sigma, cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let cos_2sigma_m = if cos2_alpha != 0.0 {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        } else {
+            0.0
+        };
+        let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+
+        lambda = l
+            + (1.0 - c)
+                * f
+                * sin_alpha
+                * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+        if (lambda - previous).abs() < VINCENTY_TOLERANCE {
+            let u_squared = cos2_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+            let a =
+                1.0 + u_squared / 16384.0 * (4096.0 + u_squared * (-768.0 + u_squared * (320.0 - 175.0 * u_squared)));
+            let b = u_squared / 1024.0 * (256.0 + u_squared * (-128.0 + u_squared * (74.0 - 47.0 * u_squared)));
+            let delta_sigma = b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                            - b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            let (sin_lambda, cos_lambda) = math::sin_cos(lambda);
+            let initial = math::atan2(cos_u2 * sin_lambda, cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            let last = math::atan2(cos_u1 * sin_lambda, -sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda);
+
+            return Ok(Geodesic {
+                distance_km: WGS84_B * a * (sigma - delta_sigma) / 1000.0,
+                initial_azimuth: normalize_degrees(initial.to_degrees()),
+                final_azimuth: normalize_degrees(last.to_degrees()),
+            });
+        }
+    }
+
+    Err(GeoError::NotConverged)
+}
+
+fn normalize_degrees(degrees: f64) -> f64 {
+    let normalized = degrees % 360.0;
+
+    if normalized < 0.0 {
+        normalized + 360.0
+    } else {
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Coord;
+    use crate::geo::{GeoCoord, GeoError, LocalFrame, azimuth, haversine_km, vincenty};
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+    }
+
+    #[test]
+    fn vincenty_reference_geodesic() {
+        // Flinders Peak to Buninyong, Vincenty (1975)
+        let flinders = GeoCoord::new(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440), 0.0);
+        let buninyong = GeoCoord::new(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390), 0.0);
+        let geodesic = vincenty(&flinders, &buninyong).unwrap();
+
+        assert!((geodesic.distance_km - 54.972271).abs() < 1e-6);
+        assert!((geodesic.initial_azimuth - dms(306.0, 52.0, 5.37)).abs() < 1e-5);
+        assert!((geodesic.final_azimuth - dms(307.0, 10.0, 25.07)).abs() < 1e-5);
+        assert!((haversine_km(&flinders, &buninyong) - 54.97).abs() < 0.2);
+        assert!((azimuth(&flinders, &buninyong) - geodesic.initial_azimuth).abs() < 0.2);
+    }
+
+    #[test]
+    fn vincenty_antipodal_points() {
+        let from = GeoCoord::new(0.0, 0.0, 0.0);
+        let to = GeoCoord::new(0.5, 179.7, 0.0);
+
+        assert_eq!(vincenty(&from, &to), Err(GeoError::NotConverged));
+        assert_eq!(vincenty(&from, &from).unwrap().distance_km, 0.0);
+    }
+
+    #[test]
+    fn local_frame_round_trip() {
+        let frame = LocalFrame::new(GeoCoord::new(45.8, 15.97, 120.0));
+        let point = GeoCoord::new(45.85, 16.05, 300.0);
+        let enu = frame.to_enu(&point);
+        let back = frame.to_geo(&enu);
+
+        assert!((back.latitude - point.latitude).abs() < 1e-9);
+        assert!((back.longitude - point.longitude).abs() < 1e-9);
+        assert!((back.elevation - point.elevation).abs() < 1e-4);
+
+        let plane = frame.project(&point);
+        let distance = (plane.x as f64).hypot(plane.y as f64);
+
+        assert!((distance - vincenty(&frame.origin, &point).unwrap().distance_km).abs() < 0.01);
+        assert!(enu.east > 0.0 && enu.north > 0.0);
+    }
+
+    #[test]
+    fn unproject_north_offset() {
+        let frame = LocalFrame::new(GeoCoord::new(0.0, 0.0, 0.0));
+        let point = frame.unproject(Coord { x: 0.0, y: 10.0 });
+
+        assert!(point.longitude.abs() < 1e-9);
+        assert!((vincenty(&frame.origin, &point).unwrap().distance_km - 10.0).abs() < 1e-3);
+    }
+}
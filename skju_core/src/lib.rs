@@ -1,9 +1,25 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod common;
+mod consts;
 pub mod filter;
+pub mod geo;
+pub mod locate;
+pub mod magnitude;
+mod math;
+pub mod mseed;
+pub mod orientation;
+pub mod picker;
+pub mod response;
 pub mod sensor;
+pub mod shaking;
+pub mod spectral;
+pub mod trigger;
+pub mod warning;
+#[cfg(feature = "std")]
 pub mod utils;
-mod consts;
-
 
 pub use common::*;
 
@@ -0,0 +1,373 @@
+use crate::common::Coord;
+use crate::consts::{P_WAVE, S_WAVE};
+use crate::math;
+use alloc::vec::Vec;
+use core::fmt;
+
+const GRID_STEPS: usize = 40;
+const MAX_ITERATIONS: usize = 50;
+const CONVERGENCE_KM: f64 = 1e-6;
+
+/// P-wave arrival at a sensor, coordinates are in km and timestamp is in ms. Sensors positioned with
+/// latitude and longitude are projected with [`LocalFrame::project`](crate::geo::LocalFrame::project).
+#[derive(Debug, Clone, Copy)]
+pub struct ArrivalPick {
+    pub coord: Coord,
+    pub timestamp: u128,
+}
+
+/// Standard errors of the solution, only available when the system is overdetermined.
+#[derive(Debug, Clone, Copy)]
+pub struct Uncertainty {
+    pub x_km: f64,
+    pub y_km: f64,
+    pub origin_time_s: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Location {
+    pub epicenter: Coord,
+    pub origin_time: u128,
+    pub rms_residual: f64,
+    pub uncertainty: Option<Uncertainty>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SingleStationEstimate {
+    pub distance_km: f64,
+    pub origin_time: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocateError {
+    NotEnoughPicks(usize),
+    InvalidPhases,
+    Degenerate,
+    InvalidVelocity,
+    NotConverged,
+}
+
+impl fmt::Display for LocateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocateError::NotEnoughPicks(count) => write!(formatter, "At least 3 picks required, got {count}"),
+            LocateError::InvalidPhases => write!(formatter, "S arrival must come after P arrival"),
+            LocateError::Degenerate => write!(formatter, "Sensor geometry does not constrain the epicenter"),
+            LocateError::InvalidVelocity => write!(formatter, "Velocity must be positive"),
+            LocateError::NotConverged => write!(formatter, "Location did not converge"),
+        }
+    }
+}
+
+pub fn locate(picks: &[ArrivalPick]) -> Result<Location, LocateError> {
+    locate_with_velocity(picks, P_WAVE)
+}
+
+/// Grid search over the area around the sensors followed by a Gauss-Newton refinement of
+/// epicenter and origin time, `velocity` is in km/s.
+pub fn locate_with_velocity(picks: &[ArrivalPick], velocity: f64) -> Result<Location, LocateError> {
+    if picks.len() < 3 {
+        return Err(LocateError::NotEnoughPicks(picks.len()));
+    }
+
+    if !velocity.is_finite() || velocity <= 0.0 {
+        return Err(LocateError::InvalidVelocity);
+    }
+
+    let first_arrival = picks.iter().map(|p| p.timestamp).min().unwrap_or_default();
+    let stations: Vec<Station> = picks
+        .iter()
+        .map(|p| Station {
+            x: p.coord.x as f64,
+            y: p.coord.y as f64,
+            time: (p.timestamp - first_arrival) as f64 / 1000.0,
+        })
+        .collect();
+
+    let (mut x, mut y) = grid_search(&stations, velocity);
+    let mut origin = best_origin(&stations, x, y, velocity);
+    let mut normal = [[0.0; 3]; 3];
+    let mut converged = false;
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut gradient = [0.0; 3];
+
+        normal = [[0.0; 3]; 3];
+
+        for station in &stations {
+            let distance = station.distance(x, y).max(f64::EPSILON);
+            let residual = station.time - (origin + distance / velocity);
+            let jacobian = [
+                (x - station.x) / (velocity * distance),
+                (y - station.y) / (velocity * distance),
+                1.0,
+            ];
+
+            for i in 0..3 {
+                gradient[i] += jacobian[i] * residual;
+
+                for j in 0..3 {
+                    normal[i][j] += jacobian[i] * jacobian[j];
+                }
+            }
+        }
+
+        let step = solve(normal, gradient).ok_or(LocateError::Degenerate)?;
+
+        x += step[0];
+        y += step[1];
+        origin += step[2];
+
+        if math::hypot(step[0], step[1]) < CONVERGENCE_KM {
+            converged = true;
+            break;
+        }
+    }
+
+    if !(converged && x.is_finite() && y.is_finite() && origin.is_finite()) {
+        return Err(LocateError::NotConverged);
+    }
+
+    let squared_residuals: f64 = stations
+        .iter()
+        .map(|s| squared(s.time - origin - s.distance(x, y) / velocity))
+        .sum();
+
+    let degrees_of_freedom = stations.len() - 3;
+    let uncertainty = if degrees_of_freedom > 0 {
+        let variance = squared_residuals / degrees_of_freedom as f64;
+        let covariance = invert(normal).ok_or(LocateError::Degenerate)?;
+
+        Some(Uncertainty {
+            x_km: math::sqrt(variance * covariance[0][0]),
+            y_km: math::sqrt(variance * covariance[1][1]),
+            origin_time_s: math::sqrt(variance * covariance[2][2]),
+        })
+    } else {
+        None
+    };
+
+    Ok(Location {
+        epicenter: Coord { x: x as f32, y: y as f32 },
+        origin_time: to_timestamp(first_arrival, origin),
+        rms_residual: math::sqrt(squared_residuals / stations.len() as f64),
+        uncertainty,
+    })
+}
+
+/// Epicentral distance in km and origin time from P and S arrivals at a single station.
+pub fn estimate_from_s_minus_p(p_arrival: u128, s_arrival: u128) -> Result<SingleStationEstimate, LocateError> {
+    if s_arrival <= p_arrival {
+        return Err(LocateError::InvalidPhases);
+    }
+
+    let distance_km = distance_from_s_minus_p((s_arrival - p_arrival) as f64 / 1000.0);
+    let origin_time = to_timestamp(p_arrival, -distance_km / P_WAVE);
+
+    Ok(SingleStationEstimate { distance_km, origin_time })
+}
+
+pub fn distance_from_s_minus_p(seconds: f64) -> f64 {
+    seconds * P_WAVE * S_WAVE / (P_WAVE - S_WAVE)
+}
+
+struct Station {
+    x: f64,
+    y: f64,
+    time: f64,
+}
+
+impl Station {
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        math::hypot(x - self.x, y - self.y)
+    }
+}
+
+fn to_timestamp(reference: u128, offset_seconds: f64) -> u128 {
+    math::round(reference as f64 + offset_seconds * 1000.0).max(0.0) as u128
+}
+
+/// Least-squares origin time for a fixed epicenter is the mean of the reduced arrival times.
+fn best_origin(stations: &[Station], x: f64, y: f64, velocity: f64) -> f64 {
+    let sum: f64 = stations
+        .iter()
+        .map(|s| s.time - s.distance(x, y) / velocity)
+        .sum();
+
+    sum / stations.len() as f64
+}
+
+fn grid_search(stations: &[Station], velocity: f64) -> (f64, f64) {
+    let min_x = stations.iter().map(|s| s.x).fold(f64::INFINITY, f64::min);
+    let max_x = stations
+        .iter()
+        .map(|s| s.x)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let min_y = stations.iter().map(|s| s.y).fold(f64::INFINITY, f64::min);
+    let max_y = stations
+        .iter()
+        .map(|s| s.y)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let margin = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (from_x, from_y) = (min_x - margin, min_y - margin);
+    let step_x = (max_x - min_x + 2.0 * margin) / GRID_STEPS as f64;
+    let step_y = (max_y - min_y + 2.0 * margin) / GRID_STEPS as f64;
+    let mut best = (f64::INFINITY, from_x, from_y);
+
+    for i in 0..=GRID_STEPS {
+        for j in 0..=GRID_STEPS {
+            let x = from_x + i as f64 * step_x;
+            let y = from_y + j as f64 * step_y;
+            let origin = best_origin(stations, x, y, velocity);
+            let misfit: f64 = stations
+                .iter()
+                .map(|s| squared(s.time - origin - s.distance(x, y) / velocity))
+                .sum();
+
+            if misfit < best.0 {
+                best = (misfit, x, y);
+            }
+        }
+    }
+
+    (best.1, best.2)
+}
+
+fn squared(value: f64) -> f64 {
+    value * value
+}
+
+fn determinant(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn invert(m: [[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let det = determinant(&m);
+
+    if det.abs() < 1e-12 {
+        return None;
+    }
+
+    let mut inverse = [[0.0; 3]; 3];
+
+    for (i, row) in inverse.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            // adjugate is the transposed cofactor matrix
+            let (r1, r2) = ((j + 1) % 3, (j + 2) % 3);
+            let (c1, c2) = ((i + 1) % 3, (i + 2) % 3);
+
+            *value = (m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]) / det;
+        }
+    }
+
+    Some(inverse)
+}
+
+fn solve(m: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let inverse = invert(m)?;
+    let mut result = [0.0; 3];
+
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = (0..3).map(|j| inverse[i][j] * b[j]).sum();
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Coord;
+    use crate::consts::P_WAVE;
+    use crate::locate::{
+        ArrivalPick, LocateError, distance_from_s_minus_p, estimate_from_s_minus_p, locate, locate_with_velocity,
+    };
+
+    const ORIGIN_TIME: u128 = 1_700_000_000_000;
+
+    fn pick(x: f32, y: f32, epicenter: Coord) -> ArrivalPick {
+        let distance = ((x - epicenter.x) as f64).hypot((y - epicenter.y) as f64);
+        let travel_ms = (distance / P_WAVE * 1000.0).round() as u128;
+
+        ArrivalPick {
+            coord: Coord { x, y },
+            timestamp: ORIGIN_TIME + travel_ms,
+        }
+    }
+
+    #[test]
+    fn requires_three_picks() {
+        let epicenter = Coord { x: 0.0, y: 0.0 };
+        let picks = [pick(1.0, 1.0, epicenter), pick(2.0, 5.0, epicenter)];
+
+        assert_eq!(locate(&picks).err(), Some(LocateError::NotEnoughPicks(2)));
+    }
+
+    #[test]
+    fn locates_epicenter_inside_network() {
+        let epicenter = Coord { x: 18.0, y: 33.0 };
+        let picks = [
+            pick(12.5, 34.8, epicenter),
+            pick(18.2, 29.1, epicenter),
+            pick(25.7, 40.3, epicenter),
+            pick(10.0, 25.0, epicenter),
+        ];
+
+        let location = locate(&picks).unwrap();
+        let uncertainty = location.uncertainty.unwrap();
+
+        assert!((location.epicenter.x - epicenter.x).abs() < 0.05);
+        assert!((location.epicenter.y - epicenter.y).abs() < 0.05);
+        assert!(location.origin_time.abs_diff(ORIGIN_TIME) <= 2);
+        assert!(location.rms_residual < 1e-3);
+        assert!(uncertainty.x_km < 0.1 && uncertainty.y_km < 0.1);
+    }
+
+    #[test]
+    fn locates_epicenter_outside_network_with_three_picks() {
+        let epicenter = Coord { x: 40.0, y: 10.0 };
+        let picks = [
+            pick(12.5, 34.8, epicenter),
+            pick(18.2, 29.1, epicenter),
+            pick(25.7, 40.3, epicenter),
+        ];
+
+        let location = locate(&picks).unwrap();
+
+        assert!((location.epicenter.x - epicenter.x).abs() < 0.5);
+        assert!((location.epicenter.y - epicenter.y).abs() < 0.5);
+        assert!(location.uncertainty.is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_velocity() {
+        let epicenter = Coord { x: 18.0, y: 33.0 };
+        let picks = [
+            pick(12.5, 34.8, epicenter),
+            pick(18.2, 29.1, epicenter),
+            pick(25.7, 40.3, epicenter),
+        ];
+
+        assert_eq!(
+            locate_with_velocity(&picks, 0.0).err(),
+            Some(LocateError::InvalidVelocity)
+        );
+        assert_eq!(
+            locate_with_velocity(&picks, f64::NAN).err(),
+            Some(LocateError::InvalidVelocity)
+        );
+    }
+
+    #[test]
+    fn single_station_distance_from_s_minus_p() {
+        let estimate = estimate_from_s_minus_p(ORIGIN_TIME + 1000, ORIGIN_TIME + 2000).unwrap();
+
+        assert!((distance_from_s_minus_p(1.0) - 8.4).abs() < 1e-9);
+        assert!((estimate.distance_km - 8.4).abs() < 1e-9);
+        assert_eq!(estimate.origin_time, ORIGIN_TIME + 1000 - 1400);
+        assert_eq!(
+            estimate_from_s_minus_p(ORIGIN_TIME, ORIGIN_TIME).err(),
+            Some(LocateError::InvalidPhases)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::common::Coord;
+use crate::math;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Closest distance the corrections are evaluated at, `log10(R)` diverges at the hypocenter.
+const MIN_DISTANCE_KM: f64 = 1.0;
+
+/// Peak amplitude at a sensor in mm of an equivalent Wood-Anderson record, coordinates are in km.
+#[derive(Debug, Clone, Copy)]
+pub struct StationAmplitude {
+    pub sensor_id: u64,
+    pub coord: Coord,
+    pub amplitude: f64,
+}
+
+/// Distance correction `-log A0(R) = distance * log10(R / R0) + anelastic * (R - R0) + constant`,
+/// with the hypocentral distance `R` in km.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub distance: f64,
+    pub anelastic: f64,
+    pub constant: f64,
+    pub reference_distance_km: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MagnitudeConfig {
+    pub attenuation: Attenuation,
+    /// Focal depth assumed for the hypocentral distance, the locator only resolves the epicenter.
+    pub depth_km: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StationMagnitude {
+    pub sensor_id: u64,
+    pub distance_km: f64,
+    pub magnitude: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalMagnitude {
+    /// Network magnitude, median of the station magnitudes.
+    pub magnitude: f64,
+    pub stations: Vec<StationMagnitude>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MagnitudeError {
+    NoAmplitudes,
+    InvalidAmplitude { sensor_id: u64, amplitude: f64 },
+}
+
+impl Attenuation {
+    /// Southern California, Hutton & Boore (1987).
+    pub const HUTTON_BOORE: Attenuation = Attenuation {
+        distance: 1.110,
+        anelastic: 0.00189,
+        constant: 3.0,
+        reference_distance_km: 100.0,
+    };
+
+    /// Distances below 1 km are evaluated at 1 km.
+    pub fn correction(&self, distance_km: f64) -> f64 {
+        let distance_km = distance_km.max(MIN_DISTANCE_KM);
+        let reference = self.reference_distance_km;
+
+        self.distance * math::log10(distance_km / reference)
+            + self.anelastic * (distance_km - reference)
+            + self.constant
+    }
+}
+
+impl Default for MagnitudeConfig {
+    fn default() -> Self {
+        MagnitudeConfig {
+            attenuation: Attenuation::HUTTON_BOORE,
+            depth_km: 10.0,
+        }
+    }
+}
+
+impl fmt::Display for MagnitudeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MagnitudeError::NoAmplitudes => write!(formatter, "At least one amplitude required"),
+            MagnitudeError::InvalidAmplitude { sensor_id, amplitude } => {
+                write!(formatter, "Invalid amplitude {amplitude} at sensor {sensor_id}")
+            }
+        }
+    }
+}
+
+/// `ML = log10(A) - log A0(R)` at every sensor, `R` is measured from the located `epicenter`.
+pub fn local_magnitude(
+    amplitudes: &[StationAmplitude],
+    epicenter: Coord,
+    config: &MagnitudeConfig,
+) -> Result<LocalMagnitude, MagnitudeError> {
+    if amplitudes.is_empty() {
+        return Err(MagnitudeError::NoAmplitudes);
+    }
+
+    let stations = amplitudes
+        .iter()
+        .map(|station| {
+            if !station.amplitude.is_finite() || station.amplitude <= 0.0 {
+                return Err(MagnitudeError::InvalidAmplitude {
+                    sensor_id: station.sensor_id,
+                    amplitude: station.amplitude,
+                });
+            }
+
+            let epicentral = math::hypot(
+                (station.coord.x - epicenter.x) as f64,
+                (station.coord.y - epicenter.y) as f64,
+            );
+            let distance_km = math::hypot(epicentral, config.depth_km);
+
+            Ok(StationMagnitude {
+                sensor_id: station.sensor_id,
+                distance_km,
+                magnitude: math::log10(station.amplitude) + config.attenuation.correction(distance_km),
+            })
+        })
+        .collect::<Result<Vec<StationMagnitude>, MagnitudeError>>()?;
+
+    let mut magnitudes: Vec<f64> = stations.iter().map(|station| station.magnitude).collect();
+    magnitudes.sort_unstable_by(f64::total_cmp);
+
+    let middle = magnitudes.len() / 2;
+    let magnitude = if magnitudes.len().is_multiple_of(2) {
+        (magnitudes[middle - 1] + magnitudes[middle]) / 2.0
+    } else {
+        magnitudes[middle]
+    };
+
+    Ok(LocalMagnitude { magnitude, stations })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Coord;
+    use crate::magnitude::{Attenuation, MagnitudeConfig, MagnitudeError, StationAmplitude, local_magnitude};
+
+    fn amplitude(sensor_id: u64, x: f32, amplitude: f64) -> StationAmplitude {
+        StationAmplitude {
+            sensor_id,
+            coord: Coord { x, y: 0.0 },
+            amplitude,
+        }
+    }
+
+    #[test]
+    fn reference_event() {
+        // 1 mm at 100 km is ML 3 by definition
+        assert!((Attenuation::HUTTON_BOORE.correction(100.0) - 3.0).abs() < 1e-12);
+
+        let config = MagnitudeConfig {
+            depth_km: 0.0,
+            ..MagnitudeConfig::default()
+        };
+        let result = local_magnitude(&[amplitude(1, 100.0, 1.0)], Coord { x: 0.0, y: 0.0 }, &config).unwrap();
+
+        assert!((result.magnitude - 3.0).abs() < 1e-12);
+        assert!((result.stations[0].distance_km - 100.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn network_median() {
+        let epicenter = Coord { x: 0.0, y: 0.0 };
+        let config = MagnitudeConfig::default();
+        let amplitudes = [
+            amplitude(1, 20.0, 10.0),
+            amplitude(2, 50.0, 2.0),
+            amplitude(3, 80.0, 0.8),
+            amplitude(4, 30.0, 500.0),
+        ];
+        let result = local_magnitude(&amplitudes, epicenter, &config).unwrap();
+        let mut magnitudes: [f64; 4] = core::array::from_fn(|i| result.stations[i].magnitude);
+
+        magnitudes.sort_unstable_by(f64::total_cmp);
+
+        assert_eq!(result.stations.len(), 4);
+        assert!((result.stations[1].distance_km - 50.0f64.hypot(10.0)).abs() < 1e-9);
+        assert!((result.magnitude - (magnitudes[1] + magnitudes[2]) / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn station_at_shallow_epicenter() {
+        let config = MagnitudeConfig {
+            depth_km: 0.0,
+            ..MagnitudeConfig::default()
+        };
+        let result = local_magnitude(&[amplitude(1, 0.0, 1.0)], Coord { x: 0.0, y: 0.0 }, &config).unwrap();
+
+        assert_eq!(result.stations[0].distance_km, 0.0);
+        assert_eq!(result.magnitude, Attenuation::HUTTON_BOORE.correction(1.0));
+        assert!(result.magnitude.is_finite());
+    }
+
+    #[test]
+    fn rejects_invalid_amplitudes() {
+        let epicenter = Coord { x: 0.0, y: 0.0 };
+        let config = MagnitudeConfig::default();
+
+        assert_eq!(
+            local_magnitude(&[], epicenter, &config).err(),
+            Some(MagnitudeError::NoAmplitudes)
+        );
+        assert_eq!(
+            local_magnitude(&[amplitude(7, 10.0, 0.0)], epicenter, &config).err(),
+            Some(MagnitudeError::InvalidAmplitude { sensor_id: 7, amplitude: 0.0 })
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Float functions which `core` does not provide, backed by `libm` so they work without `std`.
+
+pub(crate) fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+pub(crate) fn hypot(x: f64, y: f64) -> f64 {
+    libm::hypot(x, y)
+}
+
+pub(crate) fn sin_cos(x: f64) -> (f64, f64) {
+    libm::sincos(x)
+}
+
+pub(crate) fn cos(x: f64) -> f64 {
+    libm::cos(x)
+}
+
+pub(crate) fn tan(x: f64) -> f64 {
+    libm::tan(x)
+}
+
+pub(crate) fn round(x: f64) -> f64 {
+    libm::round(x)
+}
+
+pub(crate) fn log10(x: f64) -> f64 {
+    libm::log10(x)
+}
+
+pub(crate) fn sin(x: f64) -> f64 {
+    libm::sin(x)
+}
+
+pub(crate) fn asin(x: f64) -> f64 {
+    libm::asin(x)
+}
+
+pub(crate) fn atan(x: f64) -> f64 {
+    libm::atan(x)
+}
+
+pub(crate) fn atan2(y: f64, x: f64) -> f64 {
+    libm::atan2(y, x)
+}
+
+pub(crate) fn ceil(x: f64) -> f64 {
+    libm::ceil(x)
+}
+
+pub(crate) fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
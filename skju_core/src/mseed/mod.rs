@@ -0,0 +1,147 @@
+//! miniSEED 2 data records, big-endian, with a blockette 1000 and the data starting at byte 64.
+
+mod record;
+mod steim;
+
+pub use record::*;
+
+use crate::Channel;
+use alloc::string::String;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_RECORD_LENGTH_EXPONENT: u8 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Int32,
+    Float32,
+    Float64,
+    Steim1,
+    Steim2,
+}
+
+/// FDSN source identifier of a record, `NET.STA.LOC.CHA`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamId {
+    pub network: String,
+    pub station: String,
+    pub location: String,
+    pub channel: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MseedError {
+    InvalidStreamId,
+    InvalidSampleRate(f64),
+    InvalidRecordLength(u8),
+    SampleOutOfRange(f64),
+    DifferenceOutOfRange,
+    UnknownSampleRate,
+    UnsupportedEncoding(u8),
+    InvalidRecord(&'static str),
+}
+
+impl Encoding {
+    pub fn code(&self) -> u8 {
+        match self {
+            Encoding::Int32 => 3,
+            Encoding::Float32 => 4,
+            Encoding::Float64 => 5,
+            Encoding::Steim1 => 10,
+            Encoding::Steim2 => 11,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Encoding> {
+        match code {
+            3 => Some(Encoding::Int32),
+            4 => Some(Encoding::Float32),
+            5 => Some(Encoding::Float64),
+            10 => Some(Encoding::Steim1),
+            11 => Some(Encoding::Steim2),
+            _ => None,
+        }
+    }
+
+    /// Integer encodings round the samples, scale them to counts before encoding.
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, Encoding::Float32 | Encoding::Float64)
+    }
+}
+
+impl StreamId {
+    pub fn new(network: &str, station: &str, location: &str, channel: &str) -> Result<StreamId, MseedError> {
+        let valid = |code: &str, max: usize| code.len() <= max && code.bytes().all(|b| b.is_ascii_alphanumeric());
+
+        if !valid(network, 2) || !valid(station, 5) || station.is_empty() || !valid(location, 2) {
+            return Err(MseedError::InvalidStreamId);
+        }
+
+        if channel.len() != 3 || !valid(channel, 3) {
+            return Err(MseedError::InvalidStreamId);
+        }
+
+        Ok(StreamId {
+            network: network.into(),
+            station: station.into(),
+            location: location.into(),
+            channel: channel.into(),
+        })
+    }
+
+    /// Stream of an accelerometer channel, the station code is the sensor id and the SEED channel code
+    /// is built from the band of `sample_rate`, `N` for accelerometer and the channel orientation.
+    pub fn for_sensor(
+        network: &str,
+        sensor_id: u64,
+        channel: Channel,
+        sample_rate: f64,
+    ) -> Result<StreamId, MseedError> {
+        let station = alloc::format!("{sensor_id}");
+        let channel = alloc::format!("{}N{}", band_code(sample_rate), channel);
+
+        StreamId::new(network, &station, "00", &channel)
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{}.{}.{}.{}",
+            self.network, self.station, self.location, self.channel
+        )
+    }
+}
+
+impl fmt::Display for MseedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MseedError::InvalidStreamId => write!(formatter, "Invalid stream id"),
+            MseedError::InvalidSampleRate(rate) => write!(formatter, "Invalid sample rate: {rate}"),
+            MseedError::InvalidRecordLength(exponent) => {
+                write!(formatter, "Invalid record length: 2^{exponent}")
+            }
+            MseedError::SampleOutOfRange(value) => write!(formatter, "Sample {value} does not fit in 32 bits"),
+            MseedError::DifferenceOutOfRange => write!(formatter, "Sample difference too large for Steim"),
+            MseedError::UnknownSampleRate => write!(formatter, "At least 2 samples required to derive the rate"),
+            MseedError::UnsupportedEncoding(code) => write!(formatter, "Unsupported encoding: {code}"),
+            MseedError::InvalidRecord(reason) => write!(formatter, "Invalid record: {reason}"),
+        }
+    }
+}
+
+fn band_code(sample_rate: f64) -> char {
+    match sample_rate {
+        rate if rate >= 1000.0 => 'F',
+        rate if rate >= 250.0 => 'C',
+        rate if rate >= 80.0 => 'H',
+        rate if rate >= 10.0 => 'B',
+        rate if rate > 1.0 => 'M',
+        rate if rate > 0.1 => 'L',
+        rate if rate > 0.01 => 'V',
+        _ => 'U',
+    }
+}
@@ -0,0 +1,501 @@
+use crate::math;
+use crate::mseed::steim::{self, FRAME_SIZE, SteimLevel};
+use crate::mseed::{DEFAULT_RECORD_LENGTH_EXPONENT, Encoding, MseedError, StreamId};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Fixed header and blockette 1000, padded to the first Steim frame.
+const DATA_OFFSET: usize = 64;
+const BLOCKETTE_OFFSET: usize = 48;
+const MAX_SEQUENCE: u32 = 999_999;
+/// The header counts the samples of a record in 16 bits.
+const MAX_RECORD_SAMPLES: usize = u16::MAX as usize;
+/// Intervals longer than this many sample periods start a new segment.
+const GAP_TOLERANCE: f64 = 1.5;
+
+pub struct MseedEncoder {
+    stream: StreamId,
+    encoding: Encoding,
+    record_length_exponent: u8,
+    sequence: u32,
+}
+
+/// Decoded data record, `start` is in milliseconds since the Unix epoch.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub stream: StreamId,
+    pub sequence: u32,
+    pub start: u128,
+    pub sample_rate: f64,
+    pub encoding: Encoding,
+    pub record_length: usize,
+    pub samples: Vec<f64>,
+}
+
+impl MseedEncoder {
+    pub fn new(stream: StreamId, encoding: Encoding) -> MseedEncoder {
+        MseedEncoder {
+            stream,
+            encoding,
+            record_length_exponent: DEFAULT_RECORD_LENGTH_EXPONENT,
+            sequence: 1,
+        }
+    }
+
+    /// Record length as a power of two, from 128 to 65536 bytes.
+    pub fn record_length(mut self, exponent: u8) -> Result<MseedEncoder, MseedError> {
+        if !(7..=16).contains(&exponent) {
+            return Err(MseedError::InvalidRecordLength(exponent));
+        }
+
+        self.record_length_exponent = exponent;
+        Ok(self)
+    }
+
+    /// Encodes a continuous run of samples starting at `start` ms into as many records as needed.
+    pub fn encode(&mut self, start: u128, sample_rate: f64, samples: &[f64]) -> Result<Vec<u8>, MseedError> {
+        if !sample_rate.is_finite() || sample_rate <= 0.0 {
+            return Err(MseedError::InvalidSampleRate(sample_rate));
+        }
+
+        let integers = if self.encoding.is_integer() {
+            samples
+                .iter()
+                .map(|&value| to_integer(value))
+                .collect::<Result<Vec<i32>, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        let record_length = 1usize << self.record_length_exponent;
+        let mut output = Vec::new();
+        let mut offset = 0;
+
+        while offset < samples.len() {
+            let mut record = vec![0u8; record_length];
+            let data = &mut record[DATA_OFFSET..];
+            let end = (offset + MAX_RECORD_SAMPLES).min(samples.len());
+
+            let count = match self.encoding {
+                Encoding::Steim1 | Encoding::Steim2 => {
+                    let level = if self.encoding == Encoding::Steim1 {
+                        SteimLevel::One
+                    } else {
+                        SteimLevel::Two
+                    };
+                    let previous = offset.checked_sub(1).map(|i| integers[i]);
+                    let (count, frames) =
+                        steim::encode(&integers[offset..end], previous, data.len() / FRAME_SIZE, level)?;
+
+                    data[..frames.len()].copy_from_slice(&frames);
+                    count
+                }
+                Encoding::Int32 => write_values(data, &integers[offset..end], |value| value.to_be_bytes()),
+                Encoding::Float32 => write_values(data, &samples[offset..end], |value| (*value as f32).to_be_bytes()),
+                Encoding::Float64 => write_values(data, &samples[offset..end], |value| value.to_be_bytes()),
+            };
+
+            let record_start = start + math::round(offset as f64 * 1000.0 / sample_rate) as u128;
+
+            self.write_header(&mut record, record_start, sample_rate, count as u16);
+            output.extend_from_slice(&record);
+            offset += count;
+        }
+
+        Ok(output)
+    }
+
+    /// Encodes timestamped samples, the sample rate is derived from the median interval and the
+    /// series is split into separate records at gaps and overlaps.
+    pub fn encode_series(&mut self, series: &[(u128, f64)]) -> Result<Vec<u8>, MseedError> {
+        if series.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sample_rate = sample_rate(series).ok_or(MseedError::UnknownSampleRate)?;
+        let period = 1000.0 / sample_rate;
+        let mut output = Vec::new();
+        let mut segment_start = 0;
+
+        for i in 1..=series.len() {
+            let continuous = i < series.len() && {
+                let interval = series[i].0 as f64 - series[i - 1].0 as f64;
+                interval > 0.0 && interval <= period * GAP_TOLERANCE
+            };
+
+            if !continuous {
+                let values: Vec<f64> = series[segment_start..i]
+                    .iter()
+                    .map(|(_, value)| *value)
+                    .collect();
+
+                output.extend(self.encode(series[segment_start].0, sample_rate, &values)?);
+                segment_start = i;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn write_header(&mut self, record: &mut [u8], start: u128, sample_rate: f64, sample_count: u16) {
+        let (factor, multiplier) = rate_factors(sample_rate);
+
+        record[0..6].copy_from_slice(alloc::format!("{:06}", self.sequence).as_bytes());
+        record[6] = b'D';
+        record[7] = b' ';
+        write_code(&mut record[8..13], &self.stream.station);
+        write_code(&mut record[13..15], &self.stream.location);
+        write_code(&mut record[15..18], &self.stream.channel);
+        write_code(&mut record[18..20], &self.stream.network);
+        record[20..30].copy_from_slice(&btime(start));
+        record[30..32].copy_from_slice(&sample_count.to_be_bytes());
+        record[32..34].copy_from_slice(&factor.to_be_bytes());
+        record[34..36].copy_from_slice(&multiplier.to_be_bytes());
+        record[39] = 1;
+        record[44..46].copy_from_slice(&(DATA_OFFSET as u16).to_be_bytes());
+        record[46..48].copy_from_slice(&(BLOCKETTE_OFFSET as u16).to_be_bytes());
+
+        record[48..50].copy_from_slice(&1000u16.to_be_bytes());
+        record[52] = self.encoding.code();
+        record[53] = 1;
+        record[54] = self.record_length_exponent;
+
+        self.sequence = self.sequence % MAX_SEQUENCE + 1;
+    }
+}
+
+pub fn decode_record(data: &[u8]) -> Result<Record, MseedError> {
+    if data.len() < DATA_OFFSET {
+        return Err(MseedError::InvalidRecord("Record shorter than its header"));
+    }
+
+    let read_u16 = |at: usize| u16::from_be_bytes([data[at], data[at + 1]]);
+    let blockette = read_u16(46) as usize;
+
+    if blockette + 8 > data.len() || read_u16(blockette) != 1000 {
+        return Err(MseedError::InvalidRecord("Missing blockette 1000"));
+    }
+
+    if data[blockette + 5] != 1 {
+        return Err(MseedError::InvalidRecord("Only big-endian records are supported"));
+    }
+
+    let code = data[blockette + 4];
+    let encoding = Encoding::from_code(code).ok_or(MseedError::UnsupportedEncoding(code))?;
+    let exponent = data[blockette + 6];
+
+    if !(7..=16).contains(&exponent) {
+        return Err(MseedError::InvalidRecordLength(exponent));
+    }
+
+    let record_length = 1usize << exponent;
+    let data_offset = read_u16(44) as usize;
+
+    if data.len() < record_length || data_offset > record_length {
+        return Err(MseedError::InvalidRecord("Record shorter than its declared length"));
+    }
+
+    let text = |range: core::ops::Range<usize>| -> String { String::from_utf8_lossy(&data[range]).trim_end().into() };
+
+    let sample_count = read_u16(30) as usize;
+    let payload = &data[data_offset..record_length];
+
+    let samples: Vec<f64> = match encoding {
+        Encoding::Steim1 => steim::decode(payload, sample_count, SteimLevel::One)?
+            .into_iter()
+            .map(f64::from)
+            .collect(),
+        Encoding::Steim2 => steim::decode(payload, sample_count, SteimLevel::Two)?
+            .into_iter()
+            .map(f64::from)
+            .collect(),
+        Encoding::Int32 => read_values(payload, sample_count, 4, |b| {
+            i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64
+        })?,
+        Encoding::Float32 => read_values(payload, sample_count, 4, |b| {
+            f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64
+        })?,
+        Encoding::Float64 => read_values(payload, sample_count, 8, |b| {
+            f64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        })?,
+    };
+
+    Ok(Record {
+        stream: StreamId {
+            network: text(18..20),
+            station: text(8..13),
+            location: text(13..15),
+            channel: text(15..18),
+        },
+        sequence: text(0..6).parse().unwrap_or_default(),
+        start: from_btime(&data[20..30]),
+        sample_rate: rate_from_factors(read_u16(32) as i16, read_u16(34) as i16),
+        encoding,
+        record_length,
+        samples,
+    })
+}
+
+fn to_integer(value: f64) -> Result<i32, MseedError> {
+    let rounded = math::round(value);
+
+    if rounded.is_finite() && rounded >= i32::MIN as f64 && rounded <= i32::MAX as f64 {
+        Ok(rounded as i32)
+    } else {
+        Err(MseedError::SampleOutOfRange(value))
+    }
+}
+
+fn write_values<T, const N: usize>(data: &mut [u8], values: &[T], to_bytes: impl Fn(&T) -> [u8; N]) -> usize {
+    let count = values.len().min(data.len() / N);
+
+    for (chunk, value) in data.chunks_exact_mut(N).zip(&values[..count]) {
+        chunk.copy_from_slice(&to_bytes(value));
+    }
+
+    count
+}
+
+fn read_values(
+    data: &[u8],
+    count: usize,
+    width: usize,
+    from_bytes: impl Fn(&[u8]) -> f64,
+) -> Result<Vec<f64>, MseedError> {
+    if data.len() < count * width {
+        return Err(MseedError::InvalidRecord("Record holds fewer samples than the header"));
+    }
+
+    Ok(data
+        .chunks_exact(width)
+        .take(count)
+        .map(from_bytes)
+        .collect())
+}
+
+fn write_code(field: &mut [u8], code: &str) {
+    field.fill(b' ');
+    field[..code.len()].copy_from_slice(code.as_bytes());
+}
+
+/// Sample rate in Hz of millisecond timestamped samples, from the median interval between them.
+pub fn sample_rate(series: &[(u128, f64)]) -> Option<f64> {
+    let mut intervals: Vec<u128> = series
+        .windows(2)
+        .map(|w| w[1].0.saturating_sub(w[0].0))
+        .filter(|&i| i > 0)
+        .collect();
+
+    if intervals.is_empty() {
+        return None;
+    }
+
+    intervals.sort_unstable();
+    Some(1000.0 / intervals[intervals.len() / 2] as f64)
+}
+
+/// SEED sample rate factor and multiplier, positive values multiply and negative ones divide.
+fn rate_factors(sample_rate: f64) -> (i16, i16) {
+    if sample_rate >= 1.0 {
+        if math::round(sample_rate) == sample_rate && sample_rate <= i16::MAX as f64 {
+            return (sample_rate as i16, 1);
+        }
+
+        let divisor = [10_000, 1_000, 100, 10, 1]
+            .into_iter()
+            .find(|&divisor| sample_rate * divisor as f64 <= i16::MAX as f64)
+            .unwrap_or(1);
+
+        (math::round(sample_rate * divisor as f64) as i16, -divisor)
+    } else {
+        (-(math::round(1.0 / sample_rate).min(i16::MAX as f64) as i16), 1)
+    }
+}
+
+fn rate_from_factors(factor: i16, multiplier: i16) -> f64 {
+    let (factor, multiplier) = (factor as f64, multiplier as f64);
+
+    match (factor > 0.0, multiplier > 0.0) {
+        _ if factor == 0.0 || multiplier == 0.0 => 0.0,
+        (true, true) => factor * multiplier,
+        (true, false) => -factor / multiplier,
+        (false, true) => -multiplier / factor,
+        (false, false) => 1.0 / (factor * multiplier),
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_year(year: u16) -> u128 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+/// SEED BTIME of a Unix timestamp in milliseconds.
+fn btime(timestamp: u128) -> [u8; 10] {
+    let seconds = timestamp / 1000;
+    let second_of_day = seconds % 86_400;
+    let mut days = seconds / 86_400;
+    let mut year = 1970u16;
+
+    while days >= days_in_year(year) {
+        days -= days_in_year(year);
+        year += 1;
+    }
+
+    let mut btime = [0u8; 10];
+
+    btime[0..2].copy_from_slice(&year.to_be_bytes());
+    btime[2..4].copy_from_slice(&(days as u16 + 1).to_be_bytes());
+    btime[4] = (second_of_day / 3600) as u8;
+    btime[5] = (second_of_day % 3600 / 60) as u8;
+    btime[6] = (second_of_day % 60) as u8;
+    btime[8..10].copy_from_slice(&((timestamp % 1000) as u16 * 10).to_be_bytes());
+    btime
+}
+
+fn from_btime(btime: &[u8]) -> u128 {
+    let year = u16::from_be_bytes([btime[0], btime[1]]);
+    let day_of_year = u16::from_be_bytes([btime[2], btime[3]]) as u128;
+    let days: u128 = (1970..year).map(days_in_year).sum::<u128>() + day_of_year.saturating_sub(1);
+    let seconds = days * 86_400 + btime[4] as u128 * 3600 + btime[5] as u128 * 60 + btime[6] as u128;
+
+    seconds * 1000 + u16::from_be_bytes([btime[8], btime[9]]) as u128 / 10
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Channel;
+    use crate::mseed::record::{btime, from_btime, rate_factors, rate_from_factors};
+    use crate::mseed::{Encoding, MseedEncoder, MseedError, Record, StreamId, decode_record};
+    use alloc::vec::Vec;
+
+    fn stream() -> StreamId {
+        StreamId::for_sensor("SK", 12, Channel::Z, 100.0).unwrap()
+    }
+
+    fn signal(length: usize) -> Vec<f64> {
+        (0..length)
+            .map(|i| ((i * 37) % 200) as f64 - 100.0)
+            .collect()
+    }
+
+    #[test]
+    fn stream_ids() {
+        assert_eq!(alloc::format!("{}", stream()), "SK.12.00.HNZ");
+        assert_eq!(
+            StreamId::for_sensor("SK", 3, Channel::E, 20.0)
+                .unwrap()
+                .channel,
+            "BNE"
+        );
+        assert_eq!(
+            StreamId::for_sensor("SK", 123_456, Channel::Z, 100.0).err(),
+            Some(MseedError::InvalidStreamId)
+        );
+    }
+
+    #[test]
+    fn btime_conversion() {
+        // 2024-03-01T12:34:56.789Z, day 61 of a leap year.
+        let timestamp = 1_709_296_496_789;
+        let encoded = btime(timestamp);
+
+        assert_eq!(u16::from_be_bytes([encoded[0], encoded[1]]), 2024);
+        assert_eq!(u16::from_be_bytes([encoded[2], encoded[3]]), 61);
+        assert_eq!(&encoded[4..7], &[12, 34, 56]);
+        assert_eq!(from_btime(&encoded), timestamp);
+    }
+
+    #[test]
+    fn sample_rate_factors() {
+        for rate in [100.0, 0.1, 12.5, 1.0] {
+            let (factor, multiplier) = rate_factors(rate);
+            assert!((rate_from_factors(factor, multiplier) - rate).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn round_trip_records() {
+        let samples = signal(2000);
+
+        for encoding in [
+            Encoding::Steim1,
+            Encoding::Steim2,
+            Encoding::Int32,
+            Encoding::Float32,
+            Encoding::Float64,
+        ] {
+            let mut encoder = MseedEncoder::new(stream(), encoding);
+            let output = encoder.encode(1_000_000, 100.0, &samples).unwrap();
+            let mut decoded = Vec::new();
+            let mut expected_start = 1_000_000;
+
+            assert_eq!(output.len() % 512, 0);
+
+            for (i, data) in output.chunks(512).enumerate() {
+                let record = decode_record(data).unwrap();
+
+                assert_eq!(record.stream, stream());
+                assert_eq!(record.sequence, i as u32 + 1);
+                assert_eq!(record.encoding, encoding);
+                assert_eq!(record.sample_rate, 100.0);
+                assert_eq!(record.start, expected_start);
+
+                expected_start += record.samples.len() as u128 * 10;
+                decoded.extend(record.samples);
+            }
+
+            assert_eq!(decoded, samples);
+        }
+    }
+
+    #[test]
+    fn caps_samples_of_large_records() {
+        // Steim2 packs seven unchanged samples per word, a 64 KiB record could hold about 107k of them
+        let samples: Vec<f64> = (0..150_000).map(|i| (i / 1000) as f64).collect();
+        let mut encoder = MseedEncoder::new(stream(), Encoding::Steim2)
+            .record_length(16)
+            .unwrap();
+        let output = encoder.encode(0, 100.0, &samples).unwrap();
+        let records: Vec<Record> = output
+            .chunks(65536)
+            .map(|data| decode_record(data).unwrap())
+            .collect();
+
+        assert_eq!(records[0].samples.len(), u16::MAX as usize);
+        assert_eq!(records[1].start, u16::MAX as u128 * 10);
+        assert_eq!(
+            records
+                .into_iter()
+                .flat_map(|record| record.samples)
+                .collect::<Vec<_>>(),
+            samples
+        );
+    }
+
+    #[test]
+    fn series_split_at_gaps() {
+        let mut series: Vec<(u128, f64)> = (0..50).map(|i| (i * 10, i as f64)).collect();
+        series.extend((0..50).map(|i| (2000 + i * 10, i as f64)));
+
+        let mut encoder = MseedEncoder::new(stream(), Encoding::Steim2);
+        let output = encoder.encode_series(&series).unwrap();
+        let starts: Vec<u128> = output
+            .chunks(512)
+            .map(|data| decode_record(data).unwrap().start)
+            .collect();
+
+        assert_eq!(starts, [0, 2000]);
+        assert_eq!(
+            encoder.encode_series(&[(0, 1.0)]).err(),
+            Some(MseedError::UnknownSampleRate)
+        );
+        assert_eq!(
+            encoder.encode(0, 100.0, &[f64::MAX]).err(),
+            Some(MseedError::SampleOutOfRange(f64::MAX))
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::mseed::MseedError;
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub(crate) const FRAME_SIZE: usize = 64;
+const WORDS_PER_FRAME: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SteimLevel {
+    One,
+    Two,
+}
+
+/// (values per word, bits per value, nibble, dnib), ordered from the densest packing.
+const STEIM2_PACKINGS: [(usize, u32, u32, u32); 7] = [
+    (7, 4, 0b11, 0b10),
+    (6, 5, 0b11, 0b01),
+    (5, 6, 0b11, 0b00),
+    (4, 8, 0b01, 0b00),
+    (3, 10, 0b10, 0b11),
+    (2, 15, 0b10, 0b10),
+    (1, 30, 0b10, 0b01),
+];
+
+const STEIM1_PACKINGS: [(usize, u32, u32); 3] = [(4, 8, 0b01), (2, 16, 0b10), (1, 32, 0b11)];
+
+/// Packs as many samples as fit into `frames` frames, the first difference is taken against `previous`.
+/// Returns the number of packed samples and the frames.
+pub(crate) fn encode(
+    samples: &[i32],
+    previous: Option<i32>,
+    frames: usize,
+    level: SteimLevel,
+) -> Result<(usize, Vec<u8>), MseedError> {
+    let mut words = vec![0u32; frames * WORDS_PER_FRAME];
+    let differences: Vec<i64> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| {
+            let before = if i == 0 {
+                previous.unwrap_or(sample)
+            } else {
+                samples[i - 1]
+            };
+            sample as i64 - before as i64
+        })
+        .collect();
+
+    let mut packed = 0;
+
+    'frames: for frame in 0..frames {
+        let first_word = if frame == 0 { 3 } else { 1 };
+
+        for word in first_word..WORDS_PER_FRAME {
+            if packed >= differences.len() {
+                break 'frames;
+            }
+
+            let (nibble, value, count) = match level {
+                SteimLevel::One => pack_steim1(&differences[packed..])?,
+                SteimLevel::Two => pack_steim2(&differences[packed..])?,
+            };
+
+            words[frame * WORDS_PER_FRAME] |= nibble << (30 - 2 * word);
+            words[frame * WORDS_PER_FRAME + word] = value;
+            packed += count;
+        }
+    }
+
+    if packed > 0 {
+        words[1] = samples[0] as u32;
+        words[2] = samples[packed - 1] as u32;
+    }
+
+    let bytes = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+
+    Ok((packed, bytes))
+}
+
+pub(crate) fn decode(data: &[u8], sample_count: usize, level: SteimLevel) -> Result<Vec<i32>, MseedError> {
+    let words: Vec<u32> = data
+        .chunks_exact(4)
+        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+
+    if sample_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    if words.len() < 3 {
+        return Err(MseedError::InvalidRecord("Steim data is too short"));
+    }
+
+    let mut differences: Vec<i32> = Vec::with_capacity(sample_count);
+
+    for (frame, frame_words) in words.chunks_exact(WORDS_PER_FRAME).enumerate() {
+        let first_word = if frame == 0 { 3 } else { 1 };
+
+        for (word, &value) in frame_words.iter().enumerate().skip(first_word) {
+            let nibble = (frame_words[0] >> (30 - 2 * word)) & 0b11;
+
+            match level {
+                SteimLevel::One => unpack_steim1(nibble, value, &mut differences),
+                SteimLevel::Two => unpack_steim2(nibble, value, &mut differences)?,
+            }
+        }
+    }
+
+    if differences.len() < sample_count {
+        return Err(MseedError::InvalidRecord(
+            "Steim data holds fewer samples than the header",
+        ));
+    }
+
+    let mut samples = Vec::with_capacity(sample_count);
+    let mut sample = words[1] as i32;
+
+    samples.push(sample);
+
+    for &difference in &differences[1..sample_count] {
+        sample = sample.wrapping_add(difference);
+        samples.push(sample);
+    }
+
+    if sample != words[2] as i32 {
+        return Err(MseedError::InvalidRecord("Steim reverse integration constant mismatch"));
+    }
+
+    Ok(samples)
+}
+
+fn fits(value: i64, bits: u32) -> bool {
+    let limit = 1i64 << (bits - 1);
+    (-limit..limit).contains(&value)
+}
+
+fn pack_values(values: &[i64], bits: u32) -> u32 {
+    let mask = if bits == 32 { u32::MAX } else { (1u32 << bits) - 1 };
+
+    values.iter().fold(0u32, |word, &value| {
+        (word.checked_shl(bits).unwrap_or(0)) | (value as u32 & mask)
+    })
+}
+
+fn unpack_values(word: u32, count: usize, bits: u32, output: &mut Vec<i32>) {
+    for i in 0..count {
+        let shift = bits * (count - 1 - i) as u32;
+        let value = (word >> shift) << (32 - bits);
+
+        output.push((value as i32) >> (32 - bits));
+    }
+}
+
+fn pack_steim1(differences: &[i64]) -> Result<(u32, u32, usize), MseedError> {
+    STEIM1_PACKINGS
+        .iter()
+        .find(|(count, bits, _)| differences.len() >= *count && differences[..*count].iter().all(|&d| fits(d, *bits)))
+        .map(|&(count, bits, nibble)| (nibble, pack_values(&differences[..count], bits), count))
+        .ok_or(MseedError::DifferenceOutOfRange)
+}
+
+fn pack_steim2(differences: &[i64]) -> Result<(u32, u32, usize), MseedError> {
+    STEIM2_PACKINGS
+        .iter()
+        .find(|(count, bits, _, _)| {
+            differences.len() >= *count && differences[..*count].iter().all(|&d| fits(d, *bits))
+        })
+        .map(|&(count, bits, nibble, dnib)| {
+            let values = pack_values(&differences[..count], bits);
+            let word = if nibble == 0b01 { values } else { (dnib << 30) | values };
+
+            (nibble, word, count)
+        })
+        .ok_or(MseedError::DifferenceOutOfRange)
+}
+
+fn unpack_steim1(nibble: u32, word: u32, output: &mut Vec<i32>) {
+    match nibble {
+        0b01 => unpack_values(word, 4, 8, output),
+        0b10 => unpack_values(word, 2, 16, output),
+        0b11 => output.push(word as i32),
+        _ => {}
+    }
+}
+
+fn unpack_steim2(nibble: u32, word: u32, output: &mut Vec<i32>) -> Result<(), MseedError> {
+    let dnib = word >> 30;
+
+    match (nibble, dnib) {
+        (0b00, _) => {}
+        (0b01, _) => unpack_values(word, 4, 8, output),
+        (0b10, 0b01) => unpack_values(word, 1, 30, output),
+        (0b10, 0b10) => unpack_values(word, 2, 15, output),
+        (0b10, 0b11) => unpack_values(word, 3, 10, output),
+        (0b11, 0b00) => unpack_values(word, 5, 6, output),
+        (0b11, 0b01) => unpack_values(word, 6, 5, output),
+        (0b11, 0b10) => unpack_values(word, 7, 4, output),
+        _ => return Err(MseedError::InvalidRecord("Invalid Steim-2 dnib")),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mseed::MseedError;
+    use crate::mseed::steim::{FRAME_SIZE, SteimLevel, decode, encode};
+    use alloc::vec::Vec;
+
+    fn samples() -> Vec<i32> {
+        let mut value: i32 = 1000;
+
+        (0..600)
+            .map(|i| {
+                value += match i % 7 {
+                    0 => 3,
+                    1 => -120,
+                    2 => 30_000,
+                    3 => -29_990,
+                    4 => 400,
+                    5 => -5,
+                    _ => 0,
+                };
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for level in [SteimLevel::One, SteimLevel::Two] {
+            let samples = samples();
+            let (count, frames) = encode(&samples, None, 7, level).unwrap();
+            let decoded = decode(&frames, count, level).unwrap();
+
+            assert_eq!(frames.len(), 7 * FRAME_SIZE);
+            assert!(count > 0 && count < samples.len());
+            assert_eq!(decoded, samples[..count]);
+        }
+    }
+
+    #[test]
+    fn steim2_packs_small_differences_densely() {
+        let samples: Vec<i32> = (0..1000).map(|i| i % 3).collect();
+        let (steim1, _) = encode(&samples, None, 7, SteimLevel::One).unwrap();
+        let (steim2, frames) = encode(&samples, None, 7, SteimLevel::Two).unwrap();
+
+        assert_eq!(steim1, 4 * (7 * 15 - 2));
+        assert_eq!(steim2, 7 * (7 * 15 - 2));
+        assert_eq!(decode(&frames, steim2, SteimLevel::Two).unwrap(), samples[..steim2]);
+    }
+
+    #[test]
+    fn steim2_rejects_large_differences() {
+        let samples = [0, 1 << 30];
+
+        assert_eq!(
+            encode(&samples, None, 1, SteimLevel::Two).err(),
+            Some(MseedError::DifferenceOutOfRange)
+        );
+        assert!(encode(&samples, None, 1, SteimLevel::One).is_ok());
+    }
+}
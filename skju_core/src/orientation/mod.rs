@@ -0,0 +1,277 @@
+use crate::common::Sample;
+use crate::math;
+use core::f64::consts::PI;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Mounting of a sensor, `gravity` is the mean acceleration in the sensor axes over a quiet period and
+/// `azimuth` the clockwise angle in degrees from North of the leveled sensor `n` axis.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Orientation {
+    pub gravity: Sample,
+    #[serde(default)]
+    pub azimuth: Option<f64>,
+}
+
+/// Rotation from the sensor axes into the East, North and vertical frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotation {
+    matrix: [[f64; 3]; 3],
+}
+
+/// Accumulates quiet-period samples to estimate the gravity vector.
+#[derive(Debug, Clone)]
+pub struct OrientationCalibration {
+    min_samples: usize,
+    /// Largest standard deviation of the acceleration magnitude, relative to its mean, for a quiet period.
+    max_deviation: f64,
+    count: usize,
+    sum: Sample,
+    magnitude_sum: f64,
+    magnitude_square_sum: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrientationError {
+    NotEnoughSamples(usize),
+    NoGravity,
+    NotQuiet(f64),
+}
+
+impl Orientation {
+    pub fn new(gravity: Sample) -> Orientation {
+        Orientation { gravity, azimuth: None }
+    }
+
+    pub fn azimuth(mut self, degrees: f64) -> Self {
+        self.azimuth = Some(degrees);
+        self
+    }
+
+    /// Angle in degrees between the sensor `z` axis and the vertical.
+    pub fn tilt(&self) -> f64 {
+        let horizontal = self.gravity.horizontal();
+
+        math::atan2(horizontal, self.gravity.z) * 180.0 / PI
+    }
+
+    /// Levels the axes so that gravity points along `z`, then turns them to the azimuth. Without an azimuth
+    /// the leveled `n` axis is assumed to point North.
+    pub fn rotation(&self) -> Result<Rotation, OrientationError> {
+        let magnitude = self.gravity.magnitude();
+
+        if magnitude == 0.0 || !magnitude.is_finite() {
+            return Err(OrientationError::NoGravity);
+        }
+
+        let up = [
+            self.gravity.e / magnitude,
+            self.gravity.n / magnitude,
+            self.gravity.z / magnitude,
+        ];
+        let level = level(up);
+        let (sin, cos) = math::sin_cos(self.azimuth.unwrap_or(0.0) * PI / 180.0);
+        let yaw = [[cos, sin, 0.0], [-sin, cos, 0.0], [0.0, 0.0, 1.0]];
+
+        Ok(Rotation { matrix: multiply(&yaw, &level) })
+    }
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
+    /// Maps a sample in the sensor axes into East, North and vertical.
+    pub fn apply(&self, sample: Sample) -> Sample {
+        let vector = [sample.e, sample.n, sample.z];
+        let row = |i: usize| (0..3).map(|j| self.matrix[i][j] * vector[j]).sum::<f64>();
+
+        Sample::new(row(0), row(1), row(2))
+    }
+}
+
+impl OrientationCalibration {
+    pub fn new(min_samples: usize) -> Self {
+        Self {
+            min_samples,
+            max_deviation: 0.02,
+            count: 0,
+            sum: Sample::default(),
+            magnitude_sum: 0.0,
+            magnitude_square_sum: 0.0,
+        }
+    }
+
+    pub fn max_deviation(mut self, max_deviation: f64) -> Self {
+        self.max_deviation = max_deviation;
+        self
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        let magnitude = sample.magnitude();
+
+        self.count += 1;
+        self.sum = Sample::new(self.sum.e + sample.e, self.sum.n + sample.n, self.sum.z + sample.z);
+        self.magnitude_sum += magnitude;
+        self.magnitude_square_sum += magnitude * magnitude;
+    }
+
+    pub fn finish(&self) -> Result<Orientation, OrientationError> {
+        if self.count < self.min_samples.max(1) {
+            return Err(OrientationError::NotEnoughSamples(self.count));
+        }
+
+        let count = self.count as f64;
+        let mean_magnitude = self.magnitude_sum / count;
+
+        if mean_magnitude == 0.0 {
+            return Err(OrientationError::NoGravity);
+        }
+
+        let variance = (self.magnitude_square_sum / count - mean_magnitude * mean_magnitude).max(0.0);
+        let deviation = math::sqrt(variance) / mean_magnitude;
+
+        if deviation > self.max_deviation {
+            return Err(OrientationError::NotQuiet(deviation));
+        }
+
+        let gravity = Sample::new(self.sum.e / count, self.sum.n / count, self.sum.z / count);
+
+        Ok(Orientation::new(gravity))
+    }
+}
+
+impl fmt::Display for OrientationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrientationError::NotEnoughSamples(count) => write!(formatter, "Not enough quiet samples, got {count}"),
+            OrientationError::NoGravity => write!(formatter, "Gravity vector is zero"),
+            OrientationError::NotQuiet(deviation) => {
+                write!(formatter, "Sensor is moving, relative deviation {deviation}")
+            }
+        }
+    }
+}
+
+/// Rodrigues rotation taking the unit vector `up` onto the `z` axis.
+fn level(up: [f64; 3]) -> [[f64; 3]; 3] {
+    // axis = up × z, |axis| = sin(angle), up · z = cos(angle)
+    let axis = [up[1], -up[0], 0.0];
+    let sin = math::hypot(axis[0], axis[1]);
+    let cos = up[2];
+
+    if sin < 1e-12 {
+        return if cos > 0.0 {
+            Rotation::IDENTITY.matrix
+        } else {
+            [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]]
+        };
+    }
+
+    let k = [axis[0] / sin, axis[1] / sin, axis[2] / sin];
+    let skew = [[0.0, -k[2], k[1]], [k[2], 0.0, -k[0]], [-k[1], k[0], 0.0]];
+    let skew_squared = multiply(&skew, &skew);
+    let mut matrix = Rotation::IDENTITY.matrix;
+
+    for i in 0..3 {
+        for j in 0..3 {
+            matrix[i][j] += sin * skew[i][j] + (1.0 - cos) * skew_squared[i][j];
+        }
+    }
+
+    matrix
+}
+
+fn multiply(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut result = [[0.0; 3]; 3];
+
+    for i in 0..3 {
+        for j in 0..3 {
+            result[i][j] = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Sample;
+    use crate::consts::GRAVITY;
+    use crate::orientation::{Orientation, OrientationCalibration, OrientationError, Rotation};
+
+    fn assert_sample_close(actual: Sample, expected: Sample) {
+        let difference = Sample::new(actual.e - expected.e, actual.n - expected.n, actual.z - expected.z);
+
+        assert!(difference.magnitude() < 1e-9, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn flat_sensor_is_not_rotated() {
+        let orientation = Orientation::new(Sample::new(0.0, 0.0, GRAVITY));
+
+        assert_eq!(orientation.rotation(), Ok(Rotation::IDENTITY));
+        assert_eq!(orientation.tilt(), 0.0);
+    }
+
+    #[test]
+    fn wall_mounted_sensor_is_leveled() {
+        // glued to a wall with the sensor n axis pointing up
+        let orientation = Orientation::new(Sample::new(0.0, GRAVITY, 0.0));
+        let rotation = orientation.rotation().unwrap();
+
+        assert!((orientation.tilt() - 90.0).abs() < 1e-9);
+        assert_sample_close(
+            rotation.apply(Sample::new(0.0, GRAVITY, 0.0)),
+            Sample::new(0.0, 0.0, GRAVITY),
+        );
+        assert_sample_close(rotation.apply(Sample::new(1.0, 0.0, 0.0)), Sample::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn arbitrary_tilt_maps_gravity_to_vertical() {
+        let gravity = Sample::new(3.1, -4.2, 7.9);
+        let rotation = Orientation::new(gravity).rotation().unwrap();
+        let leveled = rotation.apply(gravity);
+
+        assert_sample_close(leveled, Sample::new(0.0, 0.0, gravity.magnitude()));
+        // rotations preserve the length of any vector
+        let sample = Sample::new(0.3, 0.5, -0.1);
+        assert!((rotation.apply(sample).magnitude() - sample.magnitude()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn azimuth_turns_horizontal_axes() {
+        // leveled sensor with its n axis pointing East
+        let rotation = Orientation::new(Sample::new(0.0, 0.0, GRAVITY))
+            .azimuth(90.0)
+            .rotation()
+            .unwrap();
+
+        assert_sample_close(rotation.apply(Sample::new(0.0, 1.0, 0.0)), Sample::new(1.0, 0.0, 0.0));
+        assert_sample_close(rotation.apply(Sample::new(1.0, 0.0, 0.0)), Sample::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn calibration_estimates_gravity_from_quiet_period() {
+        let mut calibration = OrientationCalibration::new(10);
+
+        assert_eq!(calibration.finish(), Err(OrientationError::NotEnoughSamples(0)));
+
+        for i in 0..100 {
+            let noise = if i % 2 == 0 { 0.01 } else { -0.01 };
+            calibration.push(Sample::new(noise, GRAVITY + noise, noise));
+        }
+
+        let orientation = calibration.finish().unwrap();
+        assert_sample_close(orientation.gravity, Sample::new(0.0, GRAVITY, 0.0));
+
+        calibration.push(Sample::new(0.0, 3.0 * GRAVITY, 0.0));
+        assert!(matches!(calibration.finish(), Err(OrientationError::NotQuiet(_))));
+        assert_eq!(
+            Orientation::new(Sample::default()).rotation(),
+            Err(OrientationError::NoGravity)
+        );
+    }
+}
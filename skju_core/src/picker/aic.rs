@@ -0,0 +1,114 @@
+use super::Picker;
+use crate::math;
+use alloc::vec::Vec;
+
+/// Akaike Information Criterion picker, the onset splits the window into the two most distinct
+/// stationary segments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AicPicker;
+
+impl Picker for AicPicker {
+    fn onset(&self, values: &[f64]) -> Option<usize> {
+        aic(values)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_finite())
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+}
+
+/// `AIC(k) = k * ln(var(x[..k])) + (N - k - 1) * ln(var(x[k..]))` after Maeda (1985), computed directly
+/// from the samples. Indices too close to the edges to estimate a variance are infinite.
+pub fn aic(values: &[f64]) -> Vec<f64> {
+    let length = values.len();
+    let mut sums = Vec::with_capacity(length + 1);
+    let mut squares = Vec::with_capacity(length + 1);
+
+    sums.push(0.0);
+    squares.push(0.0);
+
+    for value in values {
+        sums.push(sums[sums.len() - 1] + value);
+        squares.push(squares[squares.len() - 1] + value * value);
+    }
+
+    let variance = |from: usize, to: usize| {
+        let count = (to - from) as f64;
+        let mean = (sums[to] - sums[from]) / count;
+
+        ((squares[to] - squares[from]) / count - mean * mean).max(f64::MIN_POSITIVE)
+    };
+
+    (0..length)
+        .map(|k| {
+            if k < 2 || k + 2 > length {
+                return f64::INFINITY;
+            }
+
+            k as f64 * math::ln(variance(0, k)) + (length - k - 1) as f64 * math::ln(variance(k, length))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::picker::fixtures::SyntheticQuake;
+    use crate::picker::{AicPicker, Phase, Picker, PickerConfig, PickerError, aic, pick_phases};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn minimum_is_at_variance_change() {
+        let values: Vec<f64> = (0..200)
+            .map(|i| {
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                if i < 120 { 0.01 * sign } else { sign }
+            })
+            .collect();
+
+        assert_eq!(aic(&values)[0], f64::INFINITY);
+        assert_eq!(AicPicker.onset(&values), Some(120));
+    }
+
+    #[test]
+    fn picks_p_and_s_onsets() {
+        let data = SyntheticQuake::new(300, 550).generate(3);
+        let picks = pick_phases(&AicPicker, &data, &PickerConfig::default()).unwrap();
+        let p = picks.p.unwrap();
+        let s = picks.s.unwrap();
+
+        assert_eq!(p.phase, Phase::P);
+        assert!(p.timestamp.abs_diff(3000) <= 20, "P picked at {}", p.timestamp);
+        assert_eq!(p.weight, 0);
+        assert_eq!(s.phase, Phase::S);
+        assert!(s.timestamp.abs_diff(5500) <= 50, "S picked at {}", s.timestamp);
+        assert!(s.weight <= 1);
+    }
+
+    #[test]
+    fn noisy_onsets_get_lower_weights() {
+        let clean = SyntheticQuake::new(300, 550).generate(11);
+        let noisy = SyntheticQuake::new(300, 550).noise_scale(15.0).generate(11);
+        let config = PickerConfig::default();
+        let clean = pick_phases(&AicPicker, &clean, &config).unwrap().p.unwrap();
+        let noisy = pick_phases(&AicPicker, &noisy, &config).unwrap().p.unwrap();
+
+        assert!(noisy.snr < clean.snr);
+        assert!(noisy.weight > clean.weight);
+        assert!(noisy.weight_factor() < clean.weight_factor());
+    }
+
+    #[test]
+    fn rejects_short_windows() {
+        let data = SyntheticQuake::new(300, 550).generate(3);
+
+        assert_eq!(
+            pick_phases(&AicPicker, &data[..60], &PickerConfig::new(50)).err(),
+            Some(PickerError::WindowTooShort(60))
+        );
+        assert_eq!(
+            pick_phases(&AicPicker, &data, &PickerConfig::new(0)).err(),
+            Some(PickerError::InvalidWindow)
+        );
+    }
+}
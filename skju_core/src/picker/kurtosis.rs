@@ -0,0 +1,134 @@
+use super::{Picker, PickerError};
+use alloc::vec::Vec;
+
+/// Kurtosis picker after Baillard et al. (2014). Impulsive onsets make the sliding kurtosis jump, the onset
+/// is where its cumulative rise departs the most from a steady increase.
+#[derive(Debug, Clone, Copy)]
+pub struct KurtosisPicker {
+    window: usize,
+}
+
+impl KurtosisPicker {
+    /// `window` is the length of the sliding kurtosis window in samples.
+    pub fn new(window: usize) -> Result<KurtosisPicker, PickerError> {
+        if window < 4 {
+            return Err(PickerError::InvalidWindow);
+        }
+
+        Ok(KurtosisPicker { window })
+    }
+}
+
+impl Picker for KurtosisPicker {
+    fn onset(&self, values: &[f64]) -> Option<usize> {
+        let kurtosis = kurtosis(values, self.window);
+        let first = *kurtosis.first()?;
+
+        // only the rises are kept, so that the function grows monotonically
+        let mut cumulative = Vec::with_capacity(kurtosis.len());
+        let mut total = first;
+
+        cumulative.push(total);
+
+        for pair in kurtosis.windows(2) {
+            total += (pair[1] - pair[0]).max(0.0);
+            cumulative.push(total);
+        }
+
+        let slope = (total - first) / (kurtosis.len() - 1).max(1) as f64;
+        let onset = cumulative
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (i, value - first - slope * i as f64))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)?;
+
+        // a flat characteristic function has no onset
+        (total > first).then_some(onset)
+    }
+}
+
+/// Kurtosis of the trailing `window` samples at every index, the first full window value is repeated
+/// before it.
+pub fn kurtosis(values: &[f64], window: usize) -> Vec<f64> {
+    if window == 0 || values.len() < window {
+        return Vec::new();
+    }
+
+    let full: Vec<f64> = values
+        .windows(window)
+        .map(|samples| {
+            let count = samples.len() as f64;
+            let mean = samples.iter().sum::<f64>() / count;
+            let (second, fourth) = samples.iter().fold((0.0, 0.0), |(second, fourth), value| {
+                let deviation = (value - mean) * (value - mean);
+                (second + deviation, fourth + deviation * deviation)
+            });
+            let variance = second / count;
+
+            if variance == 0.0 {
+                0.0
+            } else {
+                fourth / count / (variance * variance)
+            }
+        })
+        .collect();
+
+    let mut result = Vec::with_capacity(values.len());
+
+    result.resize(window - 1, full[0]);
+    result.extend(full);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::picker::fixtures::SyntheticQuake;
+    use crate::picker::{AicPicker, KurtosisPicker, Phase, Picker, PickerConfig, PickerError, kurtosis, pick_phases};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn rejects_short_windows() {
+        assert_eq!(KurtosisPicker::new(3).err(), Some(PickerError::InvalidWindow));
+    }
+
+    #[test]
+    fn gaussian_like_noise_has_low_kurtosis() {
+        let values: Vec<f64> = (0..100)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let curve = kurtosis(&values, 20);
+
+        assert_eq!(curve.len(), values.len());
+        assert!(curve.iter().all(|value| (value - 1.0).abs() < 1e-9));
+        assert_eq!(KurtosisPicker::new(20).unwrap().onset(&values), None);
+    }
+
+    #[test]
+    fn picks_p_and_s_onsets() {
+        let picker = KurtosisPicker::new(50).unwrap();
+        let data = SyntheticQuake::new(300, 550).generate(5);
+        let picks = pick_phases(&picker, &data, &PickerConfig::default()).unwrap();
+        let p = picks.p.unwrap();
+        let s = picks.s.unwrap();
+
+        assert_eq!(p.phase, Phase::P);
+        assert!(p.timestamp.abs_diff(3000) <= 30, "P picked at {}", p.timestamp);
+        assert_eq!(p.weight, 0);
+        assert_eq!(s.phase, Phase::S);
+        assert!(s.timestamp.abs_diff(5500) <= 50, "S picked at {}", s.timestamp);
+    }
+
+    #[test]
+    fn agrees_with_aic_picker() {
+        let data = SyntheticQuake::new(420, 700).generate(9);
+        let config = PickerConfig::default();
+        let aic = pick_phases(&AicPicker, &data, &config).unwrap().p.unwrap();
+        let kurtosis = pick_phases(&KurtosisPicker::new(50).unwrap(), &data, &config)
+            .unwrap()
+            .p
+            .unwrap();
+
+        assert!(aic.timestamp.abs_diff(kurtosis.timestamp) <= 30);
+    }
+}
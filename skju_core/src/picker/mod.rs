@@ -0,0 +1,239 @@
+mod aic;
+mod kurtosis;
+
+pub use aic::*;
+pub use kurtosis::*;
+
+use crate::common::{Channel, Coord, SensorData};
+use crate::locate::ArrivalPick;
+use crate::math;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Minimum signal to noise ratios for the weight classes 0 to 3, anything below gets weight 4.
+pub const SNR_WEIGHT_THRESHOLDS: [f64; 4] = [10.0, 5.0, 3.0, 1.5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    P,
+    S,
+}
+
+/// Phase onset, `weight` follows the HYPO71 convention from 0 (best) to 4 (unusable).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pick {
+    pub phase: Phase,
+    pub timestamp: u128,
+    pub snr: f64,
+    pub weight: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhasePicks {
+    pub p: Option<Pick>,
+    pub s: Option<Pick>,
+}
+
+/// Window lengths are expressed in samples.
+#[derive(Debug, Clone, Copy)]
+pub struct PickerConfig {
+    /// Length of the windows before and after an onset compared for the signal to noise ratio.
+    pub snr_window: usize,
+    /// Samples skipped after the P onset before searching for the S onset.
+    pub s_min_delay: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerError {
+    InvalidWindow,
+    WindowTooShort(usize),
+}
+
+/// Locates a single onset in a series of values, returning its index.
+pub trait Picker {
+    fn onset(&self, values: &[f64]) -> Option<usize>;
+}
+
+impl Pick {
+    /// Relative weight for the locator, 1 for weight class 0 down to 0 for class 4.
+    pub fn weight_factor(&self) -> f64 {
+        1.0 - self.weight as f64 / 4.0
+    }
+
+    pub fn arrival(&self, coord: Coord) -> ArrivalPick {
+        ArrivalPick { coord, timestamp: self.timestamp }
+    }
+}
+
+impl PickerConfig {
+    pub fn new(snr_window: usize) -> Self {
+        Self { snr_window, ..Self::default() }
+    }
+
+    pub fn s_min_delay(mut self, samples: usize) -> Self {
+        self.s_min_delay = samples;
+        self
+    }
+}
+
+impl Default for PickerConfig {
+    fn default() -> Self {
+        Self { snr_window: 50, s_min_delay: 20 }
+    }
+}
+
+impl fmt::Display for PickerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickerError::InvalidWindow => write!(formatter, "Picker window is too short"),
+            PickerError::WindowTooShort(length) => write!(formatter, "Not enough samples to pick, got {length}"),
+        }
+    }
+}
+
+/// Refines a trigger window into phase picks. The P onset is searched on the vertical channel, the S onset
+/// on both horizontal channels after the P onset, keeping the one with the better signal to noise ratio.
+pub fn pick_phases<P: Picker>(
+    picker: &P,
+    data: &[SensorData],
+    config: &PickerConfig,
+) -> Result<PhasePicks, PickerError> {
+    if config.snr_window == 0 {
+        return Err(PickerError::InvalidWindow);
+    }
+
+    if data.len() < 2 * config.snr_window {
+        return Err(PickerError::WindowTooShort(data.len()));
+    }
+
+    let channel = |channel: Channel| -> Vec<f64> { data.iter().map(|d| d.value.get(channel)).collect() };
+    let vertical = channel(Channel::Z);
+
+    let Some(p_index) = picker.onset(&vertical) else {
+        return Ok(PhasePicks { p: None, s: None });
+    };
+
+    let p = pick(Phase::P, data, &vertical, p_index, config);
+    let s_start = p_index + config.s_min_delay;
+
+    let s = [Channel::E, Channel::N]
+        .into_iter()
+        .filter(|_| s_start + config.snr_window < data.len())
+        .filter_map(|horizontal| {
+            let values = channel(horizontal);
+            let onset = picker.onset(&values[s_start..])? + s_start;
+
+            Some(pick(Phase::S, data, &values, onset, config))
+        })
+        .max_by(|a, b| a.snr.total_cmp(&b.snr));
+
+    Ok(PhasePicks { p: Some(p), s })
+}
+
+fn pick(phase: Phase, data: &[SensorData], values: &[f64], index: usize, config: &PickerConfig) -> Pick {
+    let snr = signal_to_noise(values, index, config.snr_window);
+    let weight = SNR_WEIGHT_THRESHOLDS
+        .iter()
+        .position(|&threshold| snr >= threshold)
+        .unwrap_or(SNR_WEIGHT_THRESHOLDS.len()) as u8;
+
+    Pick {
+        phase,
+        timestamp: data[index].timestamp,
+        snr,
+        weight,
+    }
+}
+
+/// RMS ratio of the windows after and before `index`, both relative to the mean before it.
+fn signal_to_noise(values: &[f64], index: usize, window: usize) -> f64 {
+    let noise = &values[index.saturating_sub(window)..index];
+    let signal = &values[index..(index + window).min(values.len())];
+
+    if noise.is_empty() || signal.is_empty() {
+        return 0.0;
+    }
+
+    let mean = noise.iter().sum::<f64>() / noise.len() as f64;
+    let rms = |window: &[f64]| {
+        math::sqrt(
+            window
+                .iter()
+                .map(|value| (value - mean) * (value - mean))
+                .sum::<f64>()
+                / window.len() as f64,
+        )
+    };
+    let noise_rms = rms(noise);
+
+    if noise_rms == 0.0 {
+        return f64::INFINITY;
+    }
+
+    rms(signal) / noise_rms
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::common::{Sample, SensorData};
+    use crate::trigger::test_signal::SyntheticSignal;
+    use alloc::vec::Vec;
+    use core::f64::consts::PI;
+
+    pub const SAMPLE_RATE: f64 = 100.0;
+
+    /// Noise followed by a P wavelet, strongest on the vertical channel, and an S wavelet on the horizontals.
+    pub struct SyntheticQuake {
+        pub length: usize,
+        pub p_at: usize,
+        pub s_at: usize,
+        pub noise_scale: f64,
+    }
+
+    impl SyntheticQuake {
+        pub fn new(p_at: usize, s_at: usize) -> Self {
+            Self {
+                length: 1000,
+                p_at,
+                s_at,
+                noise_scale: 1.0,
+            }
+        }
+
+        pub fn noise_scale(mut self, scale: f64) -> Self {
+            self.noise_scale = scale;
+            self
+        }
+
+        pub fn generate(&self, seed: u64) -> Vec<SensorData> {
+            let mut signal = SyntheticSignal::new(seed);
+
+            (0..self.length)
+                .map(|i| {
+                    let mut noise = || signal.noise() * self.noise_scale;
+                    let p = wavelet(i, self.p_at, 0.5, 8.0);
+                    let s = wavelet(i, self.s_at, 1.5, 3.0);
+
+                    SensorData {
+                        value: Sample::new(
+                            noise() + 0.2 * p + s,
+                            noise() + 0.2 * p - 0.8 * s,
+                            noise() + p + 0.1 * s,
+                        ),
+                        timestamp: i as u128 * 10,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    fn wavelet(i: usize, onset: usize, amplitude: f64, frequency: f64) -> f64 {
+        if i < onset {
+            return 0.0;
+        }
+
+        let t = (i - onset) as f64 / SAMPLE_RATE;
+
+        amplitude * (-t / 1.5).exp() * (2.0 * PI * frequency * t + PI / 4.0).sin()
+    }
+}
@@ -0,0 +1,250 @@
+use crate::common::{Channel, Sample};
+use crate::consts::GRAVITY;
+use crate::math;
+use crate::spectral::Complex;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::fmt;
+use core::str::FromStr;
+use mpu6500::accel::AccelRange;
+use mpu6500::gyro::GyroRange;
+use serde::{Deserialize, Serialize};
+
+/// Physical unit of a reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unit {
+    /// Raw digitizer output.
+    #[default]
+    #[serde(rename = "counts")]
+    Counts,
+    #[serde(rename = "m/s^2")]
+    MetersPerSecondSquared,
+    #[serde(rename = "rad/s")]
+    RadiansPerSecond,
+}
+
+/// Per-axis correction applied to raw counts before the sensitivity, `(counts - offset) * gain`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisCalibration {
+    pub offset: f64,
+    pub gain: f64,
+}
+
+/// Transfer function `H(s) = A0 * prod(s - zeros) / prod(s - poles)` with `s` in rad/s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolesZeros {
+    pub poles: Vec<Complex>,
+    pub zeros: Vec<Complex>,
+    /// `A0`, chosen so that `|H| = 1` at the normalization frequency.
+    pub normalization: f64,
+    pub normalization_frequency: f64,
+}
+
+/// Conversion from counts into physical units, the sensitivity is in counts per unit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentResponse {
+    pub sensitivity: f64,
+    pub unit: Unit,
+    /// Indexed by [`Channel::index`].
+    #[serde(default)]
+    pub calibration: [AxisCalibration; 3],
+    #[serde(default)]
+    pub poles_zeros: Option<PolesZeros>,
+}
+
+impl Unit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Counts => "counts",
+            Unit::MetersPerSecondSquared => "m/s^2",
+            Unit::RadiansPerSecond => "rad/s",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit {
+            "counts" => Ok(Unit::Counts),
+            "m/s^2" => Ok(Unit::MetersPerSecondSquared),
+            "rad/s" => Ok(Unit::RadiansPerSecond),
+            _ => Err(alloc::format!("Unknown unit: {unit}")),
+        }
+    }
+}
+
+impl Default for AxisCalibration {
+    fn default() -> Self {
+        AxisCalibration { offset: 0.0, gain: 1.0 }
+    }
+}
+
+impl PolesZeros {
+    pub fn new(poles: Vec<Complex>, zeros: Vec<Complex>, normalization_frequency: f64) -> PolesZeros {
+        let mut poles_zeros = PolesZeros {
+            poles,
+            zeros,
+            normalization: 1.0,
+            normalization_frequency,
+        };
+        let gain = poles_zeros.transfer(normalization_frequency).norm_squared();
+
+        poles_zeros.normalization = 1.0 / math::sqrt(gain);
+        poles_zeros
+    }
+
+    pub fn transfer(&self, frequency: f64) -> Complex {
+        let s = Complex::new(0.0, 2.0 * PI * frequency);
+        let numerator = self
+            .zeros
+            .iter()
+            .fold(Complex::new(self.normalization, 0.0), |product, &zero| {
+                product * (s - zero)
+            });
+        let denominator = self
+            .poles
+            .iter()
+            .fold(Complex::new(1.0, 0.0), |product, &pole| product * (s - pole));
+
+        numerator / denominator
+    }
+}
+
+impl InstrumentResponse {
+    pub fn new(sensitivity: f64, unit: Unit) -> InstrumentResponse {
+        InstrumentResponse {
+            sensitivity,
+            unit,
+            calibration: [AxisCalibration::default(); 3],
+            poles_zeros: None,
+        }
+    }
+
+    /// MPU6500 accelerometer at the given full scale range, converting into m/s².
+    pub fn accelerometer(range: AccelRange) -> InstrumentResponse {
+        let sensitivity = range.sensitivity() as f64 / GRAVITY;
+
+        InstrumentResponse::new(sensitivity, Unit::MetersPerSecondSquared)
+    }
+
+    /// MPU6500 gyroscope at the given full scale range, converting into rad/s.
+    pub fn gyroscope(range: GyroRange) -> InstrumentResponse {
+        let sensitivity = range.sensitivity() as f64 * 180.0 / PI;
+
+        InstrumentResponse::new(sensitivity, Unit::RadiansPerSecond)
+    }
+
+    pub fn calibration(mut self, channel: Channel, offset: f64, gain: f64) -> Self {
+        self.calibration[channel.index()] = AxisCalibration { offset, gain };
+        self
+    }
+
+    pub fn poles_zeros(mut self, poles_zeros: PolesZeros) -> Self {
+        self.poles_zeros = Some(poles_zeros);
+        self
+    }
+
+    pub fn to_physical(&self, channel: Channel, counts: f64) -> f64 {
+        let AxisCalibration { offset, gain } = self.calibration[channel.index()];
+
+        (counts - offset) * gain / self.sensitivity
+    }
+
+    pub fn convert(&self, counts: Sample) -> Sample {
+        let mut sample = counts;
+
+        for channel in Channel::ALL {
+            sample.set(channel, self.to_physical(channel, counts.get(channel)));
+        }
+
+        sample
+    }
+
+    /// Counts per unit at `frequency`, flat at the sensitivity without poles and zeros.
+    pub fn amplitude(&self, frequency: f64) -> f64 {
+        match &self.poles_zeros {
+            Some(poles_zeros) => self.sensitivity * math::sqrt(poles_zeros.transfer(frequency).norm_squared()),
+            None => self.sensitivity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::response::{InstrumentResponse, PolesZeros, Unit};
+    use crate::spectral::Complex;
+    use crate::{Channel, GRAVITY, Sample};
+    use mpu6500::accel::AccelRange;
+    use mpu6500::gyro::GyroRange;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "expected {expected} ± {tolerance}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn converts_accelerometer_counts() {
+        let response = InstrumentResponse::accelerometer(AccelRange::G2);
+        let sample = response.convert(Sample::new(0.0, -8192.0, 16384.0));
+
+        assert_eq!(response.unit, Unit::MetersPerSecondSquared);
+        assert_close(sample.e, 0.0, 1e-12);
+        assert_close(sample.n, -GRAVITY / 2.0, 1e-12);
+        assert_close(sample.z, GRAVITY, 1e-12);
+
+        let coarse = InstrumentResponse::accelerometer(AccelRange::G16);
+        assert_close(coarse.to_physical(Channel::Z, 2048.0), GRAVITY, 1e-12);
+    }
+
+    #[test]
+    fn converts_gyroscope_counts() {
+        let response = InstrumentResponse::gyroscope(GyroRange::R250dps);
+
+        assert_eq!(response.unit, Unit::RadiansPerSecond);
+        assert_close(
+            response.to_physical(Channel::E, 131.0 * 90.0),
+            core::f64::consts::FRAC_PI_2,
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn applies_axis_calibration() {
+        let response = InstrumentResponse::new(100.0, Unit::MetersPerSecondSquared).calibration(Channel::N, 50.0, 2.0);
+
+        assert_close(response.to_physical(Channel::N, 150.0), 2.0, 1e-12);
+        assert_close(response.to_physical(Channel::E, 150.0), 1.5, 1e-12);
+    }
+
+    #[test]
+    fn poles_zeros_are_normalized() {
+        // single pole low-pass with a 10 Hz corner
+        let corner = -2.0 * core::f64::consts::PI * 10.0;
+        let poles_zeros = PolesZeros::new(vec![Complex::new(corner, 0.0)], vec![], 0.1);
+        let response = InstrumentResponse::new(1000.0, Unit::MetersPerSecondSquared).poles_zeros(poles_zeros);
+
+        assert_close(response.amplitude(0.1), 1000.0, 1e-9);
+        assert_close(response.amplitude(10.0), 1000.0 / 2f64.sqrt(), 1.0);
+        assert_close(response.amplitude(1000.0), 10.0, 0.1);
+    }
+
+    #[test]
+    fn units_round_trip() {
+        for unit in [Unit::Counts, Unit::MetersPerSecondSquared, Unit::RadiansPerSecond] {
+            assert_eq!(unit.to_string().parse::<Unit>(), Ok(unit));
+        }
+
+        assert!("g".parse::<Unit>().is_err());
+    }
+}
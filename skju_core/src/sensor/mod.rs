@@ -1,27 +1,44 @@
-use crate::common::{Coord, FilterContext, LowPassFilter, SensorConfig, SensorData, SensorOutput};
-use std::collections::VecDeque;
-use std::fmt;
-use std::fmt::Display;
-use std::str::FromStr;
+use crate::common::{Channel, Coord, Filter, FilterContext, Sample, SensorConfig, SensorData, SensorOutput};
+use crate::orientation::Rotation;
+use crate::response::{InstrumentResponse, Unit};
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Display;
+use core::ops::RangeBounds;
+use core::str::FromStr;
+
+mod timing;
+mod window;
+
+pub use timing::*;
+pub use window::*;
 
 // region: Typed Fields
 pub struct WithCoord(Coord);
 pub struct NoCoord;
 
-pub struct WithFilter<T: LowPassFilter>(T);
+pub struct WithFilter<T: Filter>(T);
 pub struct NoFilter;
 
 pub struct WithCapacity(usize);
 pub struct NoCapacity;
 // endregion: Typed Fields
 
-pub struct Sensor<T: LowPassFilter> {
+pub struct Sensor<T: Filter> {
     pub id: u64,
     pub name: String,
     pub coord: Coord,
-    pub filter: T,
+    pub filters: [T; 3],
     capacity: usize,
     readings: VecDeque<SensorData>,
+    timing: Option<SampleTiming>,
+    response: Option<InstrumentResponse>,
+    rotation: Option<Rotation>,
+    resampler: Option<Resampler>,
+    last_timestamp: Option<u128>,
+    stored: u64,
 }
 
 pub struct SensorBuilder<F, U, C> {
@@ -31,6 +48,16 @@ pub struct SensorBuilder<F, U, C> {
     filter: F,
     capacity: C,
     readings: Option<VecDeque<SensorData>>,
+    timing: Option<SampleTiming>,
+    response: Option<InstrumentResponse>,
+    rotation: Option<Rotation>,
+}
+
+/// Error of the legacy `id;name;x;y` sensor line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorConfigParseError {
+    MissingField(&'static str),
+    InvalidField(&'static str),
 }
 
 #[derive(Debug, Clone)]
@@ -49,18 +76,25 @@ impl SensorBuilder<NoFilter, NoCoord, NoCapacity> {
             filter: NoFilter,
             capacity: NoCapacity,
             readings: None,
+            timing: None,
+            response: None,
+            rotation: None,
         }
     }
 }
 
 impl<U, C> SensorBuilder<NoFilter, U, C> {
-    pub fn filter<T: LowPassFilter>(self, filter: T) -> SensorBuilder<WithFilter<T>, U, C> {
+    /// Every channel is filtered independently by its own copy of `filter`.
+    pub fn filter<T: Filter + Clone>(self, filter: T) -> SensorBuilder<WithFilter<T>, U, C> {
         SensorBuilder {
             id: self.id,
             name: self.name,
             coord: self.coord,
             capacity: self.capacity,
             readings: self.readings,
+            timing: self.timing,
+            response: self.response,
+            rotation: self.rotation,
             filter: WithFilter(filter),
         }
     }
@@ -74,6 +108,9 @@ impl<F, C> SensorBuilder<F, NoCoord, C> {
             coord: WithCoord(coord),
             capacity: self.capacity,
             readings: self.readings,
+            timing: self.timing,
+            response: self.response,
+            rotation: self.rotation,
             filter: self.filter,
         }
     }
@@ -88,59 +125,191 @@ impl<F, U> SensorBuilder<F, U, NoCapacity> {
             filter: self.filter,
             capacity: WithCapacity(capacity),
             readings: Some(VecDeque::with_capacity(capacity)),
+            timing: self.timing,
+            response: self.response,
+            rotation: self.rotation,
         }
     }
 }
 
-impl<T: LowPassFilter> SensorBuilder<WithFilter<T>, WithCoord, WithCapacity> {
+impl<F, U, C> SensorBuilder<F, U, C> {
+    pub fn timing(mut self, timing: SampleTiming) -> SensorBuilder<F, U, C> {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// Incoming counts are converted into physical units before filtering.
+    pub fn response(mut self, response: InstrumentResponse) -> SensorBuilder<F, U, C> {
+        self.response = Some(response);
+        self
+    }
+
+    /// Incoming samples are rotated into East, North and vertical after the unit conversion.
+    pub fn rotation(mut self, rotation: Rotation) -> SensorBuilder<F, U, C> {
+        self.rotation = Some(rotation);
+        self
+    }
+}
+
+impl<T: Filter + Clone> SensorBuilder<WithFilter<T>, WithCoord, WithCapacity> {
     pub fn build(self) -> Sensor<T> {
         Sensor {
             id: self.id,
             name: self.name,
             coord: self.coord.0,
-            filter: self.filter.0,
+            filters: [self.filter.0.clone(), self.filter.0.clone(), self.filter.0],
             capacity: self.capacity.0,
             readings: self.readings.unwrap_or_default(),
+            resampler: self
+                .timing
+                .and_then(|timing| Some(Resampler::new(timing.sample_rate, timing.resampling?))),
+            timing: self.timing,
+            response: self.response,
+            rotation: self.rotation,
+            last_timestamp: None,
+            stored: 0,
         }
     }
 }
 
-impl<T: LowPassFilter> Sensor<T> {
+impl<T: Filter> Sensor<T> {
     pub fn is_empty(&self) -> bool {
         self.readings.is_empty()
     }
 
-    pub fn write(&mut self, value: f64, timestamp: u128) {
-        let context = FilterContext {
-            timestamp,
-            readings: &self.readings,
-            raw_value: value,
-            capacity: self.capacity,
-        };
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.timing.map(|timing| timing.sample_rate)
+    }
+
+    /// Unit of the stored readings.
+    pub fn unit(&self) -> Unit {
+        self.response
+            .as_ref()
+            .map_or(Unit::Counts, |response| response.unit)
+    }
 
-        let data = SensorData {
-            value: self.filter.apply(&context),
-            timestamp,
+    /// Filters and stores a sample, samples older than the previous one are dropped as overlaps so that the
+    /// readings stay in time order. With a [`SampleTiming`] duplicate samples are dropped as well, gaps are
+    /// reported, and resampled sensors store the grid samples as soon as they are covered.
+    pub fn write(&mut self, value: Sample, timestamp: u128) -> Option<TimingEvent> {
+        let value = match &self.response {
+            Some(response) => response.convert(value),
+            None => value,
+        };
+        let value = match &self.rotation {
+            Some(rotation) => rotation.apply(value),
+            None => value,
+        };
+        let event = match (self.timing, self.last_timestamp) {
+            (Some(timing), Some(previous)) => timing.check(previous, timestamp),
+            (None, Some(previous)) if timestamp < previous => Some(TimingEvent::Overlap { timestamp, previous }),
+            _ => None,
         };
 
+        match event {
+            Some(TimingEvent::Overlap { .. } | TimingEvent::Duplicate { .. }) => return event,
+            Some(TimingEvent::Gap { .. }) => {
+                if let Some(resampler) = &mut self.resampler {
+                    resampler.reset();
+                }
+            }
+            None => {}
+        }
+
+        self.last_timestamp = Some(timestamp);
+
+        match self.resampler.take() {
+            Some(mut resampler) => {
+                resampler.push(SensorData { value, timestamp });
+
+                while let Some(data) = resampler.pop() {
+                    self.store(data.value, data.timestamp);
+                }
+
+                self.resampler = Some(resampler);
+            }
+            None => self.store(value, timestamp),
+        }
+
+        event
+    }
+
+    fn store(&mut self, value: Sample, timestamp: u128) {
+        let mut filtered = Sample::default();
+
+        for channel in Channel::ALL {
+            let context = FilterContext {
+                channel,
+                timestamp,
+                readings: &self.readings,
+                raw_value: value.get(channel),
+                capacity: self.capacity,
+            };
+
+            filtered.set(channel, self.filters[channel.index()].apply(&context));
+        }
+
+        let data = SensorData { value: filtered, timestamp };
+
         if self.readings.len() == self.capacity {
             self.readings.pop_front();
         }
 
         self.readings.push_back(data);
+        self.stored += 1;
     }
 
-    pub fn get_latest(&mut self) -> Option<SensorOutput> {
-        let latest = self.readings.back()?;
-        let output = SensorOutput {
+    pub fn len(&self) -> usize {
+        self.readings.len()
+    }
+
+    /// Readings stored since the sensor was built, including the ones evicted or read since.
+    pub fn stored(&self) -> u64 {
+        self.stored
+    }
+
+    pub fn get_latest(&self) -> Option<SensorOutput> {
+        self.readings.back().map(|latest| self.output(latest))
+    }
+
+    /// A stored reading together with the sensor it belongs to.
+    pub fn output(&self, data: &SensorData) -> SensorOutput {
+        SensorOutput {
             sensor_id: self.id,
             sensor_name: self.name.clone(),
             sensor_coord: Coord { x: self.coord.x, y: self.coord.y },
-            value: latest.value,
-            timestamp: latest.timestamp,
+            value: data.value,
+            timestamp: data.timestamp,
+        }
+    }
+
+    /// Stored readings, oldest first.
+    pub fn readings(&self) -> impl Iterator<Item = &SensorData> {
+        self.readings.iter()
+    }
+
+    /// Every stored reading without copying or consuming them.
+    pub fn snapshot(&self) -> ReadingsWindow<'_> {
+        ReadingsWindow::new(&self.readings, 0..self.readings.len())
+    }
+
+    /// Readings of the last `duration_ms` up to and including the latest one.
+    pub fn latest_window(&self, duration_ms: u128) -> ReadingsWindow<'_> {
+        let Some(latest) = self.readings.back() else {
+            return self.snapshot();
         };
 
-        Some(output)
+        self.range(latest.timestamp.saturating_sub(duration_ms)..)
+    }
+
+    /// Readings with a timestamp within `timestamps`, e.g. `from..to` or `from..`.
+    pub fn range<R: RangeBounds<u128>>(&self, timestamps: R) -> ReadingsWindow<'_> {
+        ReadingsWindow::between(&self.readings, timestamps)
+    }
+
+    /// Stored readings split at the wrap point of the ring buffer, the second slice follows the first.
+    pub fn as_slices(&self) -> (&[SensorData], &[SensorData]) {
+        self.readings.as_slices()
     }
 
     pub fn read(&mut self) -> Option<SensorData> {
@@ -150,27 +319,31 @@ impl<T: LowPassFilter> Sensor<T> {
 
 impl Display for SensorData {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(formatter, "{};{}", self.value, self.timestamp)
+        let Sample { e, n, z } = self.value;
+        write!(formatter, "{};{};{};{}", e, n, z, self.timestamp)
     }
 }
 
+/// Accepts `e;n;z;timestamp` lines as well as legacy `value;timestamp` lines,
+/// in which case the value is treated as the vertical component.
 impl FromStr for SensorData {
     type Err = String;
 
     fn from_str(data_str: &str) -> Result<Self, Self::Err> {
-        let mut split = data_str.split(';');
-
-        let value: f64 = split
-            .next()
-            .ok_or("Missing value")?
-            .parse()
-            .map_err(|_| "Unable to parse value")?;
+        let split: Vec<&str> = data_str.split(';').collect();
+        let parse_value = |value: &str| value.parse::<f64>().map_err(|_| "Unable to parse value");
+
+        let (value, timestamp) = match split.as_slice() {
+            [z, timestamp] => (Sample::vertical(parse_value(z)?), timestamp),
+            [e, n, z, timestamp] => (
+                Sample::new(parse_value(e)?, parse_value(n)?, parse_value(z)?),
+                timestamp,
+            ),
+            [] | [_] => return Err("Missing timestamp".to_string()),
+            _ => return Err("Unexpected number of channels".to_string()),
+        };
 
-        let timestamp: u128 = split
-            .next()
-            .ok_or("Missing timestamp")?
-            .parse()
-            .map_err(|_| "Unable to parse timestamp")?;
+        let timestamp: u128 = timestamp.parse().map_err(|_| "Unable to parse timestamp")?;
 
         Ok(SensorData { value, timestamp })
     }
@@ -182,34 +355,210 @@ impl Display for SensorConfig {
     }
 }
 
+impl fmt::Display for SensorConfigParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorConfigParseError::MissingField(field) => write!(formatter, "Missing {field}"),
+            SensorConfigParseError::InvalidField(field) => write!(formatter, "Unable to parse {field}"),
+        }
+    }
+}
+
+impl core::error::Error for SensorConfigParseError {}
+
 impl FromStr for SensorConfig {
-    type Err = String;
+    type Err = SensorConfigParseError;
 
     fn from_str(config_str: &str) -> Result<Self, Self::Err> {
+        use SensorConfigParseError::{InvalidField, MissingField};
+
         let mut split = config_str.split(';');
 
         let id: u64 = split
             .next()
-            .ok_or("Missing id")?
+            .ok_or(MissingField("id"))?
             .parse()
-            .map_err(|_| "Unable to parse id")?;
+            .map_err(|_| InvalidField("id"))?;
 
-        let name: String = split.next().ok_or("Missing sensor name")?.to_string();
+        let name: String = split.next().ok_or(MissingField("sensor name"))?.to_string();
 
         let coord_x: f32 = split
             .next()
-            .ok_or("Missing coord x")?
+            .ok_or(MissingField("coord x"))?
             .parse()
-            .map_err(|_| "Unable to parse x coord")?;
+            .map_err(|_| InvalidField("x coord"))?;
 
         let coord_y: f32 = split
             .next()
-            .ok_or("Missing coord y")?
+            .ok_or(MissingField("coord y"))?
             .parse()
-            .map_err(|_| "Unable to parse y coord")?;
+            .map_err(|_| InvalidField("y coord"))?;
 
         let coord = Coord { x: coord_x, y: coord_y };
 
-        Ok(SensorConfig { id, name, coord })
+        Ok(SensorConfig::new(id, &name, coord))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Coord, Sample, SensorData};
+    use crate::filter::SinglePoleExponentialLowPass;
+    use crate::orientation::Orientation;
+    use crate::response::{InstrumentResponse, Unit};
+    use crate::sensor::{Interpolation, ReadingsWindow, SampleTiming, SensorBuilder, TimingEvent};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn parses_legacy_and_three_channel_data() {
+        let legacy: SensorData = "0.5;4242".parse().unwrap();
+        let three_channel: SensorData = "0.1;-0.2;0.3;4242".parse().unwrap();
+
+        assert_eq!(legacy.value, Sample::vertical(0.5));
+        assert_eq!(three_channel.value, Sample::new(0.1, -0.2, 0.3));
+        assert_eq!(three_channel.timestamp, 4242);
+        assert_eq!(three_channel.to_string(), "0.1;-0.2;0.3;4242");
+        assert!("0.1;0.2;4242".parse::<SensorData>().is_err());
+        assert!("0.1".parse::<SensorData>().is_err());
+    }
+
+    #[test]
+    fn filters_each_channel_independently() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(0.5))
+            .with_capacity(10)
+            .build();
+
+        sensor.write(Sample::new(1.0, 2.0, 4.0), 1);
+        sensor.write(Sample::new(3.0, 2.0, 0.0), 2);
+
+        let latest = sensor.get_latest().unwrap();
+
+        assert_eq!(latest.value, Sample::new(2.0, 2.0, 2.0));
+        assert_eq!(latest.timestamp, 2);
+    }
+
+    #[test]
+    fn converts_counts_before_filtering() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(0.5))
+            .with_capacity(10)
+            .response(InstrumentResponse::new(100.0, Unit::MetersPerSecondSquared).calibration(Channel::Z, 10.0, 1.0))
+            .build();
+
+        sensor.write(Sample::new(100.0, -200.0, 410.0), 1);
+
+        assert_eq!(sensor.unit(), Unit::MetersPerSecondSquared);
+        assert_eq!(sensor.get_latest().unwrap().value, Sample::new(1.0, -2.0, 4.0));
+    }
+
+    #[test]
+    fn rotates_samples_before_filtering() {
+        let rotation = Orientation::new(Sample::new(0.0, 9.81, 0.0))
+            .rotation()
+            .unwrap();
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(0.5))
+            .with_capacity(10)
+            .rotation(rotation)
+            .build();
+
+        sensor.write(Sample::new(0.0, 9.81, 0.0), 1);
+
+        let latest = sensor.get_latest().unwrap().value;
+        assert!(latest.horizontal() < 1e-9);
+        assert!((latest.z - 9.81).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reads_windows_without_consuming() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(1.0))
+            .with_capacity(5)
+            .build();
+
+        assert!(sensor.latest_window(100).is_empty());
+
+        for i in 0..8 {
+            sensor.write(Sample::vertical(i as f64), i * 10);
+        }
+
+        let timestamps = |window: ReadingsWindow| -> Vec<u128> { window.iter().map(|data| data.timestamp).collect() };
+        let snapshot = sensor.snapshot();
+        let (front, back) = sensor.as_slices();
+
+        assert_eq!(timestamps(snapshot), [30, 40, 50, 60, 70]);
+        assert_eq!([front, back].concat(), snapshot.to_vec());
+        assert_eq!(snapshot.get(4).unwrap().timestamp, 70);
+        assert_eq!(snapshot.duration_ms(), 40);
+        assert_eq!(snapshot.values(Channel::Z).sum::<f64>(), 25.0);
+        assert_eq!(timestamps(sensor.latest_window(20)), [50, 60, 70]);
+        assert_eq!(timestamps(sensor.range(35..60)), [40, 50]);
+        assert_eq!(timestamps(sensor.range(..=40)), [30, 40]);
+        assert!(sensor.range(80..).is_empty());
+        assert_eq!(sensor.len(), 5);
+        assert_eq!(sensor.stored(), 8);
+        assert_eq!(sensor.get_latest().unwrap().timestamp, 70);
+    }
+
+    #[test]
+    fn reports_timing_events() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(1.0))
+            .with_capacity(10)
+            .timing(SampleTiming::new(100.0).resample(Interpolation::Linear))
+            .build();
+
+        assert_eq!(sensor.write(Sample::vertical(0.0), 0), None);
+        assert_eq!(sensor.write(Sample::vertical(1.0), 12), None);
+        assert_eq!(
+            sensor.write(Sample::vertical(1.0), 12),
+            Some(TimingEvent::Duplicate { timestamp: 12 })
+        );
+        assert_eq!(
+            sensor.write(Sample::vertical(1.0), 8),
+            Some(TimingEvent::Overlap { timestamp: 8, previous: 12 })
+        );
+        assert_eq!(
+            sensor.write(Sample::vertical(5.0), 60),
+            Some(TimingEvent::Gap { from: 12, to: 60, missing: 4 })
+        );
+
+        let timestamps: Vec<u128> = core::iter::from_fn(|| sensor.read())
+            .map(|data| data.timestamp)
+            .collect();
+
+        assert_eq!(timestamps, [0, 10, 60]);
+        assert_eq!(sensor.sample_rate(), Some(100.0));
+    }
+
+    #[test]
+    fn drops_older_samples_without_timing() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(1.0))
+            .with_capacity(10)
+            .build();
+
+        assert_eq!(sensor.write(Sample::vertical(0.0), 20), None);
+        assert_eq!(sensor.write(Sample::vertical(1.0), 20), None);
+        assert_eq!(
+            sensor.write(Sample::vertical(2.0), 10),
+            Some(TimingEvent::Overlap { timestamp: 10, previous: 20 })
+        );
+        assert_eq!(sensor.write(Sample::vertical(3.0), 30), None);
+
+        let timestamps: Vec<u128> = sensor.readings().map(|data| data.timestamp).collect();
+
+        assert_eq!(timestamps, [20, 20, 30]);
+        assert_eq!(sensor.stored(), 3);
+        assert_eq!(sensor.range(..25).len(), 2);
+        assert_eq!(sensor.snapshot().duration_ms(), 10);
     }
 }
@@ -0,0 +1,321 @@
+use crate::common::{Channel, Sample, SensorData};
+use crate::math;
+use alloc::collections::VecDeque;
+use core::f64::consts::PI;
+use core::fmt;
+
+const DEFAULT_GAP_TOLERANCE: f64 = 1.5;
+
+/// Nominal sampling of a sensor, enables gap and duplicate detection in
+/// [`Sensor::write`](crate::sensor::Sensor::write).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleTiming {
+    pub sample_rate: f64,
+    /// Intervals longer than this many sample periods are reported as gaps.
+    pub gap_tolerance: f64,
+    pub resampling: Option<Interpolation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    Linear,
+    /// Lanczos windowed sinc spanning `half_width` samples on each side, delays the output by as many samples.
+    Sinc {
+        half_width: usize,
+    },
+}
+
+/// Irregularities of the incoming timestamps, overlapping and duplicate samples are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingEvent {
+    Gap { from: u128, to: u128, missing: usize },
+    Overlap { timestamp: u128, previous: u128 },
+    Duplicate { timestamp: u128 },
+}
+
+/// Interpolates irregular samples onto a grid of multiples of the sample period.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    period: f64,
+    interpolation: Interpolation,
+    raw: VecDeque<SensorData>,
+    next_time: Option<f64>,
+}
+
+impl SampleTiming {
+    pub fn new(sample_rate: f64) -> SampleTiming {
+        SampleTiming {
+            sample_rate,
+            gap_tolerance: DEFAULT_GAP_TOLERANCE,
+            resampling: None,
+        }
+    }
+
+    pub fn gap_tolerance(mut self, periods: f64) -> SampleTiming {
+        self.gap_tolerance = periods;
+        self
+    }
+
+    pub fn resample(mut self, interpolation: Interpolation) -> SampleTiming {
+        self.resampling = Some(interpolation);
+        self
+    }
+
+    /// Sample period in ms.
+    pub fn period(&self) -> f64 {
+        1000.0 / self.sample_rate
+    }
+
+    /// Classifies the interval between the `previous` and the incoming `timestamp`.
+    pub fn check(&self, previous: u128, timestamp: u128) -> Option<TimingEvent> {
+        if timestamp == previous {
+            return Some(TimingEvent::Duplicate { timestamp });
+        }
+
+        if timestamp < previous {
+            return Some(TimingEvent::Overlap { timestamp, previous });
+        }
+
+        let intervals = (timestamp - previous) as f64 / self.period();
+
+        if intervals > self.gap_tolerance {
+            return Some(TimingEvent::Gap {
+                from: previous,
+                to: timestamp,
+                // a tolerance below one period reports intervals that round down to none
+                missing: (math::round(intervals) as usize).saturating_sub(1),
+            });
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for TimingEvent {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimingEvent::Gap { from, to, missing } => {
+                write!(formatter, "Gap of {missing} samples between {from} and {to}")
+            }
+            TimingEvent::Overlap { timestamp, previous } => {
+                write!(
+                    formatter,
+                    "Sample at {timestamp} overlaps previous sample at {previous}"
+                )
+            }
+            TimingEvent::Duplicate { timestamp } => write!(formatter, "Duplicate sample at {timestamp}"),
+        }
+    }
+}
+
+impl Resampler {
+    pub fn new(sample_rate: f64, interpolation: Interpolation) -> Resampler {
+        Resampler {
+            period: 1000.0 / sample_rate,
+            interpolation,
+            raw: VecDeque::new(),
+            next_time: None,
+        }
+    }
+
+    /// Samples must be pushed in increasing timestamp order.
+    pub fn push(&mut self, data: SensorData) {
+        if self.next_time.is_none() {
+            self.next_time = Some(math::ceil(data.timestamp as f64 / self.period) * self.period);
+        }
+
+        self.raw.push_back(data);
+    }
+
+    /// Forgets the buffered samples, the grid restarts at the next pushed sample.
+    pub fn reset(&mut self) {
+        self.raw.clear();
+        self.next_time = None;
+    }
+
+    /// Next grid sample which is covered by the pushed samples.
+    pub fn pop(&mut self) -> Option<SensorData> {
+        let time = self.next_time?;
+        let value = match self.interpolation {
+            Interpolation::Linear => self.linear(time)?,
+            Interpolation::Sinc { half_width } => self.sinc(time, half_width)?,
+        };
+
+        self.next_time = Some(time + self.period);
+
+        Some(SensorData {
+            value,
+            timestamp: math::round(time) as u128,
+        })
+    }
+
+    fn linear(&mut self, time: f64) -> Option<Sample> {
+        let after = self
+            .raw
+            .iter()
+            .position(|data| data.timestamp as f64 >= time)?;
+
+        // samples before the one preceding `time` are no longer needed
+        self.raw.drain(..after.saturating_sub(1));
+
+        let after = after.min(1);
+        let next = &self.raw[after];
+
+        if after == 0 || next.timestamp as f64 == time {
+            return Some(next.value);
+        }
+
+        let previous = &self.raw[0];
+        let weight = (time - previous.timestamp as f64) / (next.timestamp - previous.timestamp) as f64;
+
+        Some(combine(previous.value, next.value, |a, b| a + (b - a) * weight))
+    }
+
+    fn sinc(&mut self, time: f64, half_width: usize) -> Option<Sample> {
+        let reach = half_width.max(1) as f64 * self.period;
+
+        if (self.raw.back()?.timestamp as f64) < time + reach {
+            return None;
+        }
+
+        let mut sum = Sample::default();
+        let mut weights = 0.0;
+
+        for data in self
+            .raw
+            .iter()
+            .filter(|data| (data.timestamp as f64 - time).abs() < reach)
+        {
+            let weight = lanczos((data.timestamp as f64 - time) / self.period, half_width.max(1) as f64);
+
+            sum = combine(sum, data.value, |total, value| total + value * weight);
+            weights += weight;
+        }
+
+        while self
+            .raw
+            .front()
+            .is_some_and(|data| (data.timestamp as f64) <= time + self.period - reach)
+        {
+            self.raw.pop_front();
+        }
+
+        if weights == 0.0 {
+            return None;
+        }
+
+        Some(combine(sum, Sample::default(), |total, _| total / weights))
+    }
+}
+
+fn combine(a: Sample, b: Sample, operation: impl Fn(f64, f64) -> f64) -> Sample {
+    let mut result = Sample::default();
+
+    for channel in Channel::ALL {
+        result.set(channel, operation(a.get(channel), b.get(channel)));
+    }
+
+    result
+}
+
+fn lanczos(x: f64, half_width: f64) -> f64 {
+    if x == 0.0 {
+        return 1.0;
+    }
+
+    let pi_x = PI * x;
+    half_width * math::sin(pi_x) * math::sin(pi_x / half_width) / (pi_x * pi_x)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Sample, SensorData};
+    use crate::sensor::{Interpolation, Resampler, SampleTiming, TimingEvent};
+    use alloc::vec::Vec;
+    use core::f64::consts::PI;
+
+    fn resample(interpolation: Interpolation, input: &[(u128, f64)]) -> Vec<SensorData> {
+        let mut resampler = Resampler::new(100.0, interpolation);
+        let mut output = Vec::new();
+
+        for &(timestamp, value) in input {
+            resampler.push(SensorData {
+                value: Sample::vertical(value),
+                timestamp,
+            });
+
+            while let Some(data) = resampler.pop() {
+                output.push(data);
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn classifies_intervals() {
+        let timing = SampleTiming::new(100.0);
+
+        assert_eq!(timing.check(100, 111), None);
+        assert_eq!(
+            timing.check(100, 150),
+            Some(TimingEvent::Gap { from: 100, to: 150, missing: 4 })
+        );
+        assert_eq!(timing.check(100, 100), Some(TimingEvent::Duplicate { timestamp: 100 }));
+        assert_eq!(
+            timing.check(100, 95),
+            Some(TimingEvent::Overlap { timestamp: 95, previous: 100 })
+        );
+    }
+
+    #[test]
+    fn tight_gap_tolerance() {
+        let timing = SampleTiming::new(100.0).gap_tolerance(0.2);
+
+        assert_eq!(
+            timing.check(100, 104),
+            Some(TimingEvent::Gap { from: 100, to: 104, missing: 0 })
+        );
+    }
+
+    #[test]
+    fn linear_resampling_onto_grid() {
+        let output = resample(Interpolation::Linear, &[(3, 0.3), (12, 1.2), (24, 2.4), (31, 3.1)]);
+        let timestamps: Vec<u128> = output.iter().map(|data| data.timestamp).collect();
+
+        assert_eq!(timestamps, [10, 20, 30]);
+
+        for data in output {
+            assert!((data.value.z - data.timestamp as f64 / 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn sinc_resampling_of_jittered_sine() {
+        let jitter = [0, 1, 0, 1, 1, 0];
+        let input: Vec<(u128, f64)> = (0..400u128)
+            .map(|i| {
+                let timestamp = i * 10 + jitter[i as usize % jitter.len()];
+                (timestamp, (2.0 * PI * timestamp as f64 / 1000.0).sin())
+            })
+            .collect();
+        let output = resample(Interpolation::Sinc { half_width: 4 }, &input);
+
+        assert_eq!(output[0].timestamp, 0);
+        assert!(
+            output
+                .windows(2)
+                .all(|pair| pair[1].timestamp - pair[0].timestamp == 10)
+        );
+
+        for data in &output[10..] {
+            let expected = (2.0 * PI * data.timestamp as f64 / 1000.0).sin();
+            assert!(
+                (data.value.z - expected).abs() < 0.01,
+                "{} at {}",
+                data.value.z,
+                data.timestamp
+            );
+        }
+    }
+}
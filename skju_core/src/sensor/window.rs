@@ -0,0 +1,104 @@
+use crate::common::{Channel, SensorData};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::iter::Chain;
+use core::ops::{Bound, Range, RangeBounds};
+use core::slice::Iter;
+
+/// Borrowed, contiguous run of stored readings, oldest first. The ring buffer may wrap, so the readings are
+/// held as up to two slices and nothing is copied until [`ReadingsWindow::to_vec`] is called.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadingsWindow<'a> {
+    front: &'a [SensorData],
+    back: &'a [SensorData],
+}
+
+impl<'a> ReadingsWindow<'a> {
+    pub(crate) fn new(readings: &'a VecDeque<SensorData>, range: Range<usize>) -> Self {
+        let (front, back) = readings.as_slices();
+        let split = front.len();
+
+        ReadingsWindow {
+            front: &front[range.start.min(split)..range.end.min(split)],
+            back: &back[range.start.saturating_sub(split)..range.end.saturating_sub(split)],
+        }
+    }
+
+    /// Readings whose timestamp lies within `timestamps`, which are stored in time order by
+    /// [`Sensor::write`](crate::sensor::Sensor::write).
+    pub(crate) fn between(readings: &'a VecDeque<SensorData>, timestamps: impl RangeBounds<u128>) -> Self {
+        let start = match timestamps.start_bound() {
+            Bound::Included(from) => readings.partition_point(|data| data.timestamp < *from),
+            Bound::Excluded(from) => readings.partition_point(|data| data.timestamp <= *from),
+            Bound::Unbounded => 0,
+        };
+        let end = match timestamps.end_bound() {
+            Bound::Included(to) => readings.partition_point(|data| data.timestamp <= *to),
+            Bound::Excluded(to) => readings.partition_point(|data| data.timestamp < *to),
+            Bound::Unbounded => readings.len(),
+        };
+
+        ReadingsWindow::new(readings, start..end.max(start))
+    }
+
+    /// The readings split at the wrap point of the ring buffer, the second slice follows the first.
+    pub fn as_slices(&self) -> (&'a [SensorData], &'a [SensorData]) {
+        (self.front, self.back)
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn first(&self) -> Option<&'a SensorData> {
+        self.front.first().or(self.back.first())
+    }
+
+    pub fn last(&self) -> Option<&'a SensorData> {
+        self.back.last().or(self.front.last())
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a SensorData> {
+        self.front
+            .get(index)
+            .or_else(|| self.back.get(index - self.front.len()))
+    }
+
+    /// Time between the first and the last reading in ms.
+    pub fn duration_ms(&self) -> u128 {
+        match (self.first(), self.last()) {
+            (Some(first), Some(last)) => last.timestamp.saturating_sub(first.timestamp),
+            _ => 0,
+        }
+    }
+
+    pub fn iter(&self) -> Chain<Iter<'a, SensorData>, Iter<'a, SensorData>> {
+        self.front.iter().chain(self.back.iter())
+    }
+
+    /// Values of a single channel, as consumed by the pickers and spectra.
+    pub fn values(&self, channel: Channel) -> impl DoubleEndedIterator<Item = f64> + 'a {
+        self.iter().map(move |data| data.value.get(channel))
+    }
+
+    pub fn to_vec(&self) -> Vec<SensorData> {
+        let mut readings = Vec::with_capacity(self.len());
+
+        readings.extend_from_slice(self.front);
+        readings.extend_from_slice(self.back);
+        readings
+    }
+}
+
+impl<'a> IntoIterator for ReadingsWindow<'a> {
+    type Item = &'a SensorData;
+    type IntoIter = Chain<Iter<'a, SensorData>, Iter<'a, SensorData>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
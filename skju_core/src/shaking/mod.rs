@@ -0,0 +1,326 @@
+//! Strong motion parameters of an acceleration record, all accelerations are in m/s²,
+//! scale readings in g by [`GRAVITY`](crate::GRAVITY).
+
+use crate::common::{Channel, Sample};
+use crate::filter::{ButterworthHighPass, FilterDesignError};
+use crate::math;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::fmt;
+
+/// Periods in seconds at which ShakeMap reports spectral acceleration.
+pub const STANDARD_PERIODS: [f64; 3] = [0.3, 1.0, 3.0];
+
+/// Fraction of the record at each end smoothed with a cosine taper before filtering.
+const TAPER_FRACTION: f64 = 0.05;
+
+#[derive(Debug, Clone)]
+pub struct ShakingConfig {
+    /// Corner of the high-pass applied to acceleration and velocity to remove baseline drift, Hz.
+    pub highpass_corner: f64,
+    pub highpass_order: u8,
+    /// Fraction of critical damping of the oscillators.
+    pub damping: f64,
+    pub periods: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralAcceleration {
+    pub period: f64,
+    pub acceleration: f64,
+}
+
+/// Peak values over all channels, PGV is in m/s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShakingSummary {
+    pub pga: f64,
+    pub pgv: f64,
+    pub spectral: Vec<SpectralAcceleration>,
+    pub mmi: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShakingError {
+    NotEnoughSamples(usize),
+    InvalidSampleRate(f64),
+    InvalidDamping(f64),
+    Filter(FilterDesignError),
+}
+
+impl Default for ShakingConfig {
+    fn default() -> Self {
+        ShakingConfig {
+            highpass_corner: 0.1,
+            highpass_order: 4,
+            damping: 0.05,
+            periods: STANDARD_PERIODS.to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for ShakingError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShakingError::NotEnoughSamples(count) => write!(formatter, "At least 2 samples required, got {count}"),
+            ShakingError::InvalidSampleRate(rate) => write!(formatter, "Invalid sample rate: {rate}"),
+            ShakingError::InvalidDamping(damping) => write!(formatter, "Damping must be in [0, 1), got {damping}"),
+            ShakingError::Filter(error) => write!(formatter, "{error}"),
+        }
+    }
+}
+
+impl From<FilterDesignError> for ShakingError {
+    fn from(error: FilterDesignError) -> Self {
+        ShakingError::Filter(error)
+    }
+}
+
+impl ShakingSummary {
+    /// Largest PGA, PGV and spectral accelerations among the three channels of `samples`.
+    /// Periods shorter than two sample intervals can not be resolved and are left out.
+    pub fn compute(
+        samples: &[Sample],
+        sample_rate: f64,
+        config: &ShakingConfig,
+    ) -> Result<ShakingSummary, ShakingError> {
+        if samples.len() < 2 {
+            return Err(ShakingError::NotEnoughSamples(samples.len()));
+        }
+
+        if !sample_rate.is_finite() || sample_rate <= 0.0 {
+            return Err(ShakingError::InvalidSampleRate(sample_rate));
+        }
+
+        if !(0.0..1.0).contains(&config.damping) {
+            return Err(ShakingError::InvalidDamping(config.damping));
+        }
+
+        let periods: Vec<f64> = config
+            .periods
+            .iter()
+            .copied()
+            .filter(|&period| period >= 2.0 / sample_rate)
+            .collect();
+
+        let mut pga: f64 = 0.0;
+        let mut pgv: f64 = 0.0;
+        let mut spectral: Vec<f64> = vec![0.0; periods.len()];
+
+        for channel in Channel::ALL {
+            let raw: Vec<f64> = samples.iter().map(|sample| sample.get(channel)).collect();
+            let acceleration = remove_drift(&raw, sample_rate, config)?;
+            let velocity = remove_drift(&integrate(&acceleration, sample_rate), sample_rate, config)?;
+
+            pga = pga.max(peak(&acceleration));
+            pgv = pgv.max(peak(&velocity));
+
+            for (value, &period) in spectral.iter_mut().zip(&periods) {
+                *value = value.max(spectral_acceleration(
+                    &acceleration,
+                    sample_rate,
+                    period,
+                    config.damping,
+                ));
+            }
+        }
+
+        Ok(ShakingSummary {
+            pga,
+            pgv,
+            spectral: periods
+                .into_iter()
+                .zip(spectral)
+                .map(|(period, acceleration)| SpectralAcceleration { period, acceleration })
+                .collect(),
+            mmi: mmi_from_pga(pga).max(mmi_from_pgv(pgv)),
+        })
+    }
+}
+
+/// Pseudo-spectral acceleration of a damped single degree of freedom oscillator with the given natural
+/// period, integrated with the Newmark average acceleration method.
+pub fn spectral_acceleration(acceleration: &[f64], sample_rate: f64, period: f64, damping: f64) -> f64 {
+    const GAMMA: f64 = 0.5;
+    const BETA: f64 = 0.25;
+
+    let dt = 1.0 / sample_rate;
+    let omega = 2.0 * PI / period;
+    let stiffness = omega * omega;
+    let viscosity = 2.0 * damping * omega;
+    let effective_stiffness = stiffness + GAMMA / (BETA * dt) * viscosity + 1.0 / (BETA * dt * dt);
+
+    let (mut displacement, mut velocity) = (0.0, 0.0);
+    let mut relative_acceleration = -acceleration.first().copied().unwrap_or_default();
+    let mut peak_displacement: f64 = 0.0;
+
+    for &ground in acceleration.iter().skip(1) {
+        let load = -ground
+            + displacement / (BETA * dt * dt)
+            + velocity / (BETA * dt)
+            + (1.0 / (2.0 * BETA) - 1.0) * relative_acceleration
+            + viscosity
+                * (GAMMA / (BETA * dt) * displacement
+                    + (GAMMA / BETA - 1.0) * velocity
+                    + dt * (GAMMA / (2.0 * BETA) - 1.0) * relative_acceleration);
+
+        let next_displacement = load / effective_stiffness;
+        let next_acceleration = (next_displacement - displacement) / (BETA * dt * dt)
+            - velocity / (BETA * dt)
+            - (1.0 / (2.0 * BETA) - 1.0) * relative_acceleration;
+
+        velocity += dt * ((1.0 - GAMMA) * relative_acceleration + GAMMA * next_acceleration);
+        displacement = next_displacement;
+        relative_acceleration = next_acceleration;
+        peak_displacement = peak_displacement.max(displacement.abs());
+    }
+
+    stiffness * peak_displacement
+}
+
+/// Modified Mercalli Intensity from PGA in m/s², Worden et al. (2012).
+pub fn mmi_from_pga(pga: f64) -> f64 {
+    let log_pga = math::log10(pga * 100.0);
+    let mmi = if log_pga <= 1.57 {
+        1.78 + 1.55 * log_pga
+    } else {
+        -1.60 + 3.70 * log_pga
+    };
+
+    clamp_mmi(mmi)
+}
+
+/// Modified Mercalli Intensity from PGV in m/s, Worden et al. (2012).
+pub fn mmi_from_pgv(pgv: f64) -> f64 {
+    let log_pgv = math::log10(pgv * 100.0);
+    let mmi = if log_pgv <= 0.53 {
+        3.78 + 1.47 * log_pgv
+    } else {
+        2.89 + 3.16 * log_pgv
+    };
+
+    clamp_mmi(mmi)
+}
+
+pub(crate) fn clamp_mmi(mmi: f64) -> f64 {
+    if mmi.is_nan() { 1.0 } else { mmi.clamp(1.0, 10.0) }
+}
+
+/// Demeans, tapers the edges and applies the high-pass forwards and backwards, so the peaks are not
+/// shifted in time.
+fn remove_drift(values: &[f64], sample_rate: f64, config: &ShakingConfig) -> Result<Vec<f64>, ShakingError> {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let taper_length = (values.len() as f64 * TAPER_FRACTION) as usize;
+    let mut filter = ButterworthHighPass::new(config.highpass_order, config.highpass_corner, sample_rate)?;
+    let mut filtered: Vec<f64> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let edge = i.min(values.len() - 1 - i);
+            let weight = if edge < taper_length {
+                0.5 * (1.0 - math::cos(PI * edge as f64 / taper_length as f64))
+            } else {
+                1.0
+            };
+
+            filter.cascade.process((value - mean) * weight)
+        })
+        .collect();
+
+    filter.cascade.reset();
+
+    for value in filtered.iter_mut().rev() {
+        *value = filter.cascade.process(*value);
+    }
+
+    Ok(filtered)
+}
+
+/// Trapezoidal integration starting from rest.
+fn integrate(values: &[f64], sample_rate: f64) -> Vec<f64> {
+    let dt = 1.0 / sample_rate;
+    let mut integral = 0.0;
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            if i > 0 {
+                integral += (values[i - 1] + value) * dt / 2.0;
+            }
+            integral
+        })
+        .collect()
+}
+
+fn peak(values: &[f64]) -> f64 {
+    values
+        .iter()
+        .fold(0.0, |peak: f64, value| peak.max(value.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Sample;
+    use crate::shaking::{
+        ShakingConfig, ShakingError, ShakingSummary, mmi_from_pga, mmi_from_pgv, spectral_acceleration,
+    };
+    use alloc::vec::Vec;
+    use core::f64::consts::PI;
+
+    const SAMPLE_RATE: f64 = 100.0;
+
+    fn sine(frequency: f64, amplitude: f64, seconds: f64) -> Vec<f64> {
+        (0..(seconds * SAMPLE_RATE) as usize)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    #[test]
+    fn peak_motion_of_sine() {
+        let samples: Vec<Sample> = sine(1.0, 2.0, 30.0)
+            .into_iter()
+            .map(Sample::vertical)
+            .collect();
+        let summary = ShakingSummary::compute(&samples, SAMPLE_RATE, &ShakingConfig::default()).unwrap();
+
+        assert!((summary.pga - 2.0).abs() < 0.1, "pga {}", summary.pga);
+        assert!((summary.pgv - 2.0 / (2.0 * PI)).abs() < 0.02, "pgv {}", summary.pgv);
+        assert_eq!(summary.spectral.len(), 3);
+        assert!(summary.spectral[1].acceleration > summary.spectral[0].acceleration);
+        assert!(summary.spectral[1].acceleration > summary.spectral[2].acceleration);
+    }
+
+    #[test]
+    fn oscillator_resonance() {
+        let acceleration = sine(1.0, 1.0, 60.0);
+        let resonant = spectral_acceleration(&acceleration, SAMPLE_RATE, 1.0, 0.05);
+        let stiff = spectral_acceleration(&acceleration, SAMPLE_RATE, 0.05, 0.05);
+
+        // steady state amplification of 1 / (2 * damping) at resonance
+        assert!((resonant - 10.0).abs() < 0.5, "resonant {resonant}");
+        assert!((stiff - 1.0).abs() < 0.05, "stiff {stiff}");
+    }
+
+    #[test]
+    fn worden_intensities() {
+        assert!((mmi_from_pga(1.0) - 5.8).abs() < 1e-9);
+        assert!((mmi_from_pgv(0.1) - 6.05).abs() < 1e-9);
+        assert_eq!(mmi_from_pga(0.0), 1.0);
+        assert_eq!(mmi_from_pga(100.0), 10.0);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let config = ShakingConfig::default();
+
+        assert_eq!(
+            ShakingSummary::compute(&[Sample::vertical(1.0)], SAMPLE_RATE, &config),
+            Err(ShakingError::NotEnoughSamples(1))
+        );
+        assert_eq!(
+            ShakingSummary::compute(&[Sample::default(); 4], 0.0, &config),
+            Err(ShakingError::InvalidSampleRate(0.0))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::math;
+use core::f64::consts::PI;
+use core::ops::{Add, Div, Mul, Sub};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn norm_squared(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, other: Complex) -> Complex {
+        let denominator = other.norm_squared();
+
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+/// In-place iterative radix-2 FFT, the length of `data` must be a power of two.
+pub fn fft(data: &mut [Complex]) {
+    let length = data.len();
+
+    if length < 2 {
+        return;
+    }
+
+    debug_assert!(length.is_power_of_two(), "FFT length must be a power of two");
+
+    let bits = length.trailing_zeros();
+
+    for i in 0..length {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+
+    while size <= length {
+        let (sin, cos) = math::sin_cos(-2.0 * PI / size as f64);
+        let step = Complex::new(cos, sin);
+
+        for start in (0..length).step_by(size) {
+            let mut twiddle = Complex::new(1.0, 0.0);
+
+            for k in 0..size / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + size / 2] * twiddle;
+
+                data[start + k] = even + odd;
+                data[start + k + size / 2] = even - odd;
+                twiddle = twiddle * step;
+            }
+        }
+
+        size *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spectral::{Complex, fft};
+    use alloc::vec::Vec;
+    use core::f64::consts::PI;
+
+    #[test]
+    fn matches_direct_dft() {
+        let input: Vec<Complex> = (0..16)
+            .map(|i| Complex::new((i as f64 * 0.7).sin() + 0.3, (i as f64 * 0.2).cos()))
+            .collect();
+        let mut output = input.clone();
+
+        fft(&mut output);
+
+        for (k, value) in output.iter().enumerate() {
+            let expected = input
+                .iter()
+                .enumerate()
+                .fold(Complex::default(), |sum, (n, x)| {
+                    let angle = -2.0 * PI * (k * n) as f64 / 16.0;
+                    sum + *x * Complex::new(angle.cos(), angle.sin())
+                });
+
+            assert!((value.re - expected.re).abs() < 1e-9 && (value.im - expected.im).abs() < 1e-9);
+        }
+    }
+}
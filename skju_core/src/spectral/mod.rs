@@ -0,0 +1,424 @@
+//! Frequency domain analysis: windowed FFTs, Welch power spectral density and spectrograms.
+
+mod fft;
+mod noise_models;
+
+pub use fft::*;
+pub use noise_models::*;
+
+use crate::common::{Channel, Filter};
+use crate::math;
+use crate::sensor::Sensor;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WelchConfig {
+    /// Samples per segment, a power of two.
+    pub segment_length: usize,
+    /// Fraction of a segment shared with the next one.
+    pub overlap: f64,
+    pub window: Window,
+}
+
+/// One-sided power spectral density, in squared input units per Hz.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psd {
+    pub frequencies: Vec<f64>,
+    pub power: Vec<f64>,
+}
+
+/// Power spectra of consecutive segments, `times` are segment centers in seconds from the first sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrogram {
+    pub times: Vec<f64>,
+    pub frequencies: Vec<f64>,
+    pub power: Vec<Vec<f64>>,
+}
+
+/// Spectrogram computed while samples arrive, emitting a column every segment step.
+#[derive(Debug, Clone)]
+pub struct RollingSpectrogram {
+    config: WelchConfig,
+    sample_rate: f64,
+    weights: Vec<f64>,
+    values: VecDeque<f64>,
+    until_next: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpectralError {
+    InvalidSegmentLength(usize),
+    InvalidOverlap(f64),
+    InvalidSampleRate(f64),
+    NotEnoughSamples { needed: usize, available: usize },
+    UnknownSampleRate,
+}
+
+impl Default for WelchConfig {
+    fn default() -> Self {
+        WelchConfig {
+            segment_length: 256,
+            overlap: 0.5,
+            window: Window::Hann,
+        }
+    }
+}
+
+impl fmt::Display for SpectralError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpectralError::InvalidSegmentLength(length) => {
+                write!(formatter, "Segment length {length} must be a power of two")
+            }
+            SpectralError::InvalidOverlap(overlap) => write!(formatter, "Overlap {overlap} must be in [0, 1)"),
+            SpectralError::InvalidSampleRate(rate) => write!(formatter, "Invalid sample rate: {rate}"),
+            SpectralError::NotEnoughSamples { needed, available } => {
+                write!(formatter, "At least {needed} samples required, got {available}")
+            }
+            SpectralError::UnknownSampleRate => write!(formatter, "Sensor has no sample rate"),
+        }
+    }
+}
+
+impl Window {
+    pub fn weights(&self, length: usize) -> Vec<f64> {
+        let denominator = length.max(2) as f64 - 1.0;
+
+        (0..length)
+            .map(|i| {
+                let phase = 2.0 * PI * i as f64 / denominator;
+
+                match self {
+                    Window::Rectangular => 1.0,
+                    Window::Hann => 0.5 - 0.5 * math::cos(phase),
+                    Window::Hamming => 0.54 - 0.46 * math::cos(phase),
+                }
+            })
+            .collect()
+    }
+}
+
+impl WelchConfig {
+    pub fn new(segment_length: usize) -> WelchConfig {
+        WelchConfig { segment_length, ..WelchConfig::default() }
+    }
+
+    pub fn overlap(mut self, overlap: f64) -> WelchConfig {
+        self.overlap = overlap;
+        self
+    }
+
+    pub fn window(mut self, window: Window) -> WelchConfig {
+        self.window = window;
+        self
+    }
+
+    /// Samples between the starts of consecutive segments.
+    pub fn step(&self) -> usize {
+        ((self.segment_length as f64 * (1.0 - self.overlap)) as usize).max(1)
+    }
+
+    fn validate(&self, sample_rate: f64) -> Result<(), SpectralError> {
+        if self.segment_length < 2 || !self.segment_length.is_power_of_two() {
+            return Err(SpectralError::InvalidSegmentLength(self.segment_length));
+        }
+
+        if !(0.0..1.0).contains(&self.overlap) {
+            return Err(SpectralError::InvalidOverlap(self.overlap));
+        }
+
+        if !sample_rate.is_finite() || sample_rate <= 0.0 {
+            return Err(SpectralError::InvalidSampleRate(sample_rate));
+        }
+
+        Ok(())
+    }
+}
+
+impl Psd {
+    /// Welch PSD of one channel of the readings held by `sensor`, which must have a sample rate.
+    pub fn from_sensor<T: Filter>(
+        sensor: &Sensor<T>,
+        channel: Channel,
+        config: &WelchConfig,
+    ) -> Result<Psd, SpectralError> {
+        let sample_rate = sensor
+            .sample_rate()
+            .ok_or(SpectralError::UnknownSampleRate)?;
+        let values: Vec<f64> = sensor.snapshot().values(channel).collect();
+
+        welch(&values, sample_rate, config)
+    }
+
+    /// Power in dB relative to one squared input unit per Hz.
+    pub fn to_db(&self) -> Vec<f64> {
+        self.power
+            .iter()
+            .map(|power| 10.0 * math::log10(*power))
+            .collect()
+    }
+}
+
+/// Averages the periodograms of overlapping, demeaned and windowed segments.
+pub fn welch(values: &[f64], sample_rate: f64, config: &WelchConfig) -> Result<Psd, SpectralError> {
+    config.validate(sample_rate)?;
+
+    let length = config.segment_length;
+
+    if values.len() < length {
+        return Err(SpectralError::NotEnoughSamples { needed: length, available: values.len() });
+    }
+
+    let weights = config.window.weights(length);
+    let mut power = vec![0.0; length / 2 + 1];
+    let mut segments = 0;
+
+    for start in (0..=values.len() - length).step_by(config.step()) {
+        let segment = periodogram(&values[start..start + length], &weights, sample_rate);
+
+        power
+            .iter_mut()
+            .zip(segment)
+            .for_each(|(total, value)| *total += value);
+        segments += 1;
+    }
+
+    power.iter_mut().for_each(|value| *value /= segments as f64);
+
+    Ok(Psd {
+        frequencies: frequencies(length, sample_rate),
+        power,
+    })
+}
+
+pub fn spectrogram(values: &[f64], sample_rate: f64, config: &WelchConfig) -> Result<Spectrogram, SpectralError> {
+    let mut rolling = RollingSpectrogram::new(sample_rate, *config)?;
+    let mut result = Spectrogram {
+        times: Vec::new(),
+        frequencies: frequencies(config.segment_length, sample_rate),
+        power: Vec::new(),
+    };
+
+    for (i, &value) in values.iter().enumerate() {
+        if let Some(column) = rolling.push(value) {
+            let center = i + 1 - config.segment_length / 2;
+
+            result.times.push(center as f64 / sample_rate);
+            result.power.push(column);
+        }
+    }
+
+    Ok(result)
+}
+
+impl RollingSpectrogram {
+    pub fn new(sample_rate: f64, config: WelchConfig) -> Result<RollingSpectrogram, SpectralError> {
+        config.validate(sample_rate)?;
+
+        Ok(RollingSpectrogram {
+            config,
+            sample_rate,
+            weights: config.window.weights(config.segment_length),
+            values: VecDeque::with_capacity(config.segment_length),
+            until_next: config.segment_length,
+        })
+    }
+
+    pub fn frequencies(&self) -> Vec<f64> {
+        frequencies(self.config.segment_length, self.sample_rate)
+    }
+
+    /// Adds a sample, returns the power spectrum of the latest segment once a new one is complete.
+    pub fn push(&mut self, value: f64) -> Option<Vec<f64>> {
+        if self.values.len() == self.config.segment_length {
+            self.values.pop_front();
+        }
+
+        self.values.push_back(value);
+        self.until_next -= 1;
+
+        if self.until_next > 0 {
+            return None;
+        }
+
+        self.until_next = self.config.step();
+
+        let (front, back) = self.values.as_slices();
+        let segment: Vec<f64> = front.iter().chain(back).copied().collect();
+
+        Some(periodogram(&segment, &self.weights, self.sample_rate))
+    }
+}
+
+fn frequencies(length: usize, sample_rate: f64) -> Vec<f64> {
+    (0..=length / 2)
+        .map(|k| k as f64 * sample_rate / length as f64)
+        .collect()
+}
+
+/// One-sided periodogram of a demeaned, windowed segment.
+fn periodogram(segment: &[f64], weights: &[f64], sample_rate: f64) -> Vec<f64> {
+    let length = segment.len();
+    let mean = segment.iter().sum::<f64>() / length as f64;
+    let scale = sample_rate * weights.iter().map(|w| w * w).sum::<f64>();
+    let mut spectrum: Vec<Complex> = segment
+        .iter()
+        .zip(weights)
+        .map(|(value, weight)| Complex::new((value - mean) * weight, 0.0))
+        .collect();
+
+    fft(&mut spectrum);
+
+    spectrum[..=length / 2]
+        .iter()
+        .enumerate()
+        .map(|(k, value)| {
+            let one_sided = if k == 0 || k == length / 2 { 1.0 } else { 2.0 };
+            one_sided * value.norm_squared() / scale
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Coord, Sample};
+    use crate::filter::SinglePoleExponentialLowPass;
+    use crate::sensor::{SampleTiming, SensorBuilder};
+    use crate::spectral::{
+        NoiseComparison, NoiseRating, Psd, SpectralError, WelchConfig, nhnm_db, nlnm_db, spectrogram, welch,
+    };
+    use alloc::vec::Vec;
+    use core::f64::consts::PI;
+
+    const SAMPLE_RATE: f64 = 100.0;
+
+    fn sine(frequency: f64, amplitude: f64, length: usize) -> Vec<f64> {
+        (0..length)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    #[test]
+    fn welch_preserves_sine_power() {
+        let psd = welch(&sine(12.5, 2.0, 4096), SAMPLE_RATE, &WelchConfig::new(256)).unwrap();
+        let resolution = psd.frequencies[1];
+        let peak = psd
+            .power
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        let total: f64 = psd.power.iter().sum::<f64>() * resolution;
+
+        assert_eq!(psd.frequencies[peak], 12.5);
+        // variance of a sine is half its squared amplitude
+        assert!((total - 2.0).abs() < 0.05, "total power {total}");
+    }
+
+    #[test]
+    fn welch_of_white_noise_is_flat() {
+        let mut state: u64 = 42;
+        let noise: Vec<f64> = (0..16384)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+            })
+            .collect();
+        let psd = welch(&noise, SAMPLE_RATE, &WelchConfig::new(128)).unwrap();
+        // uniform noise on [-0.5, 0.5) has variance 1/12 spread over the 50 Hz band
+        let expected = 1.0 / 12.0 / (SAMPLE_RATE / 2.0);
+        let mean = psd.power[1..64].iter().sum::<f64>() / 63.0;
+
+        assert!((mean - expected).abs() / expected < 0.05);
+    }
+
+    #[test]
+    fn spectrogram_follows_frequency_change() {
+        let mut values = sine(5.0, 1.0, 1024);
+        values.extend(sine(20.0, 1.0, 1024));
+
+        let result = spectrogram(&values, SAMPLE_RATE, &WelchConfig::new(128).overlap(0.0)).unwrap();
+        let peak = |column: &Vec<f64>| {
+            column
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0
+        };
+
+        assert_eq!(result.times.len(), 16);
+        assert_eq!(result.times[0], 0.64);
+        assert!((result.frequencies[peak(&result.power[0])] - 5.0).abs() < 1.0);
+        assert!((result.frequencies[peak(&result.power[15])] - 20.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn psd_of_sensor_readings() {
+        let mut sensor = SensorBuilder::new(1, "Sensor Alpha")
+            .coord(Coord { x: 0.0, y: 0.0 })
+            .filter(SinglePoleExponentialLowPass::new(1.0))
+            .with_capacity(512)
+            .timing(SampleTiming::new(SAMPLE_RATE))
+            .build();
+
+        for (i, value) in sine(12.5, 1.0, 512).into_iter().enumerate() {
+            sensor.write(Sample::new(value, 0.0, 0.0), i as u128 * 10);
+        }
+
+        let psd = Psd::from_sensor(&sensor, Channel::E, &WelchConfig::new(128)).unwrap();
+        let peak = psd
+            .power
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+
+        assert_eq!(psd.frequencies[peak], 12.5);
+        assert_eq!(
+            welch(&[0.0; 10], SAMPLE_RATE, &WelchConfig::new(100)),
+            Err(SpectralError::InvalidSegmentLength(100))
+        );
+    }
+
+    #[test]
+    fn peterson_noise_models() {
+        assert!((nlnm_db(1.0).unwrap() + 166.4).abs() < 1e-9);
+        assert!((nhnm_db(1.0).unwrap() + 116.85).abs() < 1e-9);
+        assert_eq!(nlnm_db(0.05), None);
+
+        let frequencies: Vec<f64> = (1..50).map(|i| i as f64 * 0.1).collect();
+        let between = |frequency: f64, offset: f64| {
+            let period = 1.0 / frequency;
+            let db = (nlnm_db(period).unwrap() + nhnm_db(period).unwrap()) / 2.0 + offset;
+            10f64.powf(db / 10.0)
+        };
+        let quiet = Psd {
+            power: frequencies.iter().map(|&f| between(f, 0.0)).collect(),
+            frequencies: frequencies.clone(),
+        };
+        let noisy = Psd {
+            power: frequencies.iter().map(|&f| between(f, 60.0)).collect(),
+            frequencies,
+        };
+
+        assert_eq!(NoiseComparison::new(&quiet).rating(), NoiseRating::Good);
+        assert_eq!(NoiseComparison::new(&noisy).rating(), NoiseRating::Poor);
+        assert_eq!(NoiseComparison::new(&noisy).above_high, 49);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::math;
+use crate::spectral::Psd;
+
+/// Peterson (1993) New Low Noise Model, `(period s, A, B)` with `dB = A + B * log10(period)` relative to
+/// 1 (m/s²)²/Hz, each row applies up to the next period.
+const NLNM: [(f64, f64, f64); 22] = [
+    (0.10, -162.36, 5.64),
+    (0.17, -166.7, 0.0),
+    (0.40, -170.0, -8.30),
+    (0.80, -166.4, 28.90),
+    (1.24, -168.6, 52.48),
+    (2.40, -159.98, 29.81),
+    (4.30, -141.1, 0.0),
+    (5.00, -71.36, -99.77),
+    (6.00, -97.26, -66.49),
+    (10.00, -132.18, -31.57),
+    (12.00, -205.27, 36.16),
+    (15.60, -37.65, -104.33),
+    (21.90, -114.37, -47.10),
+    (31.60, -160.58, -16.28),
+    (45.00, -187.5, 0.0),
+    (70.00, -216.47, 15.70),
+    (101.00, -185.0, 0.0),
+    (154.00, -168.34, -7.61),
+    (328.00, -217.43, 11.90),
+    (600.00, -258.28, 26.60),
+    (10_000.00, -346.88, 48.75),
+    (100_000.00, 0.0, 0.0),
+];
+
+/// Peterson (1993) New High Noise Model, same layout as [`NLNM`].
+const NHNM: [(f64, f64, f64); 12] = [
+    (0.10, -108.73, -17.23),
+    (0.22, -150.34, -80.50),
+    (0.32, -122.31, -23.87),
+    (0.80, -116.85, 32.51),
+    (3.80, -108.48, 18.08),
+    (4.60, -74.66, -32.95),
+    (6.30, 0.66, -127.18),
+    (7.90, -93.37, -22.42),
+    (15.40, 73.54, -162.98),
+    (20.00, -151.52, 10.01),
+    (354.80, -206.66, 31.63),
+    (100_000.00, 0.0, 0.0),
+];
+
+/// Share of the compared bins which must lie between the models for each rating.
+const GOOD_SHARE: f64 = 0.9;
+const FAIR_SHARE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseRating {
+    Good,
+    Fair,
+    Poor,
+}
+
+/// Station acceleration PSD against the Peterson models over the periods they cover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseComparison {
+    pub bins: usize,
+    pub below_low: usize,
+    pub above_high: usize,
+    /// Median of the PSD above the NLNM, dB.
+    pub median_above_low_db: f64,
+}
+
+/// NLNM acceleration power at `period` seconds in dB, `None` outside 0.1 s to 100000 s.
+pub fn nlnm_db(period: f64) -> Option<f64> {
+    evaluate(&NLNM, period)
+}
+
+/// NHNM acceleration power at `period` seconds in dB, `None` outside 0.1 s to 100000 s.
+pub fn nhnm_db(period: f64) -> Option<f64> {
+    evaluate(&NHNM, period)
+}
+
+fn evaluate(model: &[(f64, f64, f64)], period: f64) -> Option<f64> {
+    model
+        .windows(2)
+        .find(|rows| period >= rows[0].0 && period < rows[1].0)
+        .map(|rows| rows[0].1 + rows[0].2 * math::log10(period))
+}
+
+impl NoiseComparison {
+    /// `psd` must be an acceleration PSD in (m/s²)²/Hz, bins outside the models are skipped.
+    pub fn new(psd: &Psd) -> NoiseComparison {
+        let mut excess = alloc::vec::Vec::new();
+        let (mut below_low, mut above_high) = (0, 0);
+
+        for (&frequency, &power) in psd.frequencies.iter().zip(&psd.power) {
+            if frequency <= 0.0 || power <= 0.0 {
+                continue;
+            }
+
+            let period = 1.0 / frequency;
+            let (Some(low), Some(high)) = (nlnm_db(period), nhnm_db(period)) else {
+                continue;
+            };
+            let power_db = 10.0 * math::log10(power);
+
+            below_low += usize::from(power_db < low);
+            above_high += usize::from(power_db > high);
+            excess.push(power_db - low);
+        }
+
+        excess.sort_unstable_by(f64::total_cmp);
+
+        NoiseComparison {
+            bins: excess.len(),
+            below_low,
+            above_high,
+            median_above_low_db: excess.get(excess.len() / 2).copied().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// Rates the station by the share of bins between the low and the high noise model.
+    pub fn rating(&self) -> NoiseRating {
+        if self.bins == 0 {
+            return NoiseRating::Poor;
+        }
+
+        let within = (self.bins - self.below_low - self.above_high) as f64 / self.bins as f64;
+
+        if within >= GOOD_SHARE {
+            NoiseRating::Good
+        } else if within >= FAIR_SHARE {
+            NoiseRating::Fair
+        } else {
+            NoiseRating::Poor
+        }
+    }
+}
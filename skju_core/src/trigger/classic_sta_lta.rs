@@ -0,0 +1,162 @@
+use super::{DcBlocker, StaLtaConfig, Trigger, TriggerError, TriggerEvent, TriggerState};
+use crate::common::SensorData;
+use alloc::collections::VecDeque;
+
+/// Ratio of the mean signal energy over the short and the long sliding windows.
+/// The long window includes the short one, no ratio is produced until it is filled.
+pub struct ClassicStaLta {
+    config: StaLtaConfig,
+    dc_blocker: DcBlocker,
+    energy: VecDeque<f64>,
+    sta_sum: f64,
+    lta_sum: f64,
+    state: TriggerState,
+}
+
+impl ClassicStaLta {
+    pub fn new(config: StaLtaConfig) -> Result<ClassicStaLta, TriggerError> {
+        config.validate()?;
+
+        Ok(ClassicStaLta {
+            config,
+            dc_blocker: DcBlocker::new(config.lta_length),
+            energy: VecDeque::with_capacity(config.lta_length + 1),
+            sta_sum: 0.0,
+            lta_sum: 0.0,
+            state: TriggerState::new(&config),
+        })
+    }
+
+    pub fn ratio(&mut self, value: f64) -> Option<f64> {
+        let energy = value * value;
+
+        self.energy.push_back(energy);
+        self.sta_sum += energy;
+        self.lta_sum += energy;
+
+        let len = self.energy.len();
+
+        if len > self.config.sta_length {
+            self.sta_sum -= self.energy[len - 1 - self.config.sta_length];
+        }
+
+        if len > self.config.lta_length {
+            self.lta_sum -= self.energy.pop_front().unwrap_or_default();
+        }
+
+        // running sums may drift slightly below zero due to rounding
+        self.sta_sum = self.sta_sum.max(0.0);
+        self.lta_sum = self.lta_sum.max(0.0);
+
+        if self.energy.len() < self.config.lta_length || self.lta_sum == 0.0 {
+            return None;
+        }
+
+        let sta = self.sta_sum / self.config.sta_length as f64;
+        let lta = self.lta_sum / self.config.lta_length as f64;
+
+        Some(sta / lta)
+    }
+}
+
+impl Trigger for ClassicStaLta {
+    fn process(&mut self, data: &SensorData) -> Option<TriggerEvent> {
+        let value = self.dc_blocker.remove(data.value).magnitude();
+        let ratio = self.ratio(value)?;
+        self.state.update(ratio, data.timestamp)
+    }
+
+    fn is_triggered(&self) -> bool {
+        self.state.is_triggered()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::SensorData;
+    use crate::trigger::test_signal::SyntheticSignal;
+    use crate::trigger::{ClassicStaLta, StaLtaConfig, Trigger, TriggerError, TriggerEvent};
+    use alloc::vec::Vec;
+
+    fn config() -> StaLtaConfig {
+        StaLtaConfig::new(10, 100).trigger_on(3.5).trigger_off(1.5)
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let window = StaLtaConfig::new(100, 10);
+        let thresholds = StaLtaConfig::new(10, 100).trigger_on(1.0).trigger_off(2.0);
+
+        assert_eq!(ClassicStaLta::new(window).err(), Some(TriggerError::InvalidWindow));
+        assert_eq!(
+            ClassicStaLta::new(thresholds).err(),
+            Some(TriggerError::InvalidThresholds)
+        );
+    }
+
+    #[test]
+    fn no_ratio_before_lta_window_is_filled() {
+        let mut trigger = ClassicStaLta::new(config()).unwrap();
+
+        for _ in 0..99 {
+            assert_eq!(trigger.ratio(1.0), None);
+        }
+
+        assert_eq!(trigger.ratio(1.0), Some(1.0));
+    }
+
+    #[test]
+    fn noise_does_not_trigger() {
+        let mut trigger = ClassicStaLta::new(config()).unwrap();
+        let data = SyntheticSignal::new(42).generate(2000, 2000, 0);
+
+        assert!(trigger.process_all(&data).is_empty());
+    }
+
+    #[test]
+    fn spike_triggers_on_and_off() {
+        let mut trigger = ClassicStaLta::new(config()).unwrap();
+        let data = SyntheticSignal::new(7).generate(1000, 500, 6);
+        let events = trigger.process_all(&data);
+
+        assert_eq!(events.len(), 2);
+
+        match events[0] {
+            TriggerEvent::On { timestamp, ratio } => {
+                assert_eq!(timestamp, 5000);
+                assert!(ratio >= 3.5);
+            }
+            _ => panic!("expected trigger-on event, got {:?}", events[0]),
+        }
+
+        match events[1] {
+            TriggerEvent::Off { timestamp, peak_ratio } => {
+                assert!(timestamp > 5050 && timestamp <= 5200);
+                assert!(peak_ratio >= 3.5);
+            }
+            _ => panic!("expected trigger-off event, got {:?}", events[1]),
+        }
+
+        assert!(!trigger.is_triggered());
+    }
+
+    #[test]
+    fn ignores_gravity_offset() {
+        let with_gravity = |mut data: Vec<SensorData>| {
+            data.iter_mut().for_each(|d| d.value.z += 9.81);
+            data
+        };
+        let mut trigger = ClassicStaLta::new(config()).unwrap();
+
+        assert!(
+            trigger
+                .process_all(&with_gravity(SyntheticSignal::new(42).generate(2000, 2000, 0)))
+                .is_empty()
+        );
+
+        let mut trigger = ClassicStaLta::new(config()).unwrap();
+        let events = trigger.process_all(&with_gravity(SyntheticSignal::new(7).generate(1000, 500, 6)));
+
+        assert!(matches!(events[0], TriggerEvent::On { timestamp: 5000, .. }));
+    }
+}
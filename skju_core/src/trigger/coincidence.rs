@@ -0,0 +1,274 @@
+use super::TriggerEvent;
+use crate::common::Coord;
+use crate::consts::P_WAVE;
+use crate::math;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Times are in ms, the velocity in km/s and sensor coordinates in km.
+#[derive(Debug, Clone, Copy)]
+pub struct CoincidenceConfig {
+    pub min_sensors: usize,
+    /// Allowed on top of the travel time between two sensors, covers trigger delays and clock errors.
+    pub tolerance_ms: u128,
+    /// Sensor triggers are ignored for this long after a network event is declared.
+    pub hold_off_ms: u128,
+    pub velocity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkEvent {
+    /// Earliest trigger of the coincident sensors.
+    pub timestamp: u128,
+    pub declared_at: u128,
+    pub sensor_ids: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoincidenceError {
+    NotEnoughSensors { required: usize, available: usize },
+    UnknownSensor(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SensorTrigger {
+    sensor_id: u64,
+    coord: Coord,
+    timestamp: u128,
+}
+
+/// Declares a network event once `min_sensors` of the registered sensors trigger within the P wave
+/// travel time between each pair of them, suppressing triggers local to a single sensor.
+pub struct CoincidenceTrigger {
+    config: CoincidenceConfig,
+    sensors: Vec<(u64, Coord)>,
+    pending: Vec<SensorTrigger>,
+    max_span_ms: u128,
+    hold_until: Option<u128>,
+}
+
+impl CoincidenceConfig {
+    pub fn new(min_sensors: usize) -> Self {
+        Self { min_sensors, ..Self::default() }
+    }
+
+    pub fn tolerance_ms(mut self, tolerance_ms: u128) -> Self {
+        self.tolerance_ms = tolerance_ms;
+        self
+    }
+
+    pub fn hold_off_ms(mut self, hold_off_ms: u128) -> Self {
+        self.hold_off_ms = hold_off_ms;
+        self
+    }
+
+    pub fn velocity(mut self, velocity: f64) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    fn travel_time_ms(&self, a: Coord, b: Coord) -> u128 {
+        let distance = math::hypot((a.x - b.x) as f64, (a.y - b.y) as f64);
+
+        math::ceil(distance / self.velocity * 1000.0) as u128
+    }
+}
+
+impl Default for CoincidenceConfig {
+    fn default() -> Self {
+        Self {
+            min_sensors: 3,
+            tolerance_ms: 200,
+            hold_off_ms: 30_000,
+            velocity: P_WAVE,
+        }
+    }
+}
+
+impl fmt::Display for CoincidenceError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoincidenceError::NotEnoughSensors { required, available } => {
+                write!(
+                    formatter,
+                    "Coincidence of {required} sensors required, only {available} available"
+                )
+            }
+            CoincidenceError::UnknownSensor(id) => write!(formatter, "Unknown sensor {id}"),
+        }
+    }
+}
+
+impl CoincidenceTrigger {
+    pub fn new(config: CoincidenceConfig, sensors: Vec<(u64, Coord)>) -> Result<CoincidenceTrigger, CoincidenceError> {
+        if config.min_sensors == 0 || config.min_sensors > sensors.len() {
+            return Err(CoincidenceError::NotEnoughSensors {
+                required: config.min_sensors,
+                available: sensors.len(),
+            });
+        }
+
+        let max_span_ms = sensors
+            .iter()
+            .flat_map(|(_, a)| sensors.iter().map(|(_, b)| config.travel_time_ms(*a, *b)))
+            .max()
+            .unwrap_or_default()
+            + config.tolerance_ms;
+
+        Ok(CoincidenceTrigger {
+            config,
+            sensors,
+            pending: Vec::new(),
+            max_span_ms,
+            hold_until: None,
+        })
+    }
+
+    /// Feeds the trigger events of a single sensor, only trigger-on events take part in the coincidence.
+    pub fn process(&mut self, sensor_id: u64, event: &TriggerEvent) -> Result<Option<NetworkEvent>, CoincidenceError> {
+        let TriggerEvent::On { timestamp, .. } = *event else {
+            return Ok(None);
+        };
+
+        let coord = self
+            .sensors
+            .iter()
+            .find(|(id, _)| *id == sensor_id)
+            .map(|(_, coord)| *coord)
+            .ok_or(CoincidenceError::UnknownSensor(sensor_id))?;
+
+        if self
+            .hold_until
+            .is_some_and(|hold_until| timestamp < hold_until)
+        {
+            return Ok(None);
+        }
+
+        let trigger = SensorTrigger { sensor_id, coord, timestamp };
+        let max_span_ms = self.max_span_ms;
+
+        self.pending
+            .retain(|pending| pending.sensor_id != sensor_id && pending.timestamp.abs_diff(timestamp) <= max_span_ms);
+
+        let mut candidates = self.pending.clone();
+        candidates.sort_by_key(|pending| pending.timestamp.abs_diff(timestamp));
+
+        let mut coincident = Vec::from([trigger]);
+
+        for candidate in candidates {
+            if coincident
+                .iter()
+                .all(|member| self.is_consistent(member, &candidate))
+            {
+                coincident.push(candidate);
+            }
+        }
+
+        self.pending.push(trigger);
+
+        if coincident.len() < self.config.min_sensors {
+            return Ok(None);
+        }
+
+        self.pending.clear();
+        self.hold_until = Some(timestamp + self.config.hold_off_ms);
+        coincident.sort_by_key(|member| member.timestamp);
+
+        Ok(Some(NetworkEvent {
+            timestamp: coincident[0].timestamp,
+            declared_at: timestamp,
+            sensor_ids: coincident.iter().map(|member| member.sensor_id).collect(),
+        }))
+    }
+
+    fn is_consistent(&self, a: &SensorTrigger, b: &SensorTrigger) -> bool {
+        let allowed = self.config.travel_time_ms(a.coord, b.coord) + self.config.tolerance_ms;
+
+        a.timestamp.abs_diff(b.timestamp) <= allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Coord;
+    use crate::trigger::{CoincidenceConfig, CoincidenceError, CoincidenceTrigger, NetworkEvent, TriggerEvent};
+    use alloc::vec::Vec;
+
+    fn on(timestamp: u128) -> TriggerEvent {
+        TriggerEvent::On { timestamp, ratio: 4.0 }
+    }
+
+    /// Three sensors 12 km apart along x and one 30 km north, 2 s of P travel time between neighbours.
+    fn network(min_sensors: usize) -> CoincidenceTrigger {
+        let sensors = Vec::from([
+            (1, Coord { x: 0.0, y: 0.0 }),
+            (2, Coord { x: 12.0, y: 0.0 }),
+            (3, Coord { x: 24.0, y: 0.0 }),
+            (4, Coord { x: 0.0, y: 30.0 }),
+        ]);
+
+        CoincidenceTrigger::new(CoincidenceConfig::new(min_sensors).hold_off_ms(10_000), sensors).unwrap()
+    }
+
+    #[test]
+    fn rejects_impossible_coincidence() {
+        let sensors = Vec::from([(1, Coord { x: 0.0, y: 0.0 })]);
+
+        assert_eq!(
+            CoincidenceTrigger::new(CoincidenceConfig::new(2), sensors).err(),
+            Some(CoincidenceError::NotEnoughSensors { required: 2, available: 1 })
+        );
+        assert_eq!(
+            network(2).process(9, &on(0)).err(),
+            Some(CoincidenceError::UnknownSensor(9))
+        );
+    }
+
+    #[test]
+    fn declares_event_when_wave_crosses_network() {
+        let mut trigger = network(3);
+
+        // epicenter west of sensor 1, the wave front crosses the line at the P velocity
+        assert_eq!(trigger.process(1, &on(1_000)).unwrap(), None);
+        assert_eq!(
+            trigger
+                .process(1, &TriggerEvent::Off { timestamp: 1_500, peak_ratio: 5.0 })
+                .unwrap(),
+            None
+        );
+        assert_eq!(trigger.process(2, &on(3_000)).unwrap(), None);
+
+        let event = trigger.process(3, &on(5_000)).unwrap();
+
+        assert_eq!(
+            event,
+            Some(NetworkEvent {
+                timestamp: 1_000,
+                declared_at: 5_000,
+                sensor_ids: Vec::from([1, 2, 3]),
+            })
+        );
+        assert_eq!(trigger.process(4, &on(6_000)).unwrap(), None);
+    }
+
+    #[test]
+    fn single_sensor_trigger_is_suppressed() {
+        let mut trigger = network(2);
+
+        // a door slam next to sensor 1, then another one next to sensor 3 long after any wave could travel
+        assert_eq!(trigger.process(1, &on(1_000)).unwrap(), None);
+        assert_eq!(trigger.process(1, &on(1_400)).unwrap(), None);
+        assert_eq!(trigger.process(3, &on(9_000)).unwrap(), None);
+    }
+
+    #[test]
+    fn only_mutually_consistent_triggers_are_coincident() {
+        let mut trigger = network(3);
+
+        // sensors 2 and 3 are consistent with sensor 1 but 4 s apart while only 2 s of travel time apart
+        assert_eq!(trigger.process(1, &on(4_000)).unwrap(), None);
+        assert_eq!(trigger.process(2, &on(2_000)).unwrap(), None);
+        assert_eq!(trigger.process(3, &on(6_100)).unwrap(), None);
+        assert!(trigger.process(4, &on(5_000)).unwrap().is_some());
+    }
+}
@@ -0,0 +1,217 @@
+mod classic_sta_lta;
+mod coincidence;
+mod recursive_sta_lta;
+
+pub use classic_sta_lta::*;
+pub use coincidence::*;
+pub use recursive_sta_lta::*;
+
+use crate::common::{Sample, SensorData};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerEvent {
+    On { timestamp: u128, ratio: f64 },
+    Off { timestamp: u128, peak_ratio: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerError {
+    InvalidWindow,
+    InvalidThresholds,
+}
+
+impl fmt::Display for TriggerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggerError::InvalidWindow => {
+                write!(formatter, "STA window must be non-empty and shorter than LTA window")
+            }
+            TriggerError::InvalidThresholds => write!(formatter, "Trigger-off threshold must be below trigger-on"),
+        }
+    }
+}
+
+/// Window lengths are expressed in samples, thresholds as STA/LTA ratios.
+#[derive(Debug, Clone, Copy)]
+pub struct StaLtaConfig {
+    pub sta_length: usize,
+    pub lta_length: usize,
+    pub trigger_on: f64,
+    pub trigger_off: f64,
+}
+
+impl StaLtaConfig {
+    pub fn new(sta_length: usize, lta_length: usize) -> Self {
+        Self {
+            sta_length,
+            lta_length,
+            ..Self::default()
+        }
+    }
+
+    pub fn trigger_on(mut self, threshold: f64) -> Self {
+        self.trigger_on = threshold;
+        self
+    }
+
+    pub fn trigger_off(mut self, threshold: f64) -> Self {
+        self.trigger_off = threshold;
+        self
+    }
+
+    fn validate(&self) -> Result<(), TriggerError> {
+        if self.sta_length == 0 || self.sta_length >= self.lta_length {
+            return Err(TriggerError::InvalidWindow);
+        }
+
+        if self.trigger_off <= 0.0 || self.trigger_off >= self.trigger_on {
+            return Err(TriggerError::InvalidThresholds);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for StaLtaConfig {
+    fn default() -> Self {
+        Self {
+            sta_length: 50,
+            lta_length: 500,
+            trigger_on: 3.5,
+            trigger_off: 1.5,
+        }
+    }
+}
+
+/// Detectors work on the energy of the whole sample, i.e. the squared vector magnitude once the DC offset of
+/// every channel, such as gravity on the vertical, is removed.
+pub trait Trigger {
+    fn process(&mut self, data: &SensorData) -> Option<TriggerEvent>;
+
+    fn is_triggered(&self) -> bool;
+
+    fn process_all<'a, I>(&mut self, data: I) -> Vec<TriggerEvent>
+    where
+        I: IntoIterator<Item = &'a SensorData>,
+    {
+        data.into_iter().filter_map(|d| self.process(d)).collect()
+    }
+}
+
+/// Removes the exponentially weighted mean of every channel, which starts at the first sample.
+#[derive(Debug, Clone)]
+pub(crate) struct DcBlocker {
+    coefficient: f64,
+    mean: Option<Sample>,
+}
+
+impl DcBlocker {
+    /// The mean follows the offset over about `length` samples.
+    pub(crate) fn new(length: usize) -> Self {
+        Self {
+            coefficient: 1.0 / length.max(1) as f64,
+            mean: None,
+        }
+    }
+
+    pub(crate) fn remove(&mut self, sample: Sample) -> Sample {
+        let mean = self.mean.get_or_insert(sample);
+
+        mean.e += self.coefficient * (sample.e - mean.e);
+        mean.n += self.coefficient * (sample.n - mean.n);
+        mean.z += self.coefficient * (sample.z - mean.z);
+
+        Sample::new(sample.e - mean.e, sample.n - mean.n, sample.z - mean.z)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TriggerState {
+    trigger_on: f64,
+    trigger_off: f64,
+    triggered: bool,
+    peak_ratio: f64,
+}
+
+impl TriggerState {
+    pub(crate) fn new(config: &StaLtaConfig) -> Self {
+        Self {
+            trigger_on: config.trigger_on,
+            trigger_off: config.trigger_off,
+            triggered: false,
+            peak_ratio: 0.0,
+        }
+    }
+
+    pub(crate) fn is_triggered(&self) -> bool {
+        self.triggered
+    }
+
+    pub(crate) fn update(&mut self, ratio: f64, timestamp: u128) -> Option<TriggerEvent> {
+        if !self.triggered {
+            if ratio < self.trigger_on {
+                return None;
+            }
+
+            self.triggered = true;
+            self.peak_ratio = ratio;
+
+            return Some(TriggerEvent::On { timestamp, ratio });
+        }
+
+        self.peak_ratio = self.peak_ratio.max(ratio);
+
+        if ratio >= self.trigger_off {
+            return None;
+        }
+
+        self.triggered = false;
+
+        Some(TriggerEvent::Off { timestamp, peak_ratio: self.peak_ratio })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_signal {
+    use crate::common::{Sample, SensorData};
+    use alloc::vec::Vec;
+
+    pub struct SyntheticSignal {
+        seed: u64,
+    }
+
+    impl SyntheticSignal {
+        pub fn new(seed: u64) -> Self {
+            Self { seed }
+        }
+
+        pub fn noise(&mut self) -> f64 {
+            self.seed = self
+                .seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+
+            ((self.seed >> 33) as f64 / (1u64 << 31) as f64) * 0.02 - 0.01
+        }
+
+        /// Noise with a spike of `spike_length` samples starting at `spike_at`, similar to the emulator output.
+        pub fn generate(&mut self, length: usize, spike_at: usize, spike_length: usize) -> Vec<SensorData> {
+            (0..length)
+                .map(|i| {
+                    let spike = if (spike_at..spike_at + spike_length).contains(&i) {
+                        2.0
+                    } else {
+                        0.0
+                    };
+
+                    SensorData {
+                        value: Sample::vertical(self.noise() + spike),
+                        timestamp: i as u128 * 10,
+                    }
+                })
+                .collect()
+        }
+    }
+}
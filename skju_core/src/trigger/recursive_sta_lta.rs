@@ -0,0 +1,129 @@
+use super::{DcBlocker, StaLtaConfig, Trigger, TriggerError, TriggerEvent, TriggerState};
+use crate::common::SensorData;
+
+/// Exponentially weighted STA/LTA, constant memory regardless of the window lengths.
+/// The first `lta_length` samples are used to warm the averages up and produce no ratio.
+pub struct RecursiveStaLta {
+    config: StaLtaConfig,
+    dc_blocker: DcBlocker,
+    sta: f64,
+    lta: f64,
+    samples: usize,
+    state: TriggerState,
+}
+
+impl RecursiveStaLta {
+    pub fn new(config: StaLtaConfig) -> Result<RecursiveStaLta, TriggerError> {
+        config.validate()?;
+
+        Ok(RecursiveStaLta {
+            config,
+            dc_blocker: DcBlocker::new(config.lta_length),
+            sta: 0.0,
+            lta: 0.0,
+            samples: 0,
+            state: TriggerState::new(&config),
+        })
+    }
+
+    pub fn ratio(&mut self, value: f64) -> Option<f64> {
+        let energy = value * value;
+        let sta_coefficient = 1.0 / self.config.sta_length as f64;
+        let lta_coefficient = 1.0 / self.config.lta_length as f64;
+
+        self.sta = sta_coefficient * energy + (1.0 - sta_coefficient) * self.sta;
+        self.lta = lta_coefficient * energy + (1.0 - lta_coefficient) * self.lta;
+        self.samples = self.samples.saturating_add(1);
+
+        if self.samples < self.config.lta_length || self.lta == 0.0 {
+            return None;
+        }
+
+        Some(self.sta / self.lta)
+    }
+}
+
+impl Trigger for RecursiveStaLta {
+    fn process(&mut self, data: &SensorData) -> Option<TriggerEvent> {
+        let value = self.dc_blocker.remove(data.value).magnitude();
+        let ratio = self.ratio(value)?;
+        self.state.update(ratio, data.timestamp)
+    }
+
+    fn is_triggered(&self) -> bool {
+        self.state.is_triggered()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::SensorData;
+    use crate::trigger::test_signal::SyntheticSignal;
+    use crate::trigger::{RecursiveStaLta, StaLtaConfig, Trigger, TriggerEvent};
+    use alloc::vec::Vec;
+
+    fn config() -> StaLtaConfig {
+        StaLtaConfig::new(10, 100).trigger_on(3.5).trigger_off(1.5)
+    }
+
+    #[test]
+    fn noise_does_not_trigger() {
+        let mut trigger = RecursiveStaLta::new(config()).unwrap();
+        let data = SyntheticSignal::new(42).generate(2000, 2000, 0);
+
+        assert!(trigger.process_all(&data).is_empty());
+    }
+
+    #[test]
+    fn spike_during_warm_up_is_ignored() {
+        let mut trigger = RecursiveStaLta::new(config()).unwrap();
+        let data = SyntheticSignal::new(3).generate(50, 20, 6);
+
+        assert!(trigger.process_all(&data).is_empty());
+    }
+
+    #[test]
+    fn spike_triggers_on_and_off() {
+        let mut trigger = RecursiveStaLta::new(config()).unwrap();
+        let data = SyntheticSignal::new(7).generate(1500, 500, 8);
+        let events = trigger.process_all(&data);
+
+        assert_eq!(events.len(), 2);
+
+        match events[0] {
+            TriggerEvent::On { timestamp, ratio } => {
+                assert_eq!(timestamp, 5000);
+                assert!(ratio >= 3.5);
+            }
+            _ => panic!("expected trigger-on event, got {:?}", events[0]),
+        }
+
+        match events[1] {
+            TriggerEvent::Off { timestamp, peak_ratio } => {
+                assert!(timestamp > 5080);
+                assert!(peak_ratio >= 3.5);
+            }
+            _ => panic!("expected trigger-off event, got {:?}", events[1]),
+        }
+    }
+
+    #[test]
+    fn ignores_gravity_offset() {
+        let with_gravity = |mut data: Vec<SensorData>| {
+            data.iter_mut().for_each(|d| d.value.z += 9.81);
+            data
+        };
+        let mut trigger = RecursiveStaLta::new(config()).unwrap();
+
+        assert!(
+            trigger
+                .process_all(&with_gravity(SyntheticSignal::new(42).generate(2000, 2000, 0)))
+                .is_empty()
+        );
+
+        let mut trigger = RecursiveStaLta::new(config()).unwrap();
+        let events = trigger.process_all(&with_gravity(SyntheticSignal::new(7).generate(1500, 500, 8)));
+
+        assert!(matches!(events[0], TriggerEvent::On { timestamp: 5000, .. }));
+    }
+}
@@ -1,13 +1,432 @@
 use crate::common::SensorConfig;
-use std::fs::read_to_string;
+use crate::geo::{GeoCoord, LocalFrame};
+use crate::sensor::SensorConfigParseError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{read_to_string, write};
 use std::path::Path;
 
-pub fn get_sensors_from_file<T: AsRef<Path>>(path: T) -> Result<Vec<SensorConfig>, String> {
-    let data = read_to_string(path).map_err(|e| e.to_string())?;
-    let sensors = data
-        .lines()
-        .map(|line| line.parse::<SensorConfig>())
-        .collect::<Result<Vec<SensorConfig>, String>>()?;
+/// Schema version written into new sensors files.
+pub const SENSORS_FILE_VERSION: u32 = 1;
 
-    Ok(sensors)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+    /// `id;name;x;y` lines, kept readable for migrating old setups.
+    Legacy,
+}
+
+/// Versioned description of every sensor of a deployment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorsFile {
+    pub version: u32,
+    /// Origin of the plane sensor locations are projected into, the first located sensor when unset.
+    #[serde(default)]
+    pub origin: Option<GeoCoord>,
+    #[serde(default)]
+    pub networks: Vec<NetworkConfig>,
+    pub sensors: Vec<SensorConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    pub code: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse { format: ConfigFormat, message: String },
+    Legacy { line: usize, error: SensorConfigParseError },
+    UnsupportedVersion(u32),
+    DuplicateSensor(u64),
+    UnknownNetwork { sensor_id: u64, network: String },
+    NoChannels(u64),
+    NoPosition(u64),
+}
+
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: Option<u32>,
+}
+
+impl ConfigFormat {
+    /// Picks the format from the file extension, anything unknown is read as legacy lines.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> ConfigFormat {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str());
+
+        match extension {
+            Some("json") => ConfigFormat::Json,
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Legacy,
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFormat::Json => write!(formatter, "JSON"),
+            ConfigFormat::Toml => write!(formatter, "TOML"),
+            ConfigFormat::Yaml => write!(formatter, "YAML"),
+            ConfigFormat::Legacy => write!(formatter, "legacy"),
+        }
+    }
+}
+
+impl SensorsFile {
+    pub fn new(sensors: Vec<SensorConfig>) -> SensorsFile {
+        SensorsFile {
+            version: SENSORS_FILE_VERSION,
+            origin: None,
+            networks: Vec::new(),
+            sensors,
+        }
+    }
+
+    pub fn parse(data: &str, format: ConfigFormat) -> Result<SensorsFile, ConfigError> {
+        let mut file: SensorsFile = match format {
+            ConfigFormat::Legacy => SensorsFile::new(parse_legacy(data)?),
+            // sensors lists written before the schema was versioned
+            ConfigFormat::Json if data.trim_start().starts_with('[') => SensorsFile::new(deserialize(data, format)?),
+            _ => {
+                let probe: VersionProbe = deserialize(data, format)?;
+
+                match probe.version {
+                    Some(SENSORS_FILE_VERSION) => deserialize(data, format)?,
+                    Some(version) => return Err(ConfigError::UnsupportedVersion(version)),
+                    None => return Err(parse_error(format, "missing schema version")),
+                }
+            }
+        };
+
+        file.project_locations();
+        file.validate()?;
+
+        Ok(file)
+    }
+
+    /// Local plane of the sensor coords, when the origin or any sensor location is known.
+    pub fn frame(&self) -> Option<LocalFrame> {
+        self.origin
+            .or_else(|| self.sensors.iter().find_map(|sensor| sensor.location))
+            .map(LocalFrame::new)
+    }
+
+    /// Replaces the coord of every located sensor by its position in the [`SensorsFile::frame`].
+    pub fn project_locations(&mut self) {
+        let Some(frame) = self.frame() else {
+            return;
+        };
+
+        for sensor in &mut self.sensors {
+            if let Some(location) = &sensor.location {
+                sensor.coord = frame.project(location);
+            }
+        }
+    }
+
+    pub fn to_string(&self, format: ConfigFormat) -> Result<String, ConfigError> {
+        match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(self).map_err(|e| parse_error(format, e)),
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|e| parse_error(format, e)),
+            ConfigFormat::Yaml => serde_yaml_ng::to_string(self).map_err(|e| parse_error(format, e)),
+            ConfigFormat::Legacy => Ok(self
+                .sensors
+                .iter()
+                .map(|sensor| format!("{sensor}\n"))
+                .collect()),
+        }
+    }
+
+    /// Checks that sensor ids are unique and that every sensor has a position, channels and a known network.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (index, sensor) in self.sensors.iter().enumerate() {
+            if self.sensors[..index]
+                .iter()
+                .any(|other| other.id == sensor.id)
+            {
+                return Err(ConfigError::DuplicateSensor(sensor.id));
+            }
+
+            if sensor.location.is_none() && (sensor.coord.x.is_nan() || sensor.coord.y.is_nan()) {
+                return Err(ConfigError::NoPosition(sensor.id));
+            }
+
+            if sensor.channels.is_empty() {
+                return Err(ConfigError::NoChannels(sensor.id));
+            }
+
+            if let Some(network) = &sensor.network
+                && !self.networks.iter().any(|known| &known.code == network)
+            {
+                return Err(ConfigError::UnknownNetwork {
+                    sensor_id: sensor.id,
+                    network: network.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(formatter, "Unable to access sensors file: {e}"),
+            ConfigError::Parse { format, message } => write!(formatter, "Invalid {format} sensors file: {message}"),
+            ConfigError::Legacy { line, error } => write!(formatter, "Invalid sensor on line {line}: {error}"),
+            ConfigError::UnsupportedVersion(version) => write!(
+                formatter,
+                "Unsupported sensors file version {version}, expected {SENSORS_FILE_VERSION}"
+            ),
+            ConfigError::DuplicateSensor(id) => write!(formatter, "Sensor {id} is defined more than once"),
+            ConfigError::UnknownNetwork { sensor_id, network } => {
+                write!(formatter, "Sensor {sensor_id} belongs to unknown network {network}")
+            }
+            ConfigError::NoChannels(id) => write!(formatter, "Sensor {id} has no channels"),
+            ConfigError::NoPosition(id) => write!(formatter, "Sensor {id} needs a coord or a location"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Legacy { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(error: std::io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+/// Reads a sensors file in the format given by its extension, see [`ConfigFormat::from_path`].
+pub fn read_sensors_file<T: AsRef<Path>>(path: T) -> Result<SensorsFile, ConfigError> {
+    let data = read_to_string(path.as_ref())?;
+
+    SensorsFile::parse(&data, ConfigFormat::from_path(path))
+}
+
+/// Writes the sensors in the format given by the extension, legacy files can be migrated by reading them and
+/// writing them back to a `.toml`, `.json` or `.yaml` path.
+pub fn write_sensors_file<T: AsRef<Path>>(path: T, file: &SensorsFile) -> Result<(), ConfigError> {
+    let data = file.to_string(ConfigFormat::from_path(path.as_ref()))?;
+
+    write(path, data)?;
+
+    Ok(())
+}
+
+pub fn get_sensors_from_file<T: AsRef<Path>>(path: T) -> Result<Vec<SensorConfig>, ConfigError> {
+    Ok(read_sensors_file(path)?.sensors)
+}
+
+fn parse_legacy(data: &str) -> Result<Vec<SensorConfig>, ConfigError> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.parse::<SensorConfig>()
+                .map_err(|error| ConfigError::Legacy { line: index + 1, error })
+        })
+        .collect()
+}
+
+fn deserialize<T: DeserializeOwned>(data: &str, format: ConfigFormat) -> Result<T, ConfigError> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(data).map_err(|e| parse_error(format, e)),
+        ConfigFormat::Toml => toml::from_str(data).map_err(|e| parse_error(format, e)),
+        ConfigFormat::Yaml => serde_yaml_ng::from_str(data).map_err(|e| parse_error(format, e)),
+        ConfigFormat::Legacy => Err(parse_error(format, "legacy files only contain sensor lines")),
+    }
+}
+
+fn parse_error(format: ConfigFormat, message: impl fmt::Display) -> ConfigError {
+    ConfigError::Parse { format, message: message.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{Channel, Coord, SensorConfig, SourceConfig};
+    use crate::filter::FilterSpec;
+    use crate::sensor::SensorConfigParseError;
+    use crate::utils::{ConfigError, ConfigFormat, NetworkConfig, SensorsFile, read_sensors_file, write_sensors_file};
+
+    const TOML: &str = r#"
+version = 1
+
+[[networks]]
+code = "SK"
+
+[[sensors]]
+id = 1
+name = "Sensor; Alpha"
+coord = { x = 12.5, y = 34.8 }
+sample_rate = 100.0
+channels = ["E", "N", "Z"]
+network = "SK"
+filters = [{ type = "demean", window = 100 }]
+source = { type = "udp", bind = "0.0.0.0:9000" }
+
+[sensors.response]
+sensitivity = 1670.7
+unit = "m/s^2"
+"#;
+
+    #[test]
+    fn reads_every_format() {
+        let file = SensorsFile::parse(TOML, ConfigFormat::Toml).unwrap();
+        let sensor = &file.sensors[0];
+
+        assert_eq!(sensor.name, "Sensor; Alpha");
+        assert_eq!(sensor.network.as_deref(), Some("SK"));
+        assert_eq!(sensor.sample_rate, Some(100.0));
+        assert!(matches!(sensor.filters[..], [FilterSpec::Demean { window: 100 }]));
+        assert!(sensor.response.is_some());
+        assert_eq!(sensor.source, Some(SourceConfig::Udp { bind: "0.0.0.0:9000".into() }));
+
+        for format in [ConfigFormat::Json, ConfigFormat::Yaml, ConfigFormat::Toml] {
+            let data = file.to_string(format).unwrap();
+            let parsed = SensorsFile::parse(&data, format).unwrap();
+
+            assert_eq!(parsed.sensors[0].name, sensor.name, "{format}");
+            assert_eq!(parsed.sensors[0].channels, Channel::ALL.to_vec(), "{format}");
+        }
+    }
+
+    #[test]
+    fn reads_legacy_lines() {
+        let file = SensorsFile::parse(
+            "1;Sensor Alpha;12.5;34.8\n\n2;Sensor Beta;18.2;29.1\n",
+            ConfigFormat::Legacy,
+        )
+        .unwrap();
+
+        assert_eq!(file.version, 1);
+        assert_eq!(file.sensors.len(), 2);
+        assert_eq!(file.sensors[1].channels, Channel::ALL);
+
+        let error =
+            SensorsFile::parse("1;Sensor Alpha;12.5;34.8\n2;Sensor Beta;north", ConfigFormat::Legacy).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::Legacy {
+                line: 2,
+                error: SensorConfigParseError::InvalidField("x coord")
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let future = TOML.replace("version = 1", "version = 2");
+        let unversioned = TOML.replace("version = 1", "");
+        let unknown_network = TOML.replace("code = \"SK\"", "code = \"XX\"");
+        let unplaced = TOML.replace("coord = { x = 12.5, y = 34.8 }", "");
+
+        assert!(matches!(
+            SensorsFile::parse(&future, ConfigFormat::Toml),
+            Err(ConfigError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            SensorsFile::parse(&unversioned, ConfigFormat::Toml),
+            Err(ConfigError::Parse { format: ConfigFormat::Toml, .. })
+        ));
+        assert!(matches!(
+            SensorsFile::parse(&unknown_network, ConfigFormat::Toml),
+            Err(ConfigError::UnknownNetwork { sensor_id: 1, .. })
+        ));
+        assert!(matches!(
+            SensorsFile::parse(&unplaced, ConfigFormat::Toml),
+            Err(ConfigError::NoPosition(1))
+        ));
+
+        let coord = Coord { x: 0.0, y: 0.0 };
+        let duplicate = SensorsFile::new(Vec::from([
+            SensorConfig::new(1, "Alpha", coord),
+            SensorConfig::new(1, "Beta", coord),
+        ]));
+        let json = duplicate.to_string(ConfigFormat::Json).unwrap();
+
+        assert!(matches!(
+            SensorsFile::parse(&json, ConfigFormat::Json),
+            Err(ConfigError::DuplicateSensor(1))
+        ));
+    }
+
+    #[test]
+    fn projects_locations() {
+        let toml = r#"
+version = 1
+origin = { latitude = 46.0, longitude = 14.5 }
+
+[[sensors]]
+id = 1
+name = "Alpha"
+location = { latitude = 46.0, longitude = 14.5 }
+
+[[sensors]]
+id = 2
+name = "Beta"
+location = { latitude = 46.1, longitude = 14.6 }
+
+[[sensors]]
+id = 3
+name = "Gamma"
+coord = { x = 1.0, y = 2.0 }
+"#;
+        let file = SensorsFile::parse(toml, ConfigFormat::Toml).unwrap();
+        let [alpha, beta, gamma] = [0, 1, 2].map(|index| file.sensors[index].coord);
+
+        assert!(alpha.x.abs() < 1e-3 && alpha.y.abs() < 1e-3);
+        // 0.1 degrees are about 7.7 km east and 11.1 km north at this latitude
+        assert!((beta.x - 7.74).abs() < 0.05, "{}", beta.x);
+        assert!((beta.y - 11.12).abs() < 0.05, "{}", beta.y);
+        assert_eq!((gamma.x, gamma.y), (1.0, 2.0));
+
+        let without_origin = SensorsFile::parse(&toml.replace("origin =", "# origin ="), ConfigFormat::Toml).unwrap();
+        assert_eq!(
+            without_origin.frame().unwrap().origin,
+            file.sensors[0].location.unwrap()
+        );
+    }
+
+    #[test]
+    fn migrates_legacy_file() {
+        let directory = std::env::temp_dir().join(format!("skju_sensors_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let legacy = directory.join("sensors.txt");
+        let migrated = directory.join("sensors.yaml");
+        std::fs::write(&legacy, "1;Sensor Alpha;12.5;34.8\n").unwrap();
+
+        let mut file = read_sensors_file(&legacy).unwrap();
+        file.networks
+            .push(NetworkConfig { code: "SK".into(), description: None });
+        file.sensors[0].network = Some("SK".into());
+        write_sensors_file(&migrated, &file).unwrap();
+
+        let read_back = read_sensors_file(&migrated).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(read_back.sensors[0].name, "Sensor Alpha");
+        assert_eq!(read_back.sensors[0].network.as_deref(), Some("SK"));
+        assert!(matches!(read_sensors_file(&legacy), Err(ConfigError::Io(_))));
+    }
 }
@@ -0,0 +1,143 @@
+use crate::common::Coord;
+use crate::consts::{P_WAVE, S_WAVE};
+use crate::locate::Location;
+use crate::math;
+use crate::shaking::clamp_mmi;
+
+/// Located earthquake, the epicenter is in km and the origin time in ms.
+#[derive(Debug, Clone, Copy)]
+pub struct EarthquakeSource {
+    pub epicenter: Coord,
+    pub depth_km: f64,
+    pub origin_time: u128,
+    pub magnitude: f64,
+}
+
+/// Expected shaking at a target site, `warning_seconds` is zero once the S wave has arrived.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SiteWarning {
+    pub hypocentral_distance_km: f64,
+    pub p_arrival: u128,
+    pub s_arrival: u128,
+    pub intensity: f64,
+    pub warning_seconds: f64,
+}
+
+impl EarthquakeSource {
+    /// The locator only resolves the epicenter, the depth defaults to 10 km.
+    pub fn new(location: &Location, magnitude: f64) -> EarthquakeSource {
+        EarthquakeSource {
+            epicenter: location.epicenter,
+            depth_km: 10.0,
+            origin_time: location.origin_time,
+            magnitude,
+        }
+    }
+
+    pub fn depth_km(mut self, depth_km: f64) -> Self {
+        self.depth_km = depth_km;
+        self
+    }
+
+    pub fn hypocentral_distance(&self, target: Coord) -> f64 {
+        let epicentral = math::hypot(
+            (target.x - self.epicenter.x) as f64,
+            (target.y - self.epicenter.y) as f64,
+        );
+
+        math::hypot(epicentral, self.depth_km)
+    }
+
+    pub fn p_arrival(&self, target: Coord) -> u128 {
+        self.arrival(target, P_WAVE)
+    }
+
+    pub fn s_arrival(&self, target: Coord) -> u128 {
+        self.arrival(target, S_WAVE)
+    }
+
+    fn arrival(&self, target: Coord, velocity: f64) -> u128 {
+        let travel_time_ms = self.hypocentral_distance(target) / velocity * 1000.0;
+
+        self.origin_time + math::round(travel_time_ms) as u128
+    }
+
+    /// Warning for `target` as seen at `now` in ms.
+    pub fn warning(&self, target: Coord, now: u128) -> SiteWarning {
+        let distance = self.hypocentral_distance(target);
+        let s_arrival = self.s_arrival(target);
+
+        SiteWarning {
+            hypocentral_distance_km: distance,
+            p_arrival: self.p_arrival(target),
+            s_arrival,
+            intensity: intensity(self.magnitude, distance),
+            warning_seconds: s_arrival.saturating_sub(now) as f64 / 1000.0,
+        }
+    }
+}
+
+/// Modified Mercalli Intensity at the hypocentral distance in km, intensity prediction equation of
+/// Atkinson, Worden & Wald (2014).
+pub fn intensity(magnitude: f64, hypocentral_distance_km: f64) -> f64 {
+    let distance = math::hypot(hypocentral_distance_km, 14.0);
+    let log_distance = math::log10(distance);
+    let far_field = math::log10(distance / 50.0).max(0.0);
+    let mmi = 0.309 + 1.864 * magnitude - 1.672 * log_distance - 0.00219 * distance + 1.77 * far_field
+        - 0.383 * magnitude * log_distance;
+
+    clamp_mmi(mmi)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Coord;
+    use crate::locate::Location;
+    use crate::warning::{EarthquakeSource, intensity};
+
+    fn source() -> EarthquakeSource {
+        let location = Location {
+            epicenter: Coord { x: 0.0, y: 0.0 },
+            origin_time: 10_000,
+            rms_residual: 0.0,
+            uncertainty: None,
+        };
+
+        EarthquakeSource::new(&location, 6.0).depth_km(0.0)
+    }
+
+    #[test]
+    fn s_wave_arrives_after_p_wave() {
+        let target = Coord { x: 35.0, y: 0.0 };
+        let source = source();
+
+        assert_eq!(source.s_arrival(target), 20_000);
+        assert_eq!(source.p_arrival(target), 15_833);
+        assert_eq!(
+            source
+                .depth_km(10.0)
+                .hypocentral_distance(Coord { x: 0.0, y: 0.0 }),
+            10.0
+        );
+    }
+
+    #[test]
+    fn warning_counts_down_to_s_arrival() {
+        let target = Coord { x: 0.0, y: 70.0 };
+        let source = source();
+
+        assert_eq!(source.warning(target, 12_500).warning_seconds, 17.5);
+        assert_eq!(source.warning(target, 30_000).warning_seconds, 0.0);
+    }
+
+    #[test]
+    fn intensity_decays_with_distance_and_grows_with_magnitude() {
+        let near = intensity(6.0, 10.0);
+        let far = intensity(6.0, 100.0);
+
+        assert!((near - 6.55).abs() < 0.05, "got {near}");
+        assert!(far < near);
+        assert!(intensity(7.0, 100.0) > far);
+        assert_eq!(intensity(2.0, 500.0), 1.0);
+    }
+}
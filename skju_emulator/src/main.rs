@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use rand::{random_bool, random_range};
-use skju_core::{Coord, SensorConfig, SensorData};
+use skju_core::{Channel, Coord, Sample, SensorConfig, SensorData};
 use std::fs::OpenOptions;
 use std::io::{BufWriter, Read, Write};
 use std::path::Path;
@@ -49,7 +49,7 @@ fn get_sensors() -> anyhow::Result<Vec<SensorConfig>> {
 
     let sensors: Vec<SensorConfig> = file_data
         .lines()
-        .map(|line| line.parse::<SensorConfig>().map_err(|s| anyhow!(s)))
+        .map(|line| line.parse::<SensorConfig>().map_err(|e| anyhow!(e)))
         .collect::<anyhow::Result<_>>()?;
 
     Ok(sensors)
@@ -65,7 +65,7 @@ fn generate_sensor_data(sensor_id: u64) -> anyhow::Result<()> {
         .open(file_path)?;
 
     let mut writer = BufWriter::new(file);
-    let mut previous_value = 0.0;
+    let mut previous_value = Sample::default();
     let mut now = SystemTime::now();
     let mut consecutive_spikes_left = 0;
 
@@ -73,7 +73,7 @@ fn generate_sensor_data(sensor_id: u64) -> anyhow::Result<()> {
 
     loop {
         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
-        let value = generate_random_reading(&mut previous_value, consecutive_spikes_left > 0);
+        let value = generate_random_sample(&mut previous_value, consecutive_spikes_left > 0);
         let reading_str = SensorData { timestamp, value }.to_string();
         let next_reading_in = random_range(10..=20);
 
@@ -95,6 +95,17 @@ fn generate_sensor_data(sensor_id: u64) -> anyhow::Result<()> {
     }
 }
 
+fn generate_random_sample(last_sample: &mut Sample, with_spike: bool) -> Sample {
+    for channel in Channel::ALL {
+        let mut value = last_sample.get(channel);
+
+        generate_random_reading(&mut value, with_spike);
+        last_sample.set(channel, value);
+    }
+
+    *last_sample
+}
+
 fn generate_random_reading(last_value: &mut f64, with_spike: bool) -> f64 {
     let value: f64 = random_range(-0.01..=0.01);
     let spike_dir = if random_bool(0.5) { -1.0 } else { 1.0 };
@@ -112,21 +123,9 @@ fn generate_random_reading(last_value: &mut f64, with_spike: bool) -> f64 {
 
 fn get_default_sensors() -> Vec<SensorConfig> {
     Vec::from([
-        SensorConfig {
-            id: 1,
-            name: String::from("Sensor Alpha"),
-            coord: Coord { x: 12.5, y: 34.8 },
-        },
-        SensorConfig {
-            id: 2,
-            name: String::from("Sensor Beta"),
-            coord: Coord { x: 18.2, y: 29.1 },
-        },
-        SensorConfig {
-            id: 3,
-            name: String::from("Sensor Gamma"),
-            coord: Coord { x: 25.7, y: 40.3 },
-        },
+        SensorConfig::new(1, "Sensor Alpha", Coord { x: 12.5, y: 34.8 }),
+        SensorConfig::new(2, "Sensor Beta", Coord { x: 18.2, y: 29.1 }),
+        SensorConfig::new(3, "Sensor Gamma", Coord { x: 25.7, y: 40.3 }),
     ])
 }
 
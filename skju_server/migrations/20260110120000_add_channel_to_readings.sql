@@ -0,0 +1,2 @@
+ALTER TABLE readings
+    ADD COLUMN channel TEXT NOT NULL DEFAULT 'Z' CHECK (channel IN ('E', 'N', 'Z'));
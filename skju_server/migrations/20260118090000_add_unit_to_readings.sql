@@ -0,0 +1,2 @@
+ALTER TABLE readings
+    ADD COLUMN unit TEXT NOT NULL DEFAULT 'counts' CHECK (unit IN ('counts', 'm/s^2', 'rad/s'));
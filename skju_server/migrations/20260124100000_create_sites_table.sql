@@ -0,0 +1,8 @@
+CREATE TABLE sites
+(
+    id         SERIAL PRIMARY KEY,
+    name       TEXT             NOT NULL,
+    x          DOUBLE PRECISION NOT NULL,
+    y          DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
@@ -1,2 +1,3 @@
 pub mod readings;
 pub mod sensors;
+pub mod sites;
@@ -1,12 +1,31 @@
-use crate::domain::reading::{Reading, ReadingCreate, ReadingTimestamp, ReadingValue};
+use crate::domain::reading::{
+    Reading, ReadingChannel, ReadingCreate, ReadingError, ReadingTimestamp, ReadingUnit, ReadingValue,
+};
 use crate::domain::sensor::SensorID;
 use chrono::{DateTime, Utc};
+use mpu6500::fifo::FIFOEntryType;
 use serde::{Deserialize, Serialize};
+use skju_codec::Batch;
+use skju_core::Channel;
+use skju_core::mseed::Encoding;
+use skju_core::response::Unit;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ReadingModel {
     pub id: i64,
     pub sensor_id: i32,
+    pub channel: Channel,
+    pub unit: Unit,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Reading pushed to the live stream subscribers, not yet stored and therefore without an id.
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveReadingModel {
+    pub sensor_id: i32,
+    pub channel: Channel,
+    pub unit: Unit,
     pub value: f64,
     pub timestamp: DateTime<Utc>,
 }
@@ -14,6 +33,10 @@ pub struct ReadingModel {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ReadingCreateRequest {
     pub sensor_id: i32,
+    /// Defaults to the vertical channel for single component sensors.
+    pub channel: Option<Channel>,
+    /// Defaults to raw counts.
+    pub unit: Option<Unit>,
     pub value: f64,
     pub timestamp: DateTime<Utc>,
 }
@@ -25,11 +48,41 @@ pub struct ReadingGetBetweenRequest {
     pub to: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadingStreamRequest {
+    /// Streams the readings of every sensor when unset.
+    pub sensor_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadingExportRequest {
+    pub sensor_id: i32,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub encoding: Option<Encoding>,
+    /// Multiplier applied before rounding the values for the integer encodings.
+    pub scale: Option<f64>,
+}
+
 impl From<Reading> for ReadingModel {
     fn from(reading: Reading) -> Self {
         ReadingModel {
             id: reading.id.value(),
             sensor_id: reading.sensor_id.value(),
+            channel: reading.channel.value(),
+            unit: reading.unit.value(),
+            value: reading.value.value(),
+            timestamp: reading.timestamp.value(),
+        }
+    }
+}
+
+impl From<ReadingCreate> for LiveReadingModel {
+    fn from(reading: ReadingCreate) -> Self {
+        LiveReadingModel {
+            sensor_id: reading.sensor_id.value(),
+            channel: reading.channel.value(),
+            unit: reading.unit.value(),
             value: reading.value.value(),
             timestamp: reading.timestamp.value(),
         }
@@ -40,8 +93,105 @@ impl From<ReadingCreateRequest> for ReadingCreate {
     fn from(request: ReadingCreateRequest) -> Self {
         ReadingCreate {
             sensor_id: SensorID::new(request.sensor_id),
+            channel: ReadingChannel::new(request.channel.unwrap_or(Channel::Z)),
+            unit: ReadingUnit::new(request.unit.unwrap_or_default()),
             value: ReadingValue::new(request.value),
             timestamp: ReadingTimestamp::new(request.timestamp),
         }
     }
 }
+
+/// Accelerometer axes of a FIFO sample as stored, x is east, y is north and z is vertical.
+const FIFO_CHANNELS: [(FIFOEntryType, Channel); 3] = [
+    (FIFOEntryType::AccelX, Channel::E),
+    (FIFOEntryType::AccelY, Channel::N),
+    (FIFOEntryType::AccelZ, Channel::Z),
+];
+
+/// Raw accelerometer readings of a sensor node batch, the node id is the sensor id and the batch
+/// timestamp is taken as microseconds since the Unix epoch.
+pub fn fifo_readings(batch: &Batch<'_>) -> Result<Vec<ReadingCreate>, ReadingError> {
+    let sensor_id = SensorID::new(batch.header.node_id as i32);
+    let mut readings = Vec::new();
+
+    for (index, sample) in batch.samples().enumerate() {
+        let timestamp = batch
+            .sample_timestamp_us(index)
+            .and_then(|timestamp| i64::try_from(timestamp).ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(|| ReadingError::InvalidBatch(String::from("timestamp out of range")))?;
+
+        for (entry_type, channel) in FIFO_CHANNELS {
+            let Some(value) = sample.get_value(entry_type) else {
+                return Err(ReadingError::InvalidBatch(String::from(
+                    "batch has no accelerometer samples",
+                )));
+            };
+
+            readings.push(ReadingCreate {
+                sensor_id,
+                channel: ReadingChannel::new(channel),
+                unit: ReadingUnit::new(Unit::Counts),
+                value: ReadingValue::new(value as f64),
+                timestamp: ReadingTimestamp::new(timestamp),
+            });
+        }
+    }
+
+    Ok(readings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::readings::dto::fifo_readings;
+    use crate::domain::reading::ReadingError;
+    use mpu6500::fifo::{FIFOLayout, FIFOSensors};
+    use skju_codec::{BatchHeader, decode_batch, encode_batch, encoded_len};
+    use skju_core::Channel;
+
+    fn encode(sensors: FIFOSensors, samples: &[u8]) -> Vec<u8> {
+        let layout = FIFOLayout::from_fifo_register(sensors.bits());
+        let header = BatchHeader::new(&layout, 7, 1, 100, 1_700_000_000_000_000);
+        let mut output = vec![0; encoded_len(&layout, samples.len() / layout.sample_size)];
+
+        encode_batch(&header, samples, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn converts_accelerometer_samples() {
+        let data = encode(FIFOSensors::ACCEL, &[0, 1, 0, 2, 0xff, 0xfd, 0, 4, 0, 5, 0, 6]);
+        let readings = fifo_readings(&decode_batch(&data).unwrap()).unwrap();
+        let values: Vec<(Channel, f64)> = readings
+            .iter()
+            .map(|reading| (reading.channel.value(), reading.value.value()))
+            .collect();
+
+        assert_eq!(
+            values,
+            [
+                (Channel::E, 1.0),
+                (Channel::N, 2.0),
+                (Channel::Z, -3.0),
+                (Channel::E, 4.0),
+                (Channel::N, 5.0),
+                (Channel::Z, 6.0)
+            ]
+        );
+        assert!(
+            readings
+                .iter()
+                .all(|reading| reading.sensor_id.value() == 7)
+        );
+        assert_eq!(readings[0].timestamp.value().timestamp_micros(), 1_700_000_000_000_000);
+        assert_eq!(readings[3].timestamp.value().timestamp_micros(), 1_700_000_000_010_000);
+    }
+
+    #[test]
+    fn rejects_batches_without_accelerometer() {
+        let data = encode(FIFOSensors::GYRO_X, &[0, 1]);
+        let result = fifo_readings(&decode_batch(&data).unwrap());
+
+        assert!(matches!(result, Err(ReadingError::InvalidBatch(_))));
+    }
+}
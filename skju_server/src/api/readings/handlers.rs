@@ -1,12 +1,20 @@
-use crate::api::readings::dto::{ReadingCreateRequest, ReadingGetBetweenRequest, ReadingModel};
-use crate::domain::reading::{ReadingTimestamp, ReadingsRange};
+use crate::api::readings::dto::{
+    LiveReadingModel, ReadingCreateRequest, ReadingExportRequest, ReadingGetBetweenRequest, ReadingModel,
+    ReadingStreamRequest, fifo_readings,
+};
+use crate::domain::reading::{ReadingCreate, ReadingError, ReadingTimestamp, ReadingsRange};
 use crate::domain::sensor::SensorID;
 use crate::error::ApiError;
 use crate::state::AppState;
 use axum::Json;
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, header};
 use axum::response::IntoResponse;
+use skju_core::mseed::Encoding;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 
 pub async fn create_reading(
     State(state): State<AppState>,
@@ -23,6 +31,37 @@ pub async fn create_reading(
     Ok(response)
 }
 
+/// Creates the readings of a batch in order, e.g. forwarded by the desktop processor.
+pub async fn create_readings(
+    State(state): State<AppState>,
+    Json(readings): Json<Vec<ReadingCreateRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .app_services
+        .reading_service
+        .create_many(readings.into_iter().map(Into::into).collect())
+        .await?;
+
+    let response = (StatusCode::CREATED, ());
+
+    Ok(response)
+}
+
+/// Creates the readings of a sensor node FIFO batch in the binary format of skju_codec.
+pub async fn create_fifo_readings(State(state): State<AppState>, body: Bytes) -> Result<impl IntoResponse, ApiError> {
+    let batch = skju_codec::decode_batch(&body).map_err(|e| ReadingError::InvalidBatch(e.to_string()))?;
+
+    state
+        .app_services
+        .reading_service
+        .create_many(fifo_readings(&batch)?)
+        .await?;
+
+    let response = (StatusCode::CREATED, ());
+
+    Ok(response)
+}
+
 pub async fn get_readings_between(
     State(state): State<AppState>,
     Json(request): Json<ReadingGetBetweenRequest>,
@@ -47,3 +86,73 @@ pub async fn get_readings_between(
 
     Ok(response)
 }
+
+pub async fn export_mseed(
+    State(state): State<AppState>,
+    Query(request): Query<ReadingExportRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let range_request = ReadingsRange::new(
+        Some(SensorID::new(request.sensor_id)),
+        ReadingTimestamp::new(request.from),
+        ReadingTimestamp::new(request.to),
+    )?;
+
+    let records = state
+        .app_services
+        .reading_service
+        .export_mseed(
+            range_request,
+            request.encoding.unwrap_or(Encoding::Float64),
+            request.scale.unwrap_or(1.0),
+        )
+        .await?;
+
+    let disposition = format!("attachment; filename=\"{}.mseed\"", request.sensor_id);
+    let headers = [
+        (header::CONTENT_TYPE, "application/vnd.fdsn.mseed".to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+    ];
+    let response = (StatusCode::OK, headers, records);
+
+    Ok(response)
+}
+
+pub async fn stream_readings(
+    State(state): State<AppState>,
+    Query(request): Query<ReadingStreamRequest>,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let readings = state.app_services.reading_service.subscribe();
+
+    upgrade.on_upgrade(move |socket| send_readings(socket, readings, request.sensor_id))
+}
+
+/// Forwards live readings as JSON text messages until the client disconnects.
+async fn send_readings(
+    mut socket: WebSocket,
+    mut readings: broadcast::Receiver<ReadingCreate>,
+    sensor_id: Option<i32>,
+) {
+    loop {
+        let reading = match readings.recv().await {
+            Ok(reading) => reading,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Live readings subscriber skipped {} readings", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        if sensor_id.is_some_and(|id| id != reading.sensor_id.value()) {
+            continue;
+        }
+
+        let Ok(json) = serde_json::to_string(&LiveReadingModel::from(reading)) else {
+            continue;
+        };
+
+        if socket.send(Message::Text(json.into())).await.is_err() {
+            break;
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use super::{create_reading, get_readings_between};
+use super::{
+    create_fifo_readings, create_reading, create_readings, export_mseed, get_readings_between, stream_readings,
+};
 use crate::state::AppState;
 use axum::Router;
-use axum::routing::post;
+use axum::routing::{get, post};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", post(create_reading))
+        .route("/batch", post(create_readings))
+        .route("/fifo", post(create_fifo_readings))
         .route("/get_between", post(get_readings_between))
+        .route("/export.mseed", get(export_mseed))
+        .route("/stream", get(stream_readings))
 }
@@ -0,0 +1,90 @@
+use crate::domain::site::{Site, SiteCoordinates, SiteCountdown, SiteCreate, SiteName};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use skju_core::Coord;
+use skju_core::warning::EarthquakeSource;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteModel {
+    pub id: i32,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SiteCreateRequest {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Located event, the epicenter shares the local km plane of the sensors.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WarningRequest {
+    pub x: f64,
+    pub y: f64,
+    /// Defaults to 10 km.
+    pub depth_km: Option<f64>,
+    pub origin_time: DateTime<Utc>,
+    pub magnitude: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteWarningModel {
+    pub site: SiteModel,
+    pub distance_km: f64,
+    pub p_arrival: DateTime<Utc>,
+    pub s_arrival: DateTime<Utc>,
+    pub intensity: f64,
+    pub warning_seconds: f64,
+}
+
+impl From<SiteCreateRequest> for SiteCreate {
+    fn from(request: SiteCreateRequest) -> Self {
+        SiteCreate {
+            name: SiteName::new(request.name),
+            coordinates: SiteCoordinates::new(request.x, request.y),
+        }
+    }
+}
+
+impl From<Site> for SiteModel {
+    fn from(site: Site) -> Self {
+        SiteModel {
+            id: site.id.value(),
+            name: site.name.value().to_string(),
+            x: site.coordinates.x(),
+            y: site.coordinates.y(),
+            created_at: site.created_at,
+        }
+    }
+}
+
+impl From<WarningRequest> for EarthquakeSource {
+    fn from(request: WarningRequest) -> Self {
+        EarthquakeSource {
+            epicenter: Coord { x: request.x as f32, y: request.y as f32 },
+            depth_km: request.depth_km.unwrap_or(10.0),
+            origin_time: request.origin_time.timestamp_millis().max(0) as u128,
+            magnitude: request.magnitude,
+        }
+    }
+}
+
+impl From<SiteCountdown> for SiteWarningModel {
+    fn from(countdown: SiteCountdown) -> Self {
+        let warning = countdown.warning;
+        let to_time = |timestamp: u128| DateTime::from_timestamp_millis(timestamp as i64).unwrap_or_default();
+
+        SiteWarningModel {
+            site: countdown.site.into(),
+            distance_km: warning.hypocentral_distance_km,
+            p_arrival: to_time(warning.p_arrival),
+            s_arrival: to_time(warning.s_arrival),
+            intensity: warning.intensity,
+            warning_seconds: warning.warning_seconds,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use super::dto::{SiteCreateRequest, SiteModel, SiteWarningModel, WarningRequest};
+use crate::domain::site::SiteID;
+use crate::{error::ApiError, state::AppState};
+use axum::extract::Path;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+
+pub async fn create_site(
+    State(state): State<AppState>,
+    Json(site): Json<SiteCreateRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = state.app_services.site_service.create(site.into()).await?;
+    let response = (StatusCode::CREATED, Json::<SiteModel>(result.into()));
+
+    Ok(response)
+}
+
+pub async fn delete_site(State(state): State<AppState>, Path(id): Path<i32>) -> Result<impl IntoResponse, ApiError> {
+    state
+        .app_services
+        .site_service
+        .delete(SiteID::new(id))
+        .await?;
+
+    let response = (StatusCode::NO_CONTENT, ());
+
+    Ok(response)
+}
+
+pub async fn get_all_sites(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let result = state.app_services.site_service.list().await?;
+    let sites = result
+        .into_iter()
+        .map(|site| site.into())
+        .collect::<Vec<SiteModel>>();
+    let response = (StatusCode::OK, Json(sites));
+
+    Ok(response)
+}
+
+pub async fn get_warnings(
+    State(state): State<AppState>,
+    Json(event): Json<WarningRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = state
+        .app_services
+        .site_service
+        .warnings(event.into(), Utc::now())
+        .await?;
+    let warnings = result
+        .into_iter()
+        .map(|countdown| countdown.into())
+        .collect::<Vec<SiteWarningModel>>();
+    let response = (StatusCode::OK, Json(warnings));
+
+    Ok(response)
+}
@@ -0,0 +1,6 @@
+mod dto;
+mod handlers;
+mod routes;
+
+pub use handlers::*;
+pub use routes::*;
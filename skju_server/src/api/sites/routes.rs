@@ -0,0 +1,11 @@
+use super::{create_site, delete_site, get_all_sites, get_warnings};
+use crate::state::AppState;
+use axum::Router;
+use axum::routing::{delete, get, post};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_all_sites).post(create_site))
+        .route("/{id}", delete(delete_site))
+        .route("/warnings", post(get_warnings))
+}
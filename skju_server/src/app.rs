@@ -7,10 +7,11 @@ use tokio::time;
 
 use crate::application::messages::{AppConsumer, AppMessage, ConsumerContext};
 use crate::application::readings::ReadingsBuffer;
-use crate::application::{readings, sensors};
+use crate::application::{readings, sensors, sites};
 use crate::infrastructure::app_bus::AppBus;
 use crate::infrastructure::pg_reading_repository::PgReadingRepository;
 use crate::infrastructure::pg_sensor_repository::PgSensorRepository;
+use crate::infrastructure::pg_site_repository::PgSiteRepository;
 use crate::ports::bus_service::BusMessage;
 use crate::routes::create_routes;
 use crate::state::{AppServices, AppState};
@@ -20,20 +21,29 @@ pub fn create_app(db_pool: Pool<Postgres>) -> Router<()> {
 
     let sensor_repository = PgSensorRepository::new(db_pool.clone());
     let readings_repository = PgReadingRepository::new(db_pool.clone());
+    let site_repository = PgSiteRepository::new(db_pool.clone());
     let sensor_repository = Arc::new(sensor_repository);
     let readings_repository = Arc::new(readings_repository);
+    let site_repository = Arc::new(site_repository);
 
     let bus_service = AppBus::new(tx);
     let bus_service = Arc::new(bus_service);
 
     let sensor_service = sensors::Service::new(sensor_repository.clone(), bus_service.clone());
-    let readings_service = readings::Service::new(readings_repository.clone(), bus_service.clone());
+    let readings_service = readings::Service::new(
+        readings_repository.clone(),
+        sensor_repository.clone(),
+        bus_service.clone(),
+    );
+    let site_service = sites::Service::new(site_repository);
     let sensor_service = Arc::new(sensor_service);
     let readings_service = Arc::new(readings_service);
+    let site_service = Arc::new(site_service);
 
     let app_services = AppServices {
         sensor_service: sensor_service.clone(),
         reading_service: readings_service.clone(),
+        site_service,
     };
 
     let readings_buffer = ReadingsBuffer::new(readings_repository, 1000);
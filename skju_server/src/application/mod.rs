@@ -1,3 +1,4 @@
 pub mod messages;
 pub mod readings;
 pub mod sensors;
+pub mod sites;
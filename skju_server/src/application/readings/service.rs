@@ -1,8 +1,14 @@
 use crate::domain::reading::{Reading, ReadingCreate, ReadingError, ReadingsRange};
 use async_trait::async_trait;
+use skju_core::mseed::Encoding;
+use tokio::sync::broadcast;
 
 #[async_trait]
 pub trait ReadingService: Send + Sync + 'static {
     async fn create(&self, req: ReadingCreate) -> Result<(), ReadingError>;
+    async fn create_many(&self, req: Vec<ReadingCreate>) -> Result<(), ReadingError>;
     async fn get_between(&self, req: ReadingsRange) -> Result<Vec<Reading>, ReadingError>;
+    async fn export_mseed(&self, req: ReadingsRange, encoding: Encoding, scale: f64) -> Result<Vec<u8>, ReadingError>;
+    /// Readings as they are received, before they are buffered into the database.
+    fn subscribe(&self) -> broadcast::Receiver<ReadingCreate>;
 }
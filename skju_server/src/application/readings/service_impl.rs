@@ -1,21 +1,46 @@
 use super::ReadingService;
 use crate::application::messages::AppMessage;
 use crate::domain::reading::{Reading, ReadingCreate, ReadingError, ReadingsRange};
+use crate::domain::sensor::{SensorError, SensorID};
 use crate::ports::bus_service::{BusMessage, BusService};
 use crate::ports::reading_repository::ReadingRepository;
+use crate::ports::sensors_repository::SensorRepository;
 use async_trait::async_trait;
+use skju_core::Channel;
+use skju_core::mseed::{self, Encoding, MseedEncoder, StreamId};
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing::instrument;
 
+/// FDSN network code of the exported streams.
+const MSEED_NETWORK: &str = "SK";
+/// Readings kept for live subscribers, slower ones skip the readings they missed.
+const LIVE_READINGS_CAPACITY: usize = 1000;
+/// Largest batch accepted at once.
+const MAX_BATCH_READINGS: usize = 10_000;
+
 #[derive(Clone)]
 pub struct Service {
     repository: Arc<dyn ReadingRepository>,
+    sensor_repository: Arc<dyn SensorRepository>,
     bus_service: Arc<dyn BusService<AppMessage>>,
+    live_readings: broadcast::Sender<ReadingCreate>,
 }
 
 impl Service {
-    pub fn new(repository: Arc<dyn ReadingRepository>, bus_service: Arc<dyn BusService<AppMessage>>) -> Self {
-        Self { repository, bus_service }
+    pub fn new(
+        repository: Arc<dyn ReadingRepository>,
+        sensor_repository: Arc<dyn SensorRepository>,
+        bus_service: Arc<dyn BusService<AppMessage>>,
+    ) -> Self {
+        let (live_readings, _) = broadcast::channel(LIVE_READINGS_CAPACITY);
+
+        Self {
+            repository,
+            sensor_repository,
+            bus_service,
+            live_readings,
+        }
     }
 }
 
@@ -23,6 +48,9 @@ impl Service {
 impl ReadingService for Service {
     #[instrument(name = "service.reading.create", skip(self))]
     async fn create(&self, request: ReadingCreate) -> Result<(), ReadingError> {
+        // sending only fails when nobody is subscribed
+        let _ = self.live_readings.send(request.clone());
+
         let message = BusMessage {
             message: AppMessage::SensorReadingReceived(request),
         };
@@ -35,8 +63,247 @@ impl ReadingService for Service {
         Ok(())
     }
 
+    /// Stores the whole batch before returning, unlike single readings that are buffered.
+    #[instrument(name = "service.reading.create_many", skip_all, fields(count = requests.len()))]
+    async fn create_many(&self, requests: Vec<ReadingCreate>) -> Result<(), ReadingError> {
+        if requests.len() > MAX_BATCH_READINGS {
+            return Err(ReadingError::InvalidBatch(format!(
+                "at most {MAX_BATCH_READINGS} readings are accepted at once"
+            )));
+        }
+
+        let mut sensor_ids: Vec<i32> = requests
+            .iter()
+            .map(|request| request.sensor_id.value())
+            .collect();
+        sensor_ids.sort_unstable();
+        sensor_ids.dedup();
+
+        for id in sensor_ids {
+            self.sensor_repository
+                .get_by_id(SensorID::new(id))
+                .await
+                .map_err(|error| match error {
+                    SensorError::NotFound(id) => ReadingError::InvalidBatch(format!("unknown sensor {id}")),
+                    SensorError::Database(error) => ReadingError::Database(error),
+                    error => ReadingError::Internal(error.to_string()),
+                })?;
+        }
+
+        self.repository.create(requests.clone()).await?;
+
+        for request in requests {
+            // sending only fails when nobody is subscribed
+            let _ = self.live_readings.send(request);
+        }
+
+        Ok(())
+    }
+
     #[instrument(name = "service.reading.get_between", skip(self))]
     async fn get_between(&self, request: ReadingsRange) -> Result<Vec<Reading>, ReadingError> {
         self.repository.get_between(request).await
     }
+
+    #[instrument(name = "service.reading.export_mseed", skip(self))]
+    async fn export_mseed(
+        &self,
+        request: ReadingsRange,
+        encoding: Encoding,
+        scale: f64,
+    ) -> Result<Vec<u8>, ReadingError> {
+        let sensor_id = request
+            .sensor_id()
+            .ok_or_else(|| ReadingError::InvalidRange("sensor is required for export".into()))?;
+        let readings = self.repository.get_between(request).await?;
+        let mut records = Vec::new();
+
+        for channel in Channel::ALL {
+            let series: Vec<(u128, f64)> = readings
+                .iter()
+                .filter(|reading| reading.channel.value() == channel)
+                .map(|reading| {
+                    let timestamp = reading.timestamp.value().timestamp_millis().max(0) as u128;
+                    (timestamp, reading.value.value() * scale)
+                })
+                .collect();
+
+            if series.is_empty() {
+                continue;
+            }
+
+            let to_export_error = |error: mseed::MseedError| ReadingError::Export(format!("{channel}: {error}"));
+            let sample_rate =
+                mseed::sample_rate(&series).ok_or(to_export_error(mseed::MseedError::UnknownSampleRate))?;
+            let stream = StreamId::for_sensor(MSEED_NETWORK, sensor_id.value() as u64, channel, sample_rate)
+                .map_err(to_export_error)?;
+            let mut encoder = MseedEncoder::new(stream, encoding);
+
+            records.extend(encoder.encode_series(&series).map_err(to_export_error)?);
+        }
+
+        Ok(records)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<ReadingCreate> {
+        self.live_readings.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::messages::AppMessage;
+    use crate::application::readings::{ReadingService, Service};
+    use crate::domain::reading::{
+        Reading, ReadingChannel, ReadingCreate, ReadingError, ReadingTimestamp, ReadingUnit, ReadingValue,
+        ReadingsRange,
+    };
+    use crate::domain::sensor::{DBSensor, Sensor, SensorCreate, SensorError, SensorID, SensorUpdate};
+    use crate::ports::bus_service::{BusError, BusMessage, BusService};
+    use crate::ports::reading_repository::ReadingRepository;
+    use crate::ports::sensors_repository::SensorRepository;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use skju_core::Channel;
+    use skju_core::response::Unit;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct MockReadings {
+        stored: Mutex<Vec<ReadingCreate>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ReadingRepository for MockReadings {
+        async fn create(&self, request: Vec<ReadingCreate>) -> Result<(), ReadingError> {
+            if self.fail {
+                return Err(ReadingError::Database("connection closed".into()));
+            }
+
+            self.stored.lock().unwrap().extend(request);
+            Ok(())
+        }
+
+        async fn get_between(&self, _: ReadingsRange) -> Result<Vec<Reading>, ReadingError> {
+            Ok(Vec::new())
+        }
+    }
+
+    struct MockSensors(Vec<i32>);
+
+    #[async_trait]
+    impl SensorRepository for MockSensors {
+        async fn create(&self, _: SensorCreate) -> Result<Sensor, SensorError> {
+            Err(SensorError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn update(&self, _: SensorID, _: SensorUpdate) -> Result<Sensor, SensorError> {
+            Err(SensorError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn delete(&self, _: SensorID) -> Result<(), SensorError> {
+            Err(SensorError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn list(&self) -> Result<Vec<Sensor>, SensorError> {
+            Err(SensorError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn get_by_id(&self, id: SensorID) -> Result<Sensor, SensorError> {
+            if !self.0.contains(&id.value()) {
+                return Err(SensorError::NotFound(id));
+            }
+
+            Ok(Sensor::from(DBSensor {
+                id: id.value(),
+                name: format!("sensor {id}"),
+                description: None,
+                x: 0.0,
+                y: 0.0,
+                created_at: Utc::now(),
+            }))
+        }
+
+        async fn delete_all(&self) -> Result<(), SensorError> {
+            Err(SensorError::Database(String::from("not supported by the mock")))
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBus(AtomicUsize);
+
+    #[async_trait]
+    impl BusService<AppMessage> for MockBus {
+        async fn send(&self, _: BusMessage<AppMessage>) -> Result<(), BusError<AppMessage>> {
+            self.0.fetch_add(1, Relaxed);
+            Ok(())
+        }
+    }
+
+    fn reading(sensor_id: i32, value: f64) -> ReadingCreate {
+        ReadingCreate {
+            sensor_id: SensorID::new(sensor_id),
+            channel: ReadingChannel::new(Channel::Z),
+            unit: ReadingUnit::new(Unit::Counts),
+            value: ReadingValue::new(value),
+            timestamp: ReadingTimestamp::new(Utc::now()),
+        }
+    }
+
+    fn service(readings: &Arc<MockReadings>, bus: &Arc<MockBus>) -> Service {
+        Service::new(readings.clone(), Arc::new(MockSensors(vec![1, 2])), bus.clone())
+    }
+
+    #[tokio::test]
+    async fn stores_batches_before_returning() {
+        let readings = Arc::new(MockReadings::default());
+        let bus = Arc::new(MockBus::default());
+        let service = service(&readings, &bus);
+        let mut live = service.subscribe();
+
+        service
+            .create_many(vec![reading(1, 1.0), reading(2, 2.0), reading(1, 3.0)])
+            .await
+            .unwrap();
+
+        let stored: Vec<f64> = readings
+            .stored
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.value.value())
+            .collect();
+
+        assert_eq!(stored, [1.0, 2.0, 3.0]);
+        assert_eq!(bus.0.load(Relaxed), 0);
+        assert_eq!(live.try_recv().unwrap().value.value(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn rejects_batches_of_unknown_sensors() {
+        let readings = Arc::new(MockReadings::default());
+        let bus = Arc::new(MockBus::default());
+        let service = service(&readings, &bus);
+        let result = service
+            .create_many(vec![reading(1, 1.0), reading(3, 2.0)])
+            .await;
+
+        assert!(matches!(result, Err(ReadingError::InvalidBatch(_))));
+        assert!(readings.stored.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_failed_inserts() {
+        let readings = Arc::new(MockReadings { fail: true, ..MockReadings::default() });
+        let bus = Arc::new(MockBus::default());
+        let service = service(&readings, &bus);
+        let mut live = service.subscribe();
+        let result = service.create_many(vec![reading(1, 1.0)]).await;
+
+        assert!(matches!(result, Err(ReadingError::Database(_))));
+        assert!(live.try_recv().is_err());
+    }
 }
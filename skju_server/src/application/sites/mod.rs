@@ -0,0 +1,5 @@
+mod service;
+mod service_impl;
+
+pub use service::*;
+pub use service_impl::*;
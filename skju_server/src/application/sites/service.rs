@@ -0,0 +1,13 @@
+use crate::domain::site::{Site, SiteCountdown, SiteCreate, SiteError, SiteID};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use skju_core::warning::EarthquakeSource;
+
+#[async_trait]
+pub trait SiteService: Send + Sync + 'static {
+    async fn create(&self, req: SiteCreate) -> Result<Site, SiteError>;
+    async fn delete(&self, id: SiteID) -> Result<(), SiteError>;
+    async fn list(&self) -> Result<Vec<Site>, SiteError>;
+    /// Countdowns of every registered site, the first S wave arrival first.
+    async fn warnings(&self, source: EarthquakeSource, now: DateTime<Utc>) -> Result<Vec<SiteCountdown>, SiteError>;
+}
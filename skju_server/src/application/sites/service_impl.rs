@@ -0,0 +1,162 @@
+use super::SiteService;
+use crate::domain::site::{Site, SiteCountdown, SiteCreate, SiteError, SiteID};
+use crate::ports::sites_repository::SiteRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use skju_core::warning::EarthquakeSource;
+use std::sync::Arc;
+use tracing::instrument;
+
+#[derive(Clone)]
+pub struct Service {
+    repository: Arc<dyn SiteRepository>,
+}
+
+impl Service {
+    pub fn new(repository: Arc<dyn SiteRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl SiteService for Service {
+    #[instrument(name = "service.site.create", skip(self), err)]
+    async fn create(&self, request: SiteCreate) -> Result<Site, SiteError> {
+        self.repository.create(request).await
+    }
+
+    #[instrument(name = "service.site.delete", skip(self), err)]
+    async fn delete(&self, id: SiteID) -> Result<(), SiteError> {
+        self.repository.delete(id).await
+    }
+
+    #[instrument(name = "service.site.list", skip(self), err)]
+    async fn list(&self) -> Result<Vec<Site>, SiteError> {
+        self.repository.list().await
+    }
+
+    #[instrument(name = "service.site.warnings", skip(self), err)]
+    async fn warnings(&self, source: EarthquakeSource, now: DateTime<Utc>) -> Result<Vec<SiteCountdown>, SiteError> {
+        if !source.magnitude.is_finite() || !source.depth_km.is_finite() || source.depth_km < 0.0 {
+            return Err(SiteError::Validation(String::from("invalid magnitude or depth")));
+        }
+
+        let mut countdowns: Vec<SiteCountdown> = self
+            .repository
+            .list()
+            .await?
+            .into_iter()
+            .map(|site| site.countdown(&source, now))
+            .collect();
+
+        countdowns.sort_by_key(|countdown| countdown.warning.s_arrival);
+
+        Ok(countdowns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::sites::{Service, SiteService};
+    use crate::domain::site::{DBSite, Site, SiteCreate, SiteError, SiteID};
+    use crate::ports::sites_repository::SiteRepository;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use skju_core::Coord;
+    use skju_core::warning::EarthquakeSource;
+    use std::sync::Arc;
+
+    /// Sites at the given x coordinates, numbered from 1.
+    struct MockSites(Vec<f64>);
+
+    #[async_trait]
+    impl SiteRepository for MockSites {
+        async fn create(&self, _: SiteCreate) -> Result<Site, SiteError> {
+            Err(SiteError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn delete(&self, _: SiteID) -> Result<(), SiteError> {
+            Err(SiteError::Database(String::from("not supported by the mock")))
+        }
+
+        async fn list(&self) -> Result<Vec<Site>, SiteError> {
+            let sites = self.0.iter().enumerate().map(|(index, x)| {
+                Site::from(DBSite {
+                    id: index as i32 + 1,
+                    name: format!("site {}", index + 1),
+                    x: *x,
+                    y: 0.0,
+                    created_at: DateTime::UNIX_EPOCH,
+                })
+            });
+
+            Ok(sites.collect())
+        }
+    }
+
+    fn source(magnitude: f64, depth_km: f64) -> EarthquakeSource {
+        EarthquakeSource {
+            epicenter: Coord { x: 0.0, y: 0.0 },
+            depth_km,
+            origin_time: 10_000,
+            magnitude,
+        }
+    }
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(millis).unwrap()
+    }
+
+    #[tokio::test]
+    async fn warnings_start_with_the_first_s_arrival() {
+        let service = Service::new(Arc::new(MockSites(vec![100.0, 0.0, 50.0])));
+        let warnings = service
+            .warnings(source(6.0, 10.0), at(10_000))
+            .await
+            .unwrap();
+        let ids: Vec<i32> = warnings
+            .iter()
+            .map(|countdown| countdown.site.id.value())
+            .collect();
+
+        assert_eq!(ids, [2, 3, 1]);
+        assert!(
+            warnings
+                .windows(2)
+                .all(|pair| pair[0].warning.s_arrival <= pair[1].warning.s_arrival)
+        );
+        assert!(warnings[0].warning.intensity > warnings[2].warning.intensity);
+        assert_eq!(
+            warnings[2].warning.warning_seconds,
+            (warnings[2].warning.s_arrival - 10_000) as f64 / 1000.0
+        );
+    }
+
+    #[tokio::test]
+    async fn countdown_ends_at_the_s_arrival() {
+        let service = Service::new(Arc::new(MockSites(vec![0.0, 100.0])));
+        let first = service
+            .warnings(source(6.0, 10.0), at(10_000))
+            .await
+            .unwrap();
+        let near_arrival = first[0].warning.s_arrival as i64;
+        let warnings = service
+            .warnings(source(6.0, 10.0), at(near_arrival + 1))
+            .await
+            .unwrap();
+
+        assert_eq!(warnings[0].warning.warning_seconds, 0.0);
+        assert!(warnings[1].warning.warning_seconds > 0.0);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_sources() {
+        let service = Service::new(Arc::new(MockSites(vec![0.0])));
+
+        for source in [source(f64::NAN, 10.0), source(6.0, -1.0), source(6.0, f64::INFINITY)] {
+            let result = service.warnings(source, at(10_000)).await;
+
+            assert!(matches!(result, Err(SiteError::Validation(_))));
+        }
+    }
+}
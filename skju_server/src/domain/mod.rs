@@ -1,3 +1,4 @@
 mod point;
 pub mod reading;
 pub mod sensor;
+pub mod site;
@@ -1,5 +1,7 @@
 use super::sensor::SensorID;
 use chrono::{DateTime, Utc};
+use skju_core::Channel;
+use skju_core::response::Unit;
 use sqlx::FromRow;
 use std::fmt;
 
@@ -12,10 +14,18 @@ pub struct ReadingValue(f64);
 #[derive(Debug, Clone, Copy)]
 pub struct ReadingTimestamp(DateTime<Utc>);
 
+#[derive(Debug, Clone, Copy)]
+pub struct ReadingChannel(Channel);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReadingUnit(Unit);
+
 #[derive(Debug, FromRow)]
 pub struct DBReading {
     pub id: i64,
     pub sensor_id: i32,
+    pub channel: String,
+    pub unit: String,
     pub value: f64,
     pub timestamp: DateTime<Utc>,
 }
@@ -23,13 +33,17 @@ pub struct DBReading {
 pub struct Reading {
     pub id: ReadingID,
     pub sensor_id: SensorID,
+    pub channel: ReadingChannel,
+    pub unit: ReadingUnit,
     pub value: ReadingValue,
     pub timestamp: ReadingTimestamp,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ReadingCreate {
     pub sensor_id: SensorID,
+    pub channel: ReadingChannel,
+    pub unit: ReadingUnit,
     pub value: ReadingValue,
     pub timestamp: ReadingTimestamp,
 }
@@ -44,6 +58,8 @@ pub struct ReadingsRange {
 #[derive(Debug)]
 pub enum ReadingError {
     InvalidRange(String),
+    InvalidBatch(String),
+    Export(String),
     Database(String),
     Internal(String),
 }
@@ -54,6 +70,8 @@ impl fmt::Display for ReadingError {
             ReadingError::Database(e) => write!(formatter, "Database error: {}", e),
             ReadingError::Internal(e) => write!(formatter, "Internal error: {}", e),
             ReadingError::InvalidRange(e) => write!(formatter, "Invalid range: {}", e),
+            ReadingError::InvalidBatch(e) => write!(formatter, "Invalid batch: {}", e),
+            ReadingError::Export(e) => write!(formatter, "Export error: {}", e),
         }
     }
 }
@@ -63,6 +81,10 @@ impl From<DBReading> for Reading {
         Reading {
             id: ReadingID::new(db_reading.id),
             sensor_id: SensorID::new(db_reading.sensor_id),
+            // The column is constrained to E, N and Z.
+            channel: ReadingChannel::new(db_reading.channel.parse().unwrap_or(Channel::Z)),
+            // The column is constrained to the known units.
+            unit: ReadingUnit::new(db_reading.unit.parse().unwrap_or_default()),
             value: ReadingValue::new(db_reading.value),
             timestamp: ReadingTimestamp::new(db_reading.timestamp),
         }
@@ -74,6 +96,8 @@ impl From<Reading> for DBReading {
         DBReading {
             id: reading.id.value(),
             sensor_id: reading.sensor_id.value(),
+            channel: reading.channel.to_string(),
+            unit: reading.unit.to_string(),
             value: reading.value.value(),
             timestamp: reading.timestamp.value(),
         }
@@ -137,3 +161,35 @@ impl ReadingTimestamp {
         self.0
     }
 }
+
+impl ReadingChannel {
+    pub fn new(channel: Channel) -> Self {
+        ReadingChannel(channel)
+    }
+
+    pub fn value(&self) -> Channel {
+        self.0
+    }
+}
+
+impl fmt::Display for ReadingChannel {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl ReadingUnit {
+    pub fn new(unit: Unit) -> Self {
+        ReadingUnit(unit)
+    }
+
+    pub fn value(&self) -> Unit {
+        self.0
+    }
+}
+
+impl fmt::Display for ReadingUnit {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
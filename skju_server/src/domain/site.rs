@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use skju_core::Coord;
+use skju_core::warning::{EarthquakeSource, SiteWarning};
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct SiteID(i32);
+
+impl fmt::Display for SiteID {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct SiteName(String);
+
+#[derive(Debug, Clone, Copy)]
+pub struct SiteCoordinates {
+    x: f64,
+    y: f64,
+}
+
+/// Location registered for early warnings, coordinates share the local km plane of the sensors.
+#[derive(Debug)]
+pub struct Site {
+    pub id: SiteID,
+    pub name: SiteName,
+    pub coordinates: SiteCoordinates,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DBSite {
+    pub id: i32,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct SiteCreate {
+    pub name: SiteName,
+    pub coordinates: SiteCoordinates,
+}
+
+#[derive(Debug)]
+pub struct SiteCountdown {
+    pub site: Site,
+    pub warning: SiteWarning,
+}
+
+#[derive(Debug)]
+pub enum SiteError {
+    NotFound(SiteID),
+    Database(String),
+    Validation(String),
+}
+
+impl fmt::Display for SiteError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SiteError::NotFound(id) => write!(formatter, "Site {id} not found"),
+            SiteError::Database(e) => write!(formatter, "Database error: {e}"),
+            SiteError::Validation(e) => write!(formatter, "Validation error: {e}"),
+        }
+    }
+}
+
+impl From<DBSite> for Site {
+    fn from(db_site: DBSite) -> Self {
+        Site {
+            id: SiteID::new(db_site.id),
+            name: SiteName::new(db_site.name),
+            coordinates: SiteCoordinates::new(db_site.x, db_site.y),
+            created_at: db_site.created_at,
+        }
+    }
+}
+
+impl Site {
+    pub fn countdown(self, source: &EarthquakeSource, now: DateTime<Utc>) -> SiteCountdown {
+        let now = now.timestamp_millis().max(0) as u128;
+        let warning = source.warning(self.coordinates.coord(), now);
+
+        SiteCountdown { site: self, warning }
+    }
+}
+
+impl SiteID {
+    pub fn new(id: i32) -> Self {
+        SiteID(id)
+    }
+
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+}
+
+impl SiteName {
+    pub fn new(name: String) -> Self {
+        SiteName(name)
+    }
+
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl SiteCoordinates {
+    pub fn new(x: f64, y: f64) -> Self {
+        SiteCoordinates { x, y }
+    }
+
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn coord(&self) -> Coord {
+        Coord { x: self.x as f32, y: self.y as f32 }
+    }
+}
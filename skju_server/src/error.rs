@@ -1,5 +1,6 @@
 use crate::domain::reading::ReadingError;
 use crate::domain::sensor::{SensorError, SensorID};
+use crate::domain::site::{SiteError, SiteID};
 use axum::{
     Json,
     http::StatusCode,
@@ -11,6 +12,7 @@ use serde_json::json;
 pub enum ApiError {
     Internal,
     SensorNotFound(SensorID),
+    SiteNotFound(SiteID),
     BadRequest(String),
 }
 
@@ -19,6 +21,7 @@ impl IntoResponse for ApiError {
         let (status, message) = match self {
             ApiError::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error".to_string()),
             ApiError::SensorNotFound(id) => (StatusCode::NOT_FOUND, format!("sensor {id} is not found")),
+            ApiError::SiteNotFound(id) => (StatusCode::NOT_FOUND, format!("site {id} is not found")),
             ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
         };
         let body = (status, Json(json!({ "error": message })));
@@ -44,6 +47,18 @@ impl From<ReadingError> for ApiError {
             ReadingError::Database(_) => ApiError::Internal,
             ReadingError::Internal(_) => ApiError::Internal,
             ReadingError::InvalidRange(_) => ApiError::BadRequest("Invalid range".to_string()),
+            ReadingError::InvalidBatch(message) => ApiError::BadRequest(message),
+            ReadingError::Export(message) => ApiError::BadRequest(message),
+        }
+    }
+}
+
+impl From<SiteError> for ApiError {
+    fn from(error: SiteError) -> Self {
+        match error {
+            SiteError::NotFound(id) => ApiError::SiteNotFound(id),
+            SiteError::Validation(message) => ApiError::BadRequest(message),
+            SiteError::Database(_) => ApiError::Internal,
         }
     }
 }
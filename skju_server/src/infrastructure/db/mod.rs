@@ -1,2 +1,3 @@
 pub mod pg_reading_repository;
 pub mod pg_sensor_repository;
+pub mod pg_site_repository;
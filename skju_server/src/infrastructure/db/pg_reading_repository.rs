@@ -25,10 +25,12 @@ impl From<sqlx::Error> for ReadingError {
 impl ReadingRepository for PgReadingRepository {
     #[instrument(name = "repo.reading.create", skip(self))]
     async fn create(&self, request: Vec<ReadingCreate>) -> Result<(), ReadingError> {
-        let mut query_builder = QueryBuilder::new(r#"INSERT INTO readings (sensor_id, value, timestamp)"#);
+        let mut query_builder = QueryBuilder::new(r#"INSERT INTO readings (sensor_id, channel, unit, value, timestamp)"#);
         let bind_values = |mut builder: Separated<Postgres, &str>, reading: ReadingCreate| {
             builder
                 .push_bind(reading.sensor_id.value())
+                .push_bind(reading.channel.to_string())
+                .push_bind(reading.unit.to_string())
                 .push_bind(reading.value.value())
                 .push_bind(reading.timestamp.value());
         };
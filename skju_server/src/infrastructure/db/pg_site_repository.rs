@@ -0,0 +1,62 @@
+use crate::domain::site::{DBSite, Site, SiteCreate, SiteError, SiteID};
+use crate::ports::sites_repository::SiteRepository;
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use sqlx::PgPool;
+use tracing::instrument;
+
+#[derive(Debug)]
+pub struct PgSiteRepository {
+    pool: PgPool,
+}
+
+impl PgSiteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl From<sqlx::Error> for SiteError {
+    fn from(err: sqlx::Error) -> Self {
+        SiteError::Database(err.to_string())
+    }
+}
+
+#[async_trait]
+impl SiteRepository for PgSiteRepository {
+    #[instrument(name = "repo.site.create", skip(self))]
+    async fn create(&self, request: SiteCreate) -> Result<Site, SiteError> {
+        let site: DBSite = sqlx::query_as(r#"INSERT INTO sites (name, x, y) VALUES ($1, $2, $3) RETURNING *"#)
+            .bind(request.name)
+            .bind(request.coordinates.x())
+            .bind(request.coordinates.y())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(site.into())
+    }
+
+    #[instrument(name = "repo.site.delete", skip(self))]
+    async fn delete(&self, id: SiteID) -> Result<(), SiteError> {
+        let result = sqlx::query(r#"DELETE FROM sites WHERE id = $1"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() != 0 {
+            Ok(())
+        } else {
+            Err(SiteError::NotFound(id))
+        }
+    }
+
+    #[instrument(name = "repo.site.list", skip(self))]
+    async fn list(&self) -> Result<Vec<Site>, SiteError> {
+        sqlx::query_as(r#"SELECT * FROM sites ORDER BY id"#)
+            .fetch(&self.pool)
+            .map(|r: Result<DBSite, _>| r.map(Site::from))
+            .try_collect()
+            .await
+            .map_err(SiteError::from)
+    }
+}
@@ -1,3 +1,4 @@
 pub mod bus_service;
 pub mod reading_repository;
 pub mod sensors_repository;
+pub mod sites_repository;
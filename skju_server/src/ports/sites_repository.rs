@@ -0,0 +1,9 @@
+use crate::domain::site::{Site, SiteCreate, SiteError, SiteID};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait SiteRepository: Send + Sync + 'static {
+    async fn create(&self, request: SiteCreate) -> Result<Site, SiteError>;
+    async fn delete(&self, id: SiteID) -> Result<(), SiteError>;
+    async fn list(&self) -> Result<Vec<Site>, SiteError>;
+}
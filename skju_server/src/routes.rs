@@ -1,4 +1,4 @@
-use crate::api::{readings, sensors};
+use crate::api::{readings, sensors, sites};
 use crate::state::AppState;
 use axum::http::{StatusCode, Uri};
 use axum::response::IntoResponse;
@@ -12,6 +12,7 @@ pub fn create_routes() -> Router<AppState> {
         .layer(TraceLayer::new_for_http())
         .nest("/sensors", sensors::routes())
         .nest("/readings", readings::routes())
+        .nest("/sites", sites::routes())
         .fallback(get(fallback))
 }
 
@@ -1,11 +1,13 @@
 use crate::application::readings::ReadingService;
 use crate::application::sensors::SensorService;
+use crate::application::sites::SiteService;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppServices {
     pub sensor_service: Arc<dyn SensorService>,
     pub reading_service: Arc<dyn ReadingService>,
+    pub site_service: Arc<dyn SiteService>,
 }
 
 #[derive(Clone)]
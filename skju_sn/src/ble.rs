@@ -1,7 +1,7 @@
 use nrf_softdevice::Softdevice;
 use nrf_softdevice::ble::advertisement_builder::{AdvertisementBuilder, AdvertisementPayload, Flag};
 
-use crate::constants::{MAX_SAMPLE_COUNT, SAMPLE_SIZE};
+use crate::constants::PACKET_SIZE;
 
 pub static ADV_DATA: AdvertisementPayload<32> = AdvertisementBuilder::new()
     .flags(&[Flag::GeneralDiscovery, Flag::LE_Only])
@@ -18,7 +18,7 @@ pub async fn softdevice_task(sd: &'static Softdevice) -> ! {
 #[nrf_softdevice::gatt_service(uuid = "8700bc0e-1510-4ece-851e-56940ff8757a")]
 pub struct ReadingsService {
     #[characteristic(uuid = "937eb842-06ce-49b6-a840-3e6c09151ce4", notify)]
-    pub readings: [u8; MAX_SAMPLE_COUNT * SAMPLE_SIZE],
+    pub readings: [u8; PACKET_SIZE],
     #[characteristic(uuid = "7312f746-8e77-4e05-8841-f81dfa95580b", read)]
     pub config: u8,
 }
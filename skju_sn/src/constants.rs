@@ -1,3 +1,7 @@
+use skju_codec::HEADER_SIZE;
+
+pub const NODE_ID: u16 = 1;
 pub const MAX_SAMPLE_COUNT: usize = 10;
 pub const SAMPLE_SIZE: usize = 12;
 pub const SAMPLE_RATE_HZ: u32 = 100;
+pub const PACKET_SIZE: usize = HEADER_SIZE + MAX_SAMPLE_COUNT * SAMPLE_SIZE;
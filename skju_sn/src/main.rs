@@ -18,23 +18,24 @@ use embassy_nrf::{bind_interrupts, peripherals, spim};
 use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, NoopRawMutex};
 use embassy_sync::channel::Channel;
 use embassy_sync::mutex::Mutex;
-use embassy_time::Timer;
+use embassy_time::{Instant, Timer};
 use futures::future::{Either, select};
 use futures::pin_mut;
 use mpu6500::MPU6500;
 use mpu6500::accel::AccelConfig;
 use mpu6500::config::{ConfigDLPFOptions, MPU6500Config};
-use mpu6500::fifo::{FIFOConfig, FIFOMode, FIFOSensors};
+use mpu6500::fifo::{FIFOConfig, FIFOEntryType, FIFOMode, FIFOSensors};
 use mpu6500::gyro::GyroConfig;
 use mpu6500::interrupts::{INTConfig, INTEnableFlags, INTFlags, InterruptStatus};
 use mpu6500::registers::WHO_AM_I;
 use mpu6500::user_control::UserControlConfig;
 use nrf_softdevice::Softdevice;
 use nrf_softdevice::ble::{Connection, gatt_server, peripheral};
+use skju_codec::{BatchHeader, decode_batch, encode_batch};
 use {defmt_rtt as _, panic_probe as _};
 
 use crate::ble::{ADV_DATA, ReadingsServer, ReadingsServerEvent, ReadingsServiceEvent, SCAN_DATA, softdevice_task};
-use crate::constants::{MAX_SAMPLE_COUNT, SAMPLE_RATE_HZ, SAMPLE_SIZE};
+use crate::constants::{MAX_SAMPLE_COUNT, NODE_ID, PACKET_SIZE, SAMPLE_RATE_HZ, SAMPLE_SIZE};
 use crate::spi::SpiDeviceBus;
 use crate::timer::TimerHandler;
 
@@ -105,6 +106,8 @@ async fn main(spawner: Spawner) {
 async fn handle_mpu_interrupts(mut mpu6500: MPU6500<SpiDeviceBus, TimerHandler>, mut int_pin: Input<'static>) {
     let who = mpu6500.read_register(WHO_AM_I).await;
     let fifo_layout = mpu6500.fifo_layout().await;
+    let batch_duration_us = (MAX_SAMPLE_COUNT as u64 - 1) * 1_000_000 / SAMPLE_RATE_HZ as u64;
+    let mut sequence: u32 = 0;
 
     defmt::info!("WHOAMI  {:08b}", who);
 
@@ -130,9 +133,23 @@ async fn handle_mpu_interrupts(mut mpu6500: MPU6500<SpiDeviceBus, TimerHandler>,
         }
 
         mpu6500.drain_fifo(&mut readings).await;
-        print_readings(&readings);
 
-        let _ = READINGS_CHANNEL.sender().try_send(Readings { batch_size, readings });
+        let timestamp_us = Instant::now().as_micros().saturating_sub(batch_duration_us);
+        let header = BatchHeader::new(&fifo_layout, NODE_ID, sequence, SAMPLE_RATE_HZ as u16, timestamp_us);
+        let mut packet = [0x00; PACKET_SIZE];
+
+        let packet_size = match encode_batch(&header, &readings, &mut packet) {
+            Ok(size) => size,
+            Err(e) => {
+                defmt::error!("Unable to encode readings: {}", defmt::Display2Format(&e));
+                continue;
+            }
+        };
+
+        sequence = sequence.wrapping_add(1);
+        print_readings(&packet[..packet_size]);
+
+        let _ = READINGS_CHANNEL.sender().try_send(Readings { packet_size, packet });
     }
 }
 
@@ -176,40 +193,48 @@ async fn process_readings(connection: &Connection, server: &ReadingsServer) {
     loop {
         let batch = READINGS_CHANNEL.receiver().receive().await;
 
-        defmt::info!("Readings: {=[u8]:x}", &batch.readings[..batch.batch_size]);
+        defmt::info!("Readings: {=[u8]:x}", &batch.packet[..batch.packet_size]);
 
         if !NOTIFY_ENABLED.load(Ordering::Acquire) {
             Timer::after_millis(1000).await;
             continue;
         }
 
-        match server.readings.readings_notify(connection, &batch.readings) {
+        match server.readings.readings_notify(connection, &batch.packet) {
             Ok(_) => {}
             Err(_) => {
-                let _ = server.readings.readings_set(&batch.readings);
+                let _ = server.readings.readings_set(&batch.packet);
             }
         }
     }
 }
 
 struct Readings {
-    batch_size: usize,
-    readings: [u8; MAX_SAMPLE_COUNT * SAMPLE_SIZE],
+    packet_size: usize,
+    packet: [u8; PACKET_SIZE],
 }
 
-pub fn print_readings(readings: &[u8]) {
-    let samples = readings.len() / SAMPLE_SIZE;
+pub fn print_readings(packet: &[u8]) {
+    let batch = match decode_batch(packet) {
+        Ok(batch) => batch,
+        Err(e) => {
+            defmt::error!("Unable to decode readings: {}", defmt::Display2Format(&e));
+            return;
+        }
+    };
+
+    defmt::info!("Batch #{} at {}us", batch.header.sequence, batch.header.timestamp_us);
 
-    for i in 0..samples {
-        let offset = i * SAMPLE_SIZE;
+    for (i, sample) in batch.samples().enumerate() {
+        let value = |entry_type| sample.get_value(entry_type).unwrap_or_default();
 
-        let ax = i16::from_be_bytes([readings[offset + 0], readings[offset + 1]]);
-        let ay = i16::from_be_bytes([readings[offset + 2], readings[offset + 3]]);
-        let az = i16::from_be_bytes([readings[offset + 4], readings[offset + 5]]);
+        let ax = value(FIFOEntryType::AccelX);
+        let ay = value(FIFOEntryType::AccelY);
+        let az = value(FIFOEntryType::AccelZ);
 
-        let gx = i16::from_be_bytes([readings[offset + 6], readings[offset + 7]]);
-        let gy = i16::from_be_bytes([readings[offset + 8], readings[offset + 9]]);
-        let gz = i16::from_be_bytes([readings[offset + 10], readings[offset + 11]]);
+        let gx = value(FIFOEntryType::GyroX);
+        let gy = value(FIFOEntryType::GyroY);
+        let gz = value(FIFOEntryType::GyroZ);
 
         defmt::info!("S{} ACC[x:{} y:{} z:{}] GYR[x:{} y:{} z:{}]", i, ax, ay, az, gx, gy, gz);
     }